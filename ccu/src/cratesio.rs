@@ -1,18 +1,62 @@
-use check_updates_core::{PackageInfo, Version};
-use anyhow::{anyhow, Context, Result};
+use check_updates_core::cache::HttpCache;
+use check_updates_core::date::Timestamp;
+use check_updates_core::http::{Auth, HttpClient, HttpResponse};
+use check_updates_core::registry::{NotFound, RegistryClient, RegistryError};
+use check_updates_core::{Dependency, PackageInfo, Version};
+use crate::registries::{CargoConfig, RegistrySource};
+use crate::sparse::{self, IndexConfig};
+use anyhow::{anyhow, bail, Context, Result};
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::sync::Arc;
-use tokio::sync::Semaphore;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
 
-/// Client for querying crates.io API
+/// Where a crates.io-compatible server serves crate metadata
+const API_PATH: &str = "/api/v1/crates";
+
+/// Client for querying crates.io's sparse index, or the registries a
+/// project's Cargo config defines
 pub struct CratesIoClient {
-    client: reqwest::Client,
-    base_url: String,
+    http: HttpClient,
+    cargo_config: Arc<CargoConfig>,
+    /// Registries looked up so far, by name (`None` for crates.io)
+    registries: Arc<Mutex<HashMap<Option<String>, Arc<Registry>>>>,
     include_prerelease: bool,
 }
 
+/// A registry and, once asked, how to read it
+#[derive(Debug)]
+struct Registry {
+    /// How the registry is named in messages
+    name: String,
+    source: Source,
+}
+
+#[derive(Debug)]
+enum Source {
+    Remote {
+        /// Sparse index URL, or the base of a crates.io-compatible web API
+        url: String,
+        /// Sent only if the index requires authentication
+        auth: Option<Auth>,
+        /// The index's `config.json`; `None` if it has none, in which case
+        /// `url` is taken to be a web API
+        config: OnceCell<Option<IndexConfig>>,
+    },
+    Git(String),
+    /// A `local-registry` directory
+    Local(PathBuf),
+    /// A directory of unpacked crates, scanned on first use
+    Directory {
+        path: PathBuf,
+        crates: OnceCell<HashMap<String, Vec<CrateVersion>>>,
+    },
+}
+
 /// crates.io API response for a single crate
 #[derive(Debug, Deserialize)]
 struct CrateResponse {
@@ -26,60 +70,284 @@ struct CrateInfo {
     name: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 struct CrateVersion {
     num: String,
     yanked: bool,
+    created_at: Option<String>,
+    rust_version: Option<String>,
+}
+
+/// The name `CratesIoClient` looks a dependency up by: the crate's real
+/// name (not a `package = "..."` alias), prefixed with `registry/` for one
+/// from an alternate registry
+pub fn lookup_name(dependency: &Dependency) -> String {
+    let name = dependency.package_name();
+    match &dependency.registry {
+        Some(registry) => format!("{registry}/{name}"),
+        None => name.to_string(),
+    }
+}
+
+impl Registry {
+    fn remote(name: &str, url: &str, token: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            source: Source::Remote {
+                url: url.strip_prefix("sparse+").unwrap_or(url).trim_end_matches('/').to_string(),
+                // Cargo sends registry tokens as is, without a scheme
+                auth: token.map(Auth::Header),
+                config: OnceCell::new(),
+            },
+        }
+    }
+}
+
+impl From<RegistrySource> for Registry {
+    fn from(source: RegistrySource) -> Self {
+        match source {
+            RegistrySource::Sparse { name, url, token } => Self::remote(&name, &url, token),
+            RegistrySource::Git { name, url } => Self {
+                name,
+                source: Source::Git(url),
+            },
+            RegistrySource::Local { name, path } => Self {
+                name,
+                source: Source::Local(path),
+            },
+            RegistrySource::Directory { name, path } => Self {
+                name,
+                source: Source::Directory {
+                    path,
+                    crates: OnceCell::new(),
+                },
+            },
+        }
+    }
 }
 
 impl CratesIoClient {
     pub fn new(include_prerelease: bool) -> Self {
         Self {
-            client: reqwest::Client::builder()
-                // crates.io requires a user-agent with contact info
-                .user_agent("cargo-check-updates/0.1.0 (https://github.com/folknor/cargo-check-updates)")
-                .timeout(std::time::Duration::from_secs(30))
-                .build()
-                .unwrap_or_else(|_| reqwest::Client::new()),
-            base_url: "https://crates.io/api/v1/crates".to_string(),
+            http: HttpClient::new(
+                reqwest::Client::builder()
+                    // crates.io requires a user-agent with contact info
+                    .user_agent("cargo-check-updates/0.1.0 (https://github.com/folknor/cargo-check-updates)")
+                    .timeout(std::time::Duration::from_secs(30))
+                    .build()
+                    .unwrap_or_else(|_| reqwest::Client::new()),
+            ),
+            cargo_config: Arc::default(),
+            registries: Arc::default(),
             include_prerelease,
         }
     }
 
-    /// Fetch package info from crates.io
-    pub async fn get_package(&self, name: &str) -> Result<PackageInfo> {
-        let url = format!("{}/{}", self.base_url, name);
+    /// Query a mirror or proxy instead of crates.io: a sparse index
+    /// (`sparse+` optional), or a server with the crates.io web API
+    pub fn with_registry(self, url: &str) -> Self {
+        self.lock_registries()
+            .insert(None, Arc::new(Registry::remote(url, url, None)));
+        self
+    }
+
+    /// Resolve registries (and replace sources) as Cargo would
+    pub fn with_cargo_config(mut self, config: CargoConfig) -> Self {
+        self.cargo_config = Arc::new(config);
+        self
+    }
+
+    /// Serve responses from (and store them in) an on-disk cache
+    pub fn with_cache(mut self, cache: Option<HttpCache>) -> Self {
+        self.http = self.http.with_cache(cache);
+        self
+    }
+
+    fn lock_registries(&self) -> std::sync::MutexGuard<'_, HashMap<Option<String>, Arc<Registry>>> {
+        self.registries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// The registry crates from `name` (crates.io for `None`) are read from
+    fn registry(&self, name: Option<&str>) -> Result<Arc<Registry>> {
+        let mut registries = self.lock_registries();
+        let key = name.map(str::to_string);
+        if let Some(registry) = registries.get(&key) {
+            return Ok(Arc::clone(registry));
+        }
+        let source = self.cargo_config.source(name).map_err(|e| anyhow!(e))?;
+        let registry = Arc::new(Registry::from(source));
+        registries.insert(key, Arc::clone(&registry));
+        Ok(registry)
+    }
+
+    /// Fetch package info for `lookup` (see `lookup_name`) from wherever its
+    /// registry keeps it
+    async fn fetch_package(&self, lookup: &str) -> Result<PackageInfo> {
+        let (registry, name) = match lookup.split_once('/') {
+            Some((registry, name)) => (self.registry(Some(registry))?, name),
+            None => (self.registry(None)?, lookup),
+        };
+        let (crate_data, cached_at) = match &registry.source {
+            Source::Remote { url, auth, config } => {
+                let config = config
+                    .get_or_try_init(|| self.fetch_config(&registry.name, url, auth.as_ref()))
+                    .await?;
+                match config {
+                    Some(config) => {
+                        let auth = auth.as_ref().filter(|_| config.auth_required);
+                        self.fetch_index(&registry.name, url, auth, name).await?
+                    }
+                    None => self.fetch_api(&registry.name, url, name).await?,
+                }
+            }
+            Source::Git(url) => bail!(
+                "{} uses a git index ({url}), which ccu can't read; \
+                 configure its sparse index (sparse+https://...) instead",
+                registry.name
+            ),
+            Source::Local(path) => (read_local(&registry.name, path, name)?, None),
+            Source::Directory { path, crates } => {
+                let crates = crates
+                    .get_or_try_init(|| async { scan_directory(path) })
+                    .await?;
+                let versions = crates.get(&name.to_ascii_lowercase()).ok_or_else(|| {
+                    NotFound(format!("Crate '{name}' not found in {}", registry.name))
+                })?;
+                let crate_data = CrateResponse {
+                    crate_info: CrateInfo {
+                        name: name.to_string(),
+                    },
+                    versions: versions.clone(),
+                };
+                (crate_data, None)
+            }
+        };
+        self.package_info(crate_data, cached_at)
+    }
+
+    /// Read `config.json`, `None` if the registry doesn't serve one
+    ///
+    /// It's asked for without credentials first, as Cargo does; if the
+    /// registry refuses, again with its token, and the index is then treated
+    /// as requiring authentication.
+    async fn fetch_config(
+        &self,
+        name: &str,
+        url: &str,
+        auth: Option<&Auth>,
+    ) -> Result<Option<IndexConfig>> {
+        let config_url = format!("{url}/config.json");
+        let mut response = self
+            .http
+            .get(&config_url, None)
+            .await
+            .context(format!("Failed to reach {name}"))?;
+        let mut authenticated = false;
+        if matches!(response.status.as_u16(), 401 | 403) {
+            let Some(auth) = auth else {
+                bail!("{name} requires authentication ({}); no token configured for it", response.status);
+            };
+            response = self
+                .http
+                .get_authorized(&config_url, None, Some(auth))
+                .await
+                .context(format!("Failed to reach {name}"))?;
+            authenticated = true;
+        }
+        if response.status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.is_success() {
+            return Err(anyhow!(
+                "{name} index request failed with status: {}",
+                response.status
+            ));
+        }
+        // Anything else at that path (an HTML page, say) means this isn't an index
+        Ok(serde_json::from_str(&response.body)
+            .ok()
+            .map(|config: IndexConfig| IndexConfig {
+                auth_required: config.auth_required || authenticated,
+                ..config
+            }))
+    }
+
+    /// Read the crate's file in a sparse index
+    async fn fetch_index(
+        &self,
+        registry: &str,
+        url: &str,
+        auth: Option<&Auth>,
+        name: &str,
+    ) -> Result<(CrateResponse, Option<Timestamp>)> {
+        let url = format!("{url}/{}", sparse::index_path(name));
+        let response = self.get(registry, &url, auth, name).await?;
+        Ok((index_file(&response.body, name), response.cached_at))
+    }
+
+    /// Query `<url>/api/v1/crates/<name>`
+    async fn fetch_api(
+        &self,
+        registry: &str,
+        url: &str,
+        name: &str,
+    ) -> Result<(CrateResponse, Option<Timestamp>)> {
+        let url = format!("{url}{API_PATH}/{name}");
+        let response = self.get(registry, &url, None, name).await?;
+
+        let crate_data: CrateResponse = serde_json::from_str(&response.body)
+            .context(format!("Failed to parse JSON response for '{name}'"))?;
+        Ok((crate_data, response.cached_at))
+    }
 
+    /// GET a crate's metadata, turning a missing crate into `NotFound`
+    async fn get(
+        &self,
+        registry: &str,
+        url: &str,
+        auth: Option<&Auth>,
+        name: &str,
+    ) -> Result<HttpResponse> {
         let response = self
-            .client
-            .get(&url)
-            .send()
+            .http
+            .get_authorized(url, None, auth)
             .await
             .context(format!("Failed to fetch crate '{name}'"))?;
 
-        if !response.status().is_success() {
-            if response.status() == reqwest::StatusCode::NOT_FOUND {
-                return Err(anyhow!("Crate '{name}' not found on crates.io"));
+        if !response.is_success() {
+            // Sparse indexes may answer 410 or 451 for crates they don't have
+            if matches!(response.status.as_u16(), 404 | 410 | 451) {
+                return Err(NotFound(format!("Crate '{name}' not found on {registry}")).into());
             }
             return Err(anyhow!(
-                "crates.io API request failed with status: {}",
-                response.status()
+                "{registry} request failed with status: {}",
+                response.status
             ));
         }
+        Ok(response)
+    }
 
-        let crate_data: CrateResponse = response
-            .json()
-            .await
-            .context(format!("Failed to parse JSON response for '{name}'"))?;
-
+    /// Collect a crate's published versions into `PackageInfo`
+    fn package_info(&self, crate_data: CrateResponse, cached_at: Option<Timestamp>) -> Result<PackageInfo> {
+        let name = crate_data.crate_info.name;
         // Parse all versions, skipping yanked ones
         let mut all_versions: Vec<Version> = Vec::new();
+        let mut published = BTreeMap::new();
+        let mut requires = BTreeMap::new();
         for version in &crate_data.versions {
             if version.yanked {
                 continue;
             }
 
             if let Ok(v) = Version::from_str(&version.num) {
+                if let Some(created) = version.created_at.as_deref().and_then(Timestamp::parse) {
+                    published.insert(v.clone(), created);
+                }
+                if let Some(rust_version) = &version.rust_version {
+                    requires.insert(v.clone(), rust_version.clone());
+                }
                 all_versions.push(v);
             }
         }
@@ -128,103 +396,95 @@ impl CratesIoClient {
             .cloned();
 
         Ok(PackageInfo {
-            name: crate_data.crate_info.name,
+            name,
             versions: filtered_versions,
             latest,
             latest_stable,
+            published,
+            requires,
+            cached_at,
         })
     }
+}
 
-    /// Fetch multiple packages concurrently
-    pub async fn get_packages(
-        &self,
-        names: &[String],
-        progress_callback: impl Fn(usize, usize) + Send + Sync + 'static,
-    ) -> Result<GetPackagesResult> {
-        let total = names.len();
-        let progress_callback = Arc::new(progress_callback);
-
-        // Limit concurrent requests to avoid overwhelming the server
-        // crates.io has rate limits, so be conservative
-        let semaphore = Arc::new(Semaphore::new(5));
-
-        let mut tasks = Vec::new();
-
-        for (index, name) in names.iter().enumerate() {
-            let client = self.clone();
-            let name = name.clone();
-            let callback = Arc::clone(&progress_callback);
-            let semaphore = Arc::clone(&semaphore);
-
-            let task = tokio::spawn(async move {
-                // Acquire semaphore permit
-                let _permit = semaphore.acquire().await.expect("semaphore closed");
-
-                let result = client.get_package(&name).await;
-
-                // Call progress callback
-                callback(index + 1, total);
-
-                (name, result)
-            });
+/// The versions in a crate's index file, as the web API would list them
+fn index_file(body: &str, name: &str) -> CrateResponse {
+    let entries = sparse::parse_entries(body);
+    let crate_info = CrateInfo {
+        name: entries.first().map_or_else(|| name.to_string(), |e| e.name.clone()),
+    };
+    let versions = entries
+        .into_iter()
+        .map(|entry| CrateVersion {
+            num: entry.vers,
+            yanked: entry.yanked,
+            created_at: entry.pubtime,
+            rust_version: entry.rust_version,
+        })
+        .collect();
+    CrateResponse {
+        crate_info,
+        versions,
+    }
+}
 
-            tasks.push(task);
-        }
-
-        // Wait for all tasks to complete
-        let mut packages = HashMap::new();
-        let mut errors = Vec::new();
-
-        for task in tasks {
-            match task.await {
-                Ok((name, Ok(package_info))) => {
-                    packages.insert(name, package_info);
-                }
-                Ok((name, Err(e))) => {
-                    let error_msg = e.to_string();
-                    errors.push((name, error_msg));
-                }
-                Err(e) => {
-                    errors.push(("unknown".to_string(), format!("Task failed: {e}")));
-                }
-            }
+/// Read a crate's file from a local registry's `index` directory
+fn read_local(registry: &str, path: &Path, name: &str) -> Result<CrateResponse> {
+    let file = path.join("index").join(sparse::index_path(name));
+    match fs::read_to_string(&file) {
+        Ok(body) => Ok(index_file(&body, name)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            Err(NotFound(format!("Crate '{name}' not found in {registry}")).into())
         }
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", file.display())),
+    }
+}
 
-        // Format errors as strings
-        let formatted_errors: Vec<String> = errors
-            .into_iter()
-            .map(|(name, msg)| format!("{name}: {msg}"))
-            .collect();
-
-        // If we have some results, return them even if some packages failed
-        if !packages.is_empty() || formatted_errors.is_empty() {
-            Ok(GetPackagesResult {
-                packages,
-                errors: formatted_errors,
-            })
-        } else {
-            // All packages failed
-            Err(anyhow!(
-                "Failed to fetch all crates:\n{}",
-                formatted_errors.join("\n")
-            ))
+/// The crates in a directory source (`cargo vendor`), by lowercased name,
+/// from the manifest in each subdirectory
+fn scan_directory(path: &Path) -> Result<HashMap<String, Vec<CrateVersion>>> {
+    let entries =
+        fs::read_dir(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut crates: HashMap<String, Vec<CrateVersion>> = HashMap::new();
+    for entry in entries.flatten() {
+        let Ok(content) = fs::read_to_string(entry.path().join("Cargo.toml")) else {
+            continue;
+        };
+        let Ok(manifest) = toml::from_str::<toml::Value>(&content) else {
+            continue;
+        };
+        let Some(package) = manifest.get("package") else {
+            continue;
+        };
+        let field = |key: &str| package.get(key).and_then(toml::Value::as_str).map(String::from);
+        if let (Some(name), Some(version)) = (field("name"), field("version")) {
+            crates.entry(name.to_ascii_lowercase()).or_default().push(CrateVersion {
+                num: version,
+                yanked: false,
+                created_at: None,
+                rust_version: field("rust-version"),
+            });
         }
     }
+    Ok(crates)
 }
 
-/// Result of fetching multiple packages
-#[derive(Debug, Clone)]
-pub struct GetPackagesResult {
-    pub packages: HashMap<String, PackageInfo>,
-    pub errors: Vec<String>,
+impl RegistryClient for CratesIoClient {
+    async fn get_package(&self, name: &str) -> Result<PackageInfo, RegistryError> {
+        // Unwrap `NotFound` so the caller can tell it apart from other failures
+        self.fetch_package(name)
+            .await
+            .map_err(|e| e.downcast::<NotFound>().map_or_else(RegistryError::new, RegistryError::new))
+    }
 }
 
 // Implement Clone for CratesIoClient to support concurrent usage
 impl Clone for CratesIoClient {
     fn clone(&self) -> Self {
         Self {
-            client: self.client.clone(),
-            base_url: self.base_url.clone(),
+            http: self.http.clone(),
+            cargo_config: Arc::clone(&self.cargo_config),
+            registries: Arc::clone(&self.registries),
             include_prerelease: self.include_prerelease,
         }
     }
@@ -255,4 +515,152 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not found"));
     }
+
+    #[tokio::test]
+    async fn test_custom_registry() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/api/v1/crates/anyhow"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"crate": {"name": "anyhow"}, "versions": [
+                    {"num": "1.0.100", "yanked": false, "created_at": "2025-09-01T00:00:00Z"},
+                    {"num": "1.0.99", "yanked": true, "created_at": null}
+                ]}"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let client = CratesIoClient::new(false).with_registry(&server.uri());
+        let info = client.get_package("anyhow").await.expect("served by the mirror");
+        assert_eq!(info.latest.to_string(), "1.0.100");
+        assert_eq!(info.versions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sparse_index() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/index/config.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"dl": "https://static.example.com/crates", "api": "https://example.com"}"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/index/an/yh/anyhow"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(concat!(
+                r#"{"name":"anyhow","vers":"1.0.99","deps":[],"cksum":"00","features":{},"yanked":false,"rust_version":"1.39"}"#,
+                "\n",
+                r#"{"name":"anyhow","vers":"1.0.100","deps":[],"cksum":"00","features":{},"yanked":false,"rust_version":"1.39","pubtime":"2025-09-01T00:00:00Z"}"#,
+                "\n",
+                r#"{"name":"anyhow","vers":"1.0.101","deps":[],"cksum":"00","features":{},"yanked":true}"#,
+                "\n",
+            )))
+            .mount(&server)
+            .await;
+
+        let client = CratesIoClient::new(false).with_registry(&format!("sparse+{}/index/", server.uri()));
+        let result = client
+            .get_packages(&["anyhow".to_string(), "nope".to_string()], |_, _| {})
+            .await;
+
+        let info = &result.packages["anyhow"];
+        assert_eq!(info.latest.to_string(), "1.0.100");
+        assert_eq!(info.versions.len(), 2);
+        assert_eq!(info.published.len(), 1);
+        assert_eq!(info.requires.get(&info.latest).map(String::as_str), Some("1.39"));
+        assert_eq!(result.not_found.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_alternate_and_local_registries() -> Result<(), Box<dyn std::error::Error>> {
+        use wiremock::matchers::{header, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/acme/config.json"))
+            .and(header("authorization", "s3cret"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"dl": "https://cargo.acme.dev/dl", "auth-required": true}"#,
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(path("/acme/config.json"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+        Mock::given(path("/acme/ac/me/acme-core"))
+            .and(header("authorization", "s3cret"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"name":"acme-core","vers":"0.5.0","deps":[],"cksum":"00","features":{},"yanked":false}"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir()?;
+        let config_file = dir.path().join(".cargo/config.toml");
+        fs::create_dir_all(dir.path().join(".cargo"))?;
+        fs::write(
+            &config_file,
+            format!(
+                r#"
+                [registries.acme]
+                index = "sparse+{}/acme/"
+                token = "s3cret"
+
+                [source.crates-io]
+                replace-with = "vendored"
+
+                [source.vendored]
+                directory = "vendor"
+
+                [source.offline]
+                local-registry = "registry"
+
+                [registries.offline]
+                index = "https://example.com/unused.git"
+                "#,
+                server.uri()
+            ),
+        )?;
+        fs::create_dir_all(dir.path().join("vendor/serde"))?;
+        fs::write(
+            dir.path().join("vendor/serde/Cargo.toml"),
+            "[package]\nname = \"serde\"\nversion = \"1.0.200\"\nrust-version = \"1.61\"\n",
+        )?;
+        fs::create_dir_all(dir.path().join("registry/index/3/l"))?;
+        fs::write(
+            dir.path().join("registry/index/3/l/log"),
+            r#"{"name":"log","vers":"0.4.22","deps":[],"cksum":"00","features":{},"yanked":false}"#,
+        )?;
+
+        let client = CratesIoClient::new(false)
+            .with_cargo_config(CargoConfig::from_files(&[config_file], HashMap::new())?);
+        let result = client
+            .get_packages(
+                &[
+                    "acme/acme-core".to_string(),
+                    "serde".to_string(),
+                    "offline/log".to_string(),
+                    "tokio".to_string(),
+                    "nowhere/thing".to_string(),
+                ],
+                |_, _| {},
+            )
+            .await;
+
+        assert_eq!(result.packages["acme/acme-core"].latest.to_string(), "0.5.0");
+        let serde = &result.packages["serde"];
+        assert_eq!(serde.latest.to_string(), "1.0.200");
+        assert_eq!(serde.requires.get(&serde.latest).map(String::as_str), Some("1.61"));
+        assert_eq!(result.packages["offline/log"].latest.to_string(), "0.4.22");
+        assert_eq!(result.not_found, ["tokio: Crate 'tokio' not found in vendored"]);
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].contains("registry 'nowhere' isn't configured"));
+        Ok(())
+    }
 }
@@ -0,0 +1,118 @@
+use check_updates_core::{
+    leftmost_nonzero_severity, Ecosystem, UpdateSeverity, Version, VersionError, VersionSpec,
+};
+
+/// Cargo's version requirement semantics
+pub struct CargoEcosystem;
+
+impl Ecosystem for CargoEcosystem {
+    fn name(&self) -> &'static str {
+        "cargo"
+    }
+
+    /// Parse a Cargo version requirement (bare versions are caret)
+    fn parse_spec(&self, s: &str) -> Result<VersionSpec, VersionError> {
+        let s = s.trim();
+
+        // Cargo pins with a single `=`
+        if let Some(version_str) = s.strip_prefix('=')
+            && !version_str.starts_with('=')
+            && !s.contains(',')
+        {
+            return Ok(VersionSpec::Pinned(version_str.trim().parse()?));
+        }
+
+        // If it has an operator, use standard parsing
+        if s.starts_with('^') || s.starts_with('~') || s.starts_with('>')
+            || s.starts_with('<') || s.starts_with('=') || s.contains('*')
+            || s.contains(',')
+        {
+            return VersionSpec::parse(s);
+        }
+
+        // Bare version in Cargo means caret (^)
+        // e.g., "1.0" means "^1.0" which allows 1.x but not 2.0
+        VersionSpec::parse(&format!("^{s}"))
+    }
+
+    fn satisfies(&self, spec: &VersionSpec, version: &Version) -> bool {
+        match spec {
+            // `=1.2` in Cargo means any 1.2.x, not exactly 1.2.0
+            VersionSpec::Pinned(v) if v.release.len() < 3 => {
+                (0..v.release.len()).all(|i| version.release_segment(i) == v.release_segment(i))
+                    && !version.is_prerelease()
+            }
+            _ => spec.satisfies(version),
+        }
+    }
+
+    /// A bump of the leftmost non-zero component is breaking under caret rules
+    fn severity(&self, current: &Version, target: &Version) -> Option<UpdateSeverity> {
+        leftmost_nonzero_severity(current, target)
+    }
+
+    /// Serialize to Cargo.toml requirement syntax.
+    /// Cargo conventions: bare version = caret, `=` for exact pin, `~` for tilde, etc.
+    /// Build metadata (+...) is stripped since it's not valid in requirements.
+    fn format_spec(&self, spec: &VersionSpec) -> Option<String> {
+        let formatted = match spec {
+            VersionSpec::Caret(v) => v.to_string(), // bare = caret in Cargo
+            VersionSpec::Tilde(v) => format!("~{v}"),
+            VersionSpec::Pinned(v) => format!("={v}"), // Cargo uses single =
+            VersionSpec::Minimum(v) => format!(">={v}"),
+            VersionSpec::Maximum(v) => format!("<={v}"),
+            VersionSpec::GreaterThan(v) => format!(">{v}"),
+            VersionSpec::LessThan(v) => format!("<{v}"),
+            VersionSpec::Range { min, max } => format!(">={min}, <{max}"),
+            VersionSpec::Wildcard { prefix, .. } => format!("{prefix}.*"),
+            VersionSpec::NotEqual(v) => format!("!={v}"),
+            VersionSpec::Compatible(v) => v.to_string(), // not a Cargo concept, treat as bare
+            VersionSpec::NpmRange(range) => range.to_string(),
+            VersionSpec::Complex(s) => s.clone(),
+            VersionSpec::Any => "*".to_string(),
+        };
+        Some(strip_build_metadata(&formatted))
+    }
+}
+
+/// Remove `+build` suffixes from every version in a requirement
+fn strip_build_metadata(spec: &str) -> String {
+    spec.split(", ")
+        .map(|part| part.split('+').next().unwrap_or(part))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_parse_cargo_specs() {
+        let eco = CargoEcosystem;
+        assert!(matches!(eco.parse_spec("1.0").unwrap(), VersionSpec::Caret(_)));
+        assert!(matches!(eco.parse_spec("=1.2.3").unwrap(), VersionSpec::Pinned(_)));
+        assert!(matches!(eco.parse_spec("~1.2").unwrap(), VersionSpec::Tilde(_)));
+        assert!(matches!(eco.parse_spec(">=1.0, <2.0").unwrap(), VersionSpec::Range { .. }));
+    }
+
+    #[test]
+    fn test_partial_pin_matches_series() {
+        let eco = CargoEcosystem;
+        let spec = eco.parse_spec("=1.2").unwrap();
+        assert!(eco.satisfies(&spec, &Version::from_str("1.2.7").unwrap()));
+        assert!(!eco.satisfies(&spec, &Version::from_str("1.3.0").unwrap()));
+    }
+
+    #[test]
+    fn test_format_cargo_specs() {
+        let eco = CargoEcosystem;
+        let spec = VersionSpec::Caret(Version::from_str("1.0.200+build.1").unwrap());
+        assert_eq!(eco.format_spec(&spec).unwrap(), "1.0.200");
+        let spec = VersionSpec::parse(">=1.0.0, <2.0.0").unwrap();
+        assert_eq!(eco.format_spec(&spec).unwrap(), ">=1.0.0, <2.0.0");
+        let spec = VersionSpec::Pinned(Version::new(1, 2, 3));
+        assert_eq!(eco.format_spec(&spec).unwrap(), "=1.2.3");
+    }
+}
@@ -1,4 +1,7 @@
-use check_updates_core::{UpdateSeverity, Version};
+use crate::ecosystem::CargoEcosystem;
+use check_updates_core::http::{HttpClient, HttpError};
+use check_updates_core::report::GlobalRecord;
+use check_updates_core::{Ecosystem, UpdateSeverity, Version};
 use anyhow::Result;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -63,23 +66,36 @@ impl GlobalCheck {
             return None;
         }
         let latest = self.latest_version.as_ref()?;
-        let current = &self.package.installed_version;
-
-        if latest.major > current.major {
-            Some(UpdateSeverity::Major)
-        } else if latest.minor > current.minor {
-            Some(UpdateSeverity::Minor)
-        } else if latest.patch > current.patch {
-            Some(UpdateSeverity::Patch)
-        } else {
-            None
+        CargoEcosystem.severity(&self.package.installed_version, latest)
+    }
+
+    /// Convert to a machine-readable record
+    pub fn to_record(&self) -> GlobalRecord {
+        let mut record = GlobalRecord::new(
+            &self.package.name,
+            &self.package.source.to_string(),
+            &self.package.installed_version.to_string(),
+            self.latest_version.as_ref().map(ToString::to_string),
+        )
+        .with_update(self.has_update, self.update_severity());
+        record.latest_hash.clone_from(&self.latest_hash);
+        record.commits_behind = self.commits_behind;
+        if self.package.local_path.is_some() {
+            record.has_dirty_changes = Some(self.has_dirty_changes);
         }
+        record
     }
 }
 
 /// Discovers globally installed cargo crates from ~/.cargo/.crates.toml
 pub struct GlobalPackageDiscovery {}
 
+impl Default for GlobalPackageDiscovery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl GlobalPackageDiscovery {
     pub fn new() -> Self {
         Self {}
@@ -162,9 +178,8 @@ impl GlobalPackageDiscovery {
                 git_hash: None,
                 local_path: None,
             })
-        } else if source_str.starts_with("git+") {
+        } else if let Some(git_part) = source_str.strip_prefix("git+") {
             // Parse: "git+https://github.com/user/repo#commithash"
-            let git_part = &source_str[4..]; // strip "git+"
             let (url, hash) = if let Some(hash_idx) = git_part.find('#') {
                 (
                     git_part[..hash_idx].to_string(),
@@ -183,11 +198,9 @@ impl GlobalPackageDiscovery {
                 git_hash: hash,
                 local_path: None,
             })
-        } else if source_str.starts_with("path+") {
+        } else if let Some(path_part) = source_str.strip_prefix("path+") {
             // Parse: "path+file:///home/user/project"
-            let path_str = source_str
-                .strip_prefix("path+file://")
-                .unwrap_or(&source_str[5..]);
+            let path_str = path_part.strip_prefix("file://").unwrap_or(path_part);
             let path = PathBuf::from(path_str);
 
             // Only include if the path still exists and is a git repo
@@ -211,14 +224,18 @@ impl GlobalPackageDiscovery {
 }
 
 /// Check git repositories for newer commits
-pub async fn check_git_updates(packages: &[GlobalPackage]) -> HashMap<String, GitStatus> {
-    let client = reqwest::Client::builder()
-        .user_agent("cargo-check-updates/0.1.0")
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .unwrap_or_else(|_| reqwest::Client::new());
+pub async fn check_git_updates(packages: &[GlobalPackage]) -> GitCheckResult {
+    // Unauthenticated GitHub API calls are rate limited per hour; the client
+    // waits out short resets and reports the rest as failures
+    let client = HttpClient::new(
+        reqwest::Client::builder()
+            .user_agent("cargo-check-updates/0.1.0")
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new()),
+    );
 
-    let mut results = HashMap::new();
+    let mut result = GitCheckResult::default();
 
     for pkg in packages {
         if pkg.source != GlobalSource::Git {
@@ -232,13 +249,26 @@ pub async fn check_git_updates(packages: &[GlobalPackage]) -> HashMap<String, Gi
         };
 
         if let Some((owner, repo)) = parse_github_url(url) {
-            if let Some(status) = check_github_repo(&client, &owner, &repo, installed_hash).await {
-                results.insert(pkg.name.clone(), status);
+            match check_github_repo(&client, &owner, &repo, installed_hash).await {
+                Ok(Some(status)) => {
+                    result.statuses.insert(pkg.name.clone(), status);
+                }
+                Ok(None) => {}
+                Err(e) => result.errors.push(format!("{}: {e}", pkg.name)),
             }
         }
     }
 
-    results
+    result
+}
+
+/// Result of checking git-installed crates
+#[derive(Debug, Clone, Default)]
+pub struct GitCheckResult {
+    pub statuses: HashMap<String, GitStatus>,
+    /// `"name: message"` for repos GitHub couldn't be asked about
+    /// (rate limiting, server or network errors)
+    pub errors: Vec<String>,
 }
 
 /// Status of a git-installed crate
@@ -266,24 +296,32 @@ fn parse_github_url(url: &str) -> Option<(String, String)> {
 }
 
 /// Query GitHub API to check if a commit is behind HEAD
+///
+/// `Ok(None)` when GitHub has no answer for this repo (deleted, private, or
+/// the commit is gone); `Err` when the request itself kept failing.
 async fn check_github_repo(
-    client: &reqwest::Client,
+    client: &HttpClient,
     owner: &str,
     repo: &str,
     installed_hash: &str,
-) -> Option<GitStatus> {
+) -> Result<Option<GitStatus>, HttpError> {
     // Use the compare API: compare installed_hash...HEAD
     let url = format!(
         "https://api.github.com/repos/{owner}/{repo}/compare/{installed_hash}...HEAD"
     );
 
-    let response = client.get(&url).send().await.ok()?;
+    let response = client.get(&url, Some("application/vnd.github+json")).await?;
 
-    if !response.status().is_success() {
-        return None;
+    if !response.is_success() {
+        return Ok(None);
     }
 
-    let json: serde_json::Value = response.json().await.ok()?;
+    Ok(parse_compare(&response.body, installed_hash))
+}
+
+/// Read a compare API response
+fn parse_compare(body: &str, installed_hash: &str) -> Option<GitStatus> {
+    let json: serde_json::Value = serde_json::from_str(body).ok()?;
 
     let status = json.get("status")?.as_str()?;
     let ahead_by = json.get("ahead_by")?.as_u64()?;
@@ -1,9 +1,13 @@
 pub mod cli;
 pub mod cratesio;
 pub mod detector;
+pub mod ecosystem;
 pub mod global;
+pub mod msrv;
 pub mod output;
 pub mod parsers;
+pub mod registries;
+pub mod sparse;
 pub mod updater;
 
 // Re-export core types for convenience
@@ -2,34 +2,89 @@ use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::process::ExitCode;
 use std::sync::{Arc, Mutex};
 
 use ccu::cli::Args;
-use ccu::cratesio::CratesIoClient;
+use ccu::cratesio::{lookup_name, CratesIoClient};
 use ccu::detector::ProjectDetector;
+use ccu::ecosystem::CargoEcosystem;
 use ccu::global::{
-    check_git_updates, check_path_updates, generate_upgrade_commands, GlobalCheck,
+    check_git_updates, check_path_updates, generate_upgrade_commands, GitCheckResult, GlobalCheck,
     GlobalPackageDiscovery, GlobalSource,
 };
+use ccu::msrv::RustVersions;
 use ccu::output::GlobalTableRenderer;
 use ccu::parsers::{CargoLockParser, CargoTomlParser, DependencyParser};
+use ccu::registries::CargoConfig;
 use ccu::updater::FileUpdater;
-use check_updates_core::{DependencyCheck, DependencyResolver, TableRenderer, Version};
+use check_updates_core::config::ManifestSection;
+use check_updates_core::exit::code;
+use check_updates_core::interactive::Picker;
+use check_updates_core::registry::{FixtureRegistry, RegistryClient};
+use check_updates_core::{
+    exit_status, Annotations, Config, DependencyCheck, DependencyResolver, Ecosystem, Report, ReportMode, TableRenderer, Version,
+};
+
+/// `[package.metadata.check-updates]` or `[workspace.metadata.check-updates]` in Cargo.toml,
+/// used when there's no .check-updates.toml
+const CONFIG_SECTIONS: &[ManifestSection] = &[
+    ManifestSection {
+        file: "Cargo.toml",
+        path: &["package", "metadata", "check-updates"],
+    },
+    ManifestSection {
+        file: "Cargo.toml",
+        path: &["workspace", "metadata", "check-updates"],
+    },
+];
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let args = Args::parse();
 
+    // Load .check-updates.toml (or the manifest section) and merge its default
+    // flags before building the client, which needs `registry` and `pre-release`
+    let config = if args.global {
+        Config::default()
+    } else {
+        Config::load(&args.project_path(), CONFIG_SECTIONS)?
+    };
+    let args = args.with_defaults(&config.defaults);
+
+    let status = match &args.registry_fixtures {
+        Some(dir) => {
+            let fixtures = FixtureRegistry::from_dir(dir, args.pre_release)?;
+            run(&args, &config, &fixtures).await?
+        }
+        None => {
+            // Alternate registries and source replacement from .cargo/config.toml
+            let project_dir = (!args.global).then(|| args.project_path());
+            let mut cratesio_client = CratesIoClient::new(args.pre_release)
+                .with_cache(args.cache())
+                .with_cargo_config(CargoConfig::discover(project_dir.as_deref())?);
+            if let Some(registry) = &args.registry {
+                cratesio_client = cratesio_client.with_registry(registry.as_str());
+            }
+            run(&args, &config, &cratesio_client).await?
+        }
+    };
+    Ok(ExitCode::from(status))
+}
+
+async fn run(args: &Args, config: &Config, client: &impl RegistryClient) -> Result<u8> {
     if args.global {
-        run_global_mode(&args).await
+        run_global_mode(args, client).await
     } else {
-        run_project_mode(&args).await
+        run_project_mode(args, config, client).await
     }
 }
 
-async fn run_global_mode(args: &Args) -> Result<()> {
-    if args.update {
+async fn run_global_mode(args: &Args, cratesio_client: &impl RegistryClient) -> Result<u8> {
+    let human = args.format.is_human();
+
+    if args.update && human {
         println!(
             "Note: --update flag is ignored in global mode. Commands will be shown instead.\n"
         );
@@ -40,8 +95,11 @@ async fn run_global_mode(args: &Args) -> Result<()> {
     let packages = discovery.discover()?;
 
     if packages.is_empty() {
-        println!("No globally installed cargo crates found.");
-        return Ok(());
+        if human {
+            println!("No globally installed cargo crates found.");
+        }
+        Report::new("ccu", ReportMode::Global).print(args.format)?;
+        return Ok(code::SUCCESS);
     }
 
     let registry_names: Vec<String> = packages
@@ -52,9 +110,12 @@ async fn run_global_mode(args: &Args) -> Result<()> {
     let git_count = packages.iter().filter(|p| p.source == GlobalSource::Git).count();
 
     // 2. Check path repos (local git fetch), query crates.io, and check git repos concurrently
-    let path_statuses = check_path_updates(&packages);
-
-    let cratesio_client = CratesIoClient::new(args.pre_release);
+    // Offline, only the crates.io cache is consulted
+    let path_statuses = if args.offline {
+        HashMap::new()
+    } else {
+        check_path_updates(&packages)
+    };
 
     let progress_bar = ProgressBar::new((registry_names.len() + git_count) as u64);
     progress_bar.set_style(
@@ -75,7 +136,11 @@ async fn run_global_mode(args: &Args) -> Result<()> {
             pb.set_position(current as u64);
         }),
         async {
-            let result = check_git_updates(&packages).await;
+            let result = if args.offline {
+                GitCheckResult::default()
+            } else {
+                check_git_updates(&packages).await
+            };
             let pb = progress_bar_clone.lock().expect("lock poisoned");
             pb.set_position(pb.length().unwrap_or(0));
             result
@@ -84,8 +149,15 @@ async fn run_global_mode(args: &Args) -> Result<()> {
 
     progress_bar.finish_and_clear();
 
-    let cratesio_result = cratesio_result?;
+    if cratesio_result.all_failed() {
+        anyhow::bail!("Failed to fetch all crates:\n{}", cratesio_result.failures());
+    }
     let package_infos = cratesio_result.packages;
+    let not_found = cratesio_result.not_found;
+    let mut fetch_errors = cratesio_result.errors;
+    fetch_errors.extend(git_statuses.errors);
+    let git_statuses = git_statuses.statuses;
+    let uncached = cratesio_result.uncached;
 
     // 3. Build checks
     let mut checks: Vec<GlobalCheck> = Vec::new();
@@ -152,11 +224,30 @@ async fn run_global_mode(args: &Args) -> Result<()> {
         }
     }
 
-    // 4. Render results
+    // Packages the registry doesn't have are listed, but only failed lookups
+    // leave the run unsure of them
+    let status = exit_status(
+        args.fail_on,
+        checks.iter().filter_map(GlobalCheck::update_severity),
+        !fetch_errors.is_empty() || !uncached.is_empty(),
+    );
+
+    // 4. Machine-readable output replaces the table and upgrade commands
+    if !human {
+        let mut report = Report::new("ccu", ReportMode::Global);
+        report.global.extend(checks.iter().map(GlobalCheck::to_record));
+        report.add_not_found(&not_found);
+        report.add_formatted_errors(&fetch_errors);
+        report.uncached = uncached;
+        report.print(args.format)?;
+        return Ok(status);
+    }
+
+    // 5. Render results
     let renderer = GlobalTableRenderer::new(true);
     renderer.render(&checks);
 
-    // 5. Generate upgrade commands
+    // 6. Generate upgrade commands
     let commands = generate_upgrade_commands(&checks);
     if !commands.is_empty() {
         println!("\nTo upgrade, run:\n");
@@ -165,11 +256,43 @@ async fn run_global_mode(args: &Args) -> Result<()> {
         }
     }
 
-    Ok(())
+    if !not_found.is_empty() {
+        println!();
+        println!("{}", "Crates not found on crates.io:".dimmed());
+        for error in &not_found {
+            println!("  {}", error.dimmed());
+        }
+    }
+    if !fetch_errors.is_empty() {
+        println!();
+        println!("{}", "Failed to fetch from crates.io or GitHub:".dimmed());
+        for error in &fetch_errors {
+            println!("  {}", error.dimmed());
+        }
+    }
+    if !uncached.is_empty() {
+        println!();
+        println!("{}", "Not in the offline cache (run once without --offline to fill it):".dimmed());
+        for name in &uncached {
+            println!("  {}", name.dimmed());
+        }
+    }
+
+    Ok(status)
 }
 
-async fn run_project_mode(args: &Args) -> Result<()> {
+async fn run_project_mode(
+    args: &Args,
+    config: &Config,
+    cratesio_client: &impl RegistryClient,
+) -> Result<u8> {
     let project_path = args.project_path();
+    let human = args.format.is_human();
+    let mut report = Report::new("ccu", ReportMode::Project);
+
+    if args.interactive && !human {
+        anyhow::bail!("--interactive can't be combined with --format {}", args.format);
+    }
 
     // Validate project path exists
     if !project_path.exists() {
@@ -185,8 +308,11 @@ async fn run_project_mode(args: &Args) -> Result<()> {
     let detected_files = detector.detect()?;
 
     if detected_files.is_empty() {
-        println!("No Cargo.toml found in {project_path:?}");
-        return Ok(());
+        if human {
+            println!("No Cargo.toml found in {project_path:?}");
+        }
+        report.print(args.format)?;
+        return Ok(code::SUCCESS);
     }
 
     // 2. Parse Cargo.toml
@@ -209,9 +335,19 @@ async fn run_project_mode(args: &Args) -> Result<()> {
         }
     }
 
+    // Drop packages excluded by config or a `check-updates: ignore` comment
+    let annotations = Annotations::scan(&all_dependencies, &CargoEcosystem);
+    for warning in annotations.warnings() {
+        eprintln!("Warning: {warning}");
+    }
+    all_dependencies.retain(|d| !config.is_ignored(&d.name) && !annotations.is_ignored(d));
+
     if all_dependencies.is_empty() {
-        println!("No dependencies found in Cargo.toml");
-        return Ok(());
+        if human {
+            println!("No dependencies found in Cargo.toml");
+        }
+        report.print(args.format)?;
+        return Ok(code::SUCCESS);
     }
 
     // Get installed versions from Cargo.lock
@@ -220,13 +356,11 @@ async fn run_project_mode(args: &Args) -> Result<()> {
     // 3. Query crates.io for latest versions
     let package_names: Vec<String> = all_dependencies
         .iter()
-        .map(|d| d.name.clone())
+        .map(lookup_name)
         .collect::<HashSet<_>>()
         .into_iter()
         .collect();
 
-    let cratesio_client = CratesIoClient::new(args.pre_release);
-
     // Create progress bar
     let progress_bar = ProgressBar::new(package_names.len() as u64);
     progress_bar.set_style(
@@ -245,33 +379,62 @@ async fn run_project_mode(args: &Args) -> Result<()> {
             let pb = progress_bar_clone.lock().expect("lock poisoned");
             pb.set_position(current as u64);
         })
-        .await?;
+        .await;
 
+    if cratesio_result.all_failed() {
+        anyhow::bail!("Failed to fetch all crates:\n{}", cratesio_result.failures());
+    }
     let package_infos = cratesio_result.packages;
+    let not_found = cratesio_result.not_found;
     let fetch_errors = cratesio_result.errors;
+    let uncached = cratesio_result.uncached;
     progress_bar.finish_and_clear();
 
     // Print fetch errors if any
-    if !fetch_errors.is_empty() {
+    if human && !not_found.is_empty() {
         println!("{}", "Crates not found on crates.io:".dimmed());
+        for error in &not_found {
+            println!("  {}", error.dimmed());
+        }
+    }
+    if human && !fetch_errors.is_empty() {
+        println!("{}", "Failed to fetch from crates.io:".dimmed());
         for error in &fetch_errors {
             println!("  {}", error.dimmed());
         }
         println!();
     }
+    if human && !uncached.is_empty() {
+        println!("{}", "Not in the offline cache (run once without --offline to fill it):".dimmed());
+        for name in &uncached {
+            println!("  {}", name.dimmed());
+        }
+        println!();
+    }
 
-    // 4. Resolve updates
-    let resolver = DependencyResolver::new();
+    // 4. Resolve updates, leaving out versions that need a newer Rust than
+    // the declaring package's `rust-version`
+    let mut resolver = DependencyResolver::new()
+        .with_ecosystem(CargoEcosystem)
+        .with_min_age(args.min_age);
+    if !args.ignore_rust_version {
+        let manifests: Vec<_> = detected_files.iter().map(|d| d.path.clone()).collect();
+        let rust_versions = RustVersions::load(&manifests)?;
+        resolver = resolver.with_filter(move |dependency, info, version| {
+            rust_versions.incompatible(dependency, info, version)
+        });
+    }
     let mut checks: Vec<DependencyCheck> = Vec::new();
 
     for dependency in &all_dependencies {
-        if let Some(package_info) = package_infos.get(&dependency.name) {
-            let installed = installed_versions.get(&dependency.name).and_then(|versions| {
+        if let Some(package_info) = package_infos.get(&lookup_name(dependency)) {
+            // Cargo.lock lists renamed dependencies under their real name
+            let installed = installed_versions.get(dependency.package_name()).and_then(|versions| {
                 // When multiple versions exist in Cargo.lock (e.g. direct + transitive),
                 // pick the highest version that satisfies the declared spec
                 let mut matching: Vec<&Version> = versions
                     .iter()
-                    .filter(|v| dependency.version_spec.satisfies(v))
+                    .filter(|v| CargoEcosystem.satisfies(&dependency.version_spec, v))
                     .collect();
                 matching.sort();
                 matching.last().copied().or_else(|| {
@@ -280,10 +443,20 @@ async fn run_project_mode(args: &Args) -> Result<()> {
                 })
             });
             let check = resolver.resolve(dependency, package_info, installed);
+            let check = config.apply(check, package_info, &resolver);
+            let check = annotations.apply(check, package_info, &resolver);
             checks.push(check);
         }
     }
 
+    // Packages the registry doesn't have are listed, but only failed lookups
+    // leave the run unsure of them
+    let status = exit_status(
+        args.fail_on,
+        checks.iter().filter_map(|c| c.severity),
+        !fetch_errors.is_empty() || !uncached.is_empty(),
+    );
+
     // 5. Deduplicate for display (same crate with same target)
     let mut seen: HashSet<String> = HashSet::new();
     let deduplicated: Vec<&DependencyCheck> = checks
@@ -301,20 +474,61 @@ async fn run_project_mode(args: &Args) -> Result<()> {
         })
         .collect();
 
-    // 6. Display results
-    let renderer = TableRenderer::new(true);
-    let header = if args.update {
-        "Dependencies updated:"
-    } else {
-        "Outdated dependencies:"
-    };
-    renderer.render_deduped(&deduplicated, header);
+    // 6. Interactive mode: choose updates instead of showing the table
+    if args.interactive {
+        let picker = Picker::new(&checks, &CargoEcosystem);
+        if picker.is_empty() {
+            println!("All dependencies are up to date!");
+            return Ok(status);
+        }
+        let Some(selected) = picker.run()? else {
+            println!("No changes made.");
+            return Ok(status);
+        };
+        let result = FileUpdater::new().apply_updates(&selected, false, true)?;
+        if !result.modified_files.is_empty() {
+            println!("Updated {} file(s):", result.modified_files.len());
+            for file in &result.modified_files {
+                println!("  - {}", file.display());
+            }
+        }
+        result.print_summary();
+        return Ok(status);
+    }
 
-    // 7. If --update, apply updates based on severity filter
+    // 7. Display results
+    if human {
+        let renderer = TableRenderer::new(true);
+        let header = if args.update {
+            "Dependencies updated:"
+        } else {
+            "Outdated dependencies:"
+        };
+        renderer.render_deduped(&deduplicated, header);
+        renderer.render_too_new(&checks);
+        renderer.render_blocked(&checks);
+        renderer.render_summary(&checks);
+    }
+
+    // 8. If --update, apply updates based on severity filter
     if args.update {
         let updater = FileUpdater::new();
         let result = updater.apply_updates(&checks, args.minor, args.force)?;
 
+        if !human {
+            report.add_checks(&checks, &CargoEcosystem);
+            report.add_not_found(&not_found);
+            report.add_formatted_errors(&fetch_errors);
+            report.uncached.clone_from(&uncached);
+            report.updated_files = result
+                .modified_files
+                .iter()
+                .map(|f| f.display().to_string())
+                .collect();
+            report.print(args.format)?;
+            return Ok(status);
+        }
+
         println!();
         if !result.modified_files.is_empty() {
             println!("Updated {} file(s):", result.modified_files.len());
@@ -324,6 +538,12 @@ async fn run_project_mode(args: &Args) -> Result<()> {
         }
 
         result.print_summary();
+    } else if !human {
+        report.add_checks(&checks, &CargoEcosystem);
+        report.add_not_found(&not_found);
+        report.add_formatted_errors(&fetch_errors);
+        report.uncached.clone_from(&uncached);
+        report.print(args.format)?;
     } else if !deduplicated.is_empty() {
         println!();
         println!(
@@ -334,5 +554,5 @@ async fn run_project_mode(args: &Args) -> Result<()> {
         );
     }
 
-    Ok(())
+    Ok(status)
 }
@@ -0,0 +1,168 @@
+use anyhow::{Context, Result};
+use check_updates_core::{Dependency, PackageInfo, Version};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+/// The `rust-version` (MSRV) each manifest's package declares
+#[derive(Debug, Clone, Default)]
+pub struct RustVersions {
+    by_manifest: HashMap<PathBuf, Version>,
+    /// Manifest of the workspace root, where `[workspace.dependencies]` live
+    root: Option<PathBuf>,
+}
+
+impl RustVersions {
+    /// Read `package.rust-version` from each manifest, resolving
+    /// `rust-version.workspace = true` against `[workspace.package]` in the
+    /// first one, the workspace root
+    pub fn load(manifests: &[PathBuf]) -> Result<Self> {
+        let mut versions = Self {
+            root: manifests.first().cloned(),
+            ..Self::default()
+        };
+        let mut inherited = None;
+        for (i, path) in manifests.iter().enumerate() {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let parsed: Value = toml::from_str(&content)
+                .with_context(|| format!("Failed to parse TOML in {}", path.display()))?;
+            if i == 0 {
+                inherited = parsed
+                    .get("workspace")
+                    .and_then(|w| w.get("package"))
+                    .and_then(|p| p.get("rust-version"))
+                    .and_then(Value::as_str)
+                    .and_then(|v| v.parse().ok());
+            }
+
+            let declared = parsed.get("package").and_then(|p| p.get("rust-version"));
+            let version = match declared {
+                Some(Value::String(v)) => v.parse().ok(),
+                Some(Value::Table(t))
+                    if t.get("workspace").and_then(Value::as_bool) == Some(true) =>
+                {
+                    inherited.clone()
+                }
+                _ => None,
+            };
+            if let Some(version) = version {
+                versions.by_manifest.insert(path.clone(), version);
+            }
+        }
+        Ok(versions)
+    }
+
+    /// MSRV that applies to dependencies declared in `manifest`
+    ///
+    /// The workspace root's dependencies (`[workspace.dependencies]` among
+    /// them) are shared by the members, so it gets the lowest MSRV in the
+    /// workspace.
+    pub fn for_manifest(&self, manifest: &Path) -> Option<&Version> {
+        if self.root.as_deref() == Some(manifest) {
+            return self.by_manifest.values().min();
+        }
+        self.by_manifest.get(manifest)
+    }
+
+    /// Why `version` can't be used where `dependency` is declared: it needs
+    /// a newer Rust than the package's `rust-version`
+    pub fn incompatible(
+        &self,
+        dependency: &Dependency,
+        info: &PackageInfo,
+        version: &Version,
+    ) -> Option<String> {
+        let msrv = self.for_manifest(&dependency.source_file)?;
+        let required: Version = info.requires.get(version)?.parse().ok()?;
+        (required > *msrv).then(|| format!("requires Rust {required}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use check_updates_core::VersionSpec;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_workspace_rust_versions() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let write = |path: &str, content: &str| -> Result<PathBuf> {
+            let path = tmp.path().join(path);
+            fs::create_dir_all(path.parent().context("parent")?)?;
+            fs::write(&path, content)?;
+            Ok(path)
+        };
+        let root = write(
+            "Cargo.toml",
+            r#"
+[workspace]
+members = ["app", "lib", "tool"]
+
+[workspace.package]
+rust-version = "1.74"
+"#,
+        )?;
+        let app = write(
+            "app/Cargo.toml",
+            r#"
+[package]
+name = "app"
+rust-version.workspace = true
+"#,
+        )?;
+        let lib = write(
+            "lib/Cargo.toml",
+            r#"
+[package]
+name = "lib"
+rust-version = "1.70"
+"#,
+        )?;
+        let tool = write("tool/Cargo.toml", "[package]\nname = \"tool\"\n")?;
+
+        let versions = RustVersions::load(&[root.clone(), app.clone(), lib, tool.clone()])?;
+        let msrv = |path: &Path| versions.for_manifest(path).map(ToString::to_string);
+        assert_eq!(msrv(&app).as_deref(), Some("1.74"));
+        assert_eq!(msrv(&root).as_deref(), Some("1.70"));
+        assert_eq!(msrv(&tool), None);
+
+        let mut info = PackageInfo {
+            name: "clap".to_string(),
+            versions: Vec::new(),
+            latest: "4.5.20".parse()?,
+            latest_stable: None,
+            published: Default::default(),
+            requires: Default::default(),
+            cached_at: None,
+        };
+        let version: Version = "4.5.20".parse()?;
+        info.requires.insert(version.clone(), "1.74".to_string());
+        let dependency = |source_file: &Path| Dependency {
+            name: "clap".to_string(),
+            registry: None,
+            package: None,
+            version_spec: VersionSpec::parse("4.4").expect("spec"),
+            source_file: source_file.to_path_buf(),
+            line_number: 1,
+            original_line: String::new(),
+        };
+        assert_eq!(
+            versions.incompatible(&dependency(&app), &info, &version),
+            None
+        );
+        assert_eq!(
+            versions
+                .incompatible(&dependency(&root), &info, &version)
+                .as_deref(),
+            Some("requires Rust 1.74")
+        );
+        assert_eq!(
+            versions.incompatible(&dependency(&tool), &info, &version),
+            None
+        );
+        Ok(())
+    }
+}
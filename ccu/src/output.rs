@@ -152,8 +152,8 @@ impl GlobalTableRenderer {
         for check in sorted {
             let mut status_parts: Vec<String> = Vec::new();
 
-            if let Some(n) = check.commits_behind {
-                if n > 0 {
+            if let Some(n) = check.commits_behind
+                && n > 0 {
                     let behind_str = if n == 1 {
                         "1 commit behind".to_string()
                     } else {
@@ -165,7 +165,6 @@ impl GlobalTableRenderer {
                         status_parts.push(behind_str);
                     }
                 }
-            }
 
             if check.has_dirty_changes {
                 let dirty = "dirty";
@@ -1,5 +1,6 @@
 use super::{Dependency, DependencyParser};
-use check_updates_core::VersionSpec;
+use crate::ecosystem::CargoEcosystem;
+use check_updates_core::{Ecosystem, VersionSpec};
 use anyhow::{Context, Result};
 use std::collections::HashMap;
 use std::fs;
@@ -12,6 +13,17 @@ pub struct CargoTomlParser {
     workspace_deps: HashMap<String, String>,
     /// Path to the root Cargo.toml (for correct source_file attribution on workspace deps)
     workspace_root: Option<std::path::PathBuf>,
+    /// Registry and real package name of [workspace.dependencies] entries, for deps that inherit them
+    workspace_sources: HashMap<String, DepSource>,
+}
+
+/// Where a dependency comes from, besides its version
+#[derive(Debug, Clone, Default)]
+struct DepSource {
+    /// `registry = "..."`
+    registry: Option<String>,
+    /// `package = "..."`, when the key is an alias
+    package: Option<String>,
 }
 
 impl CargoTomlParser {
@@ -19,6 +31,7 @@ impl CargoTomlParser {
         Self {
             workspace_deps: HashMap::new(),
             workspace_root: None,
+            workspace_sources: HashMap::new(),
         }
     }
 
@@ -38,6 +51,7 @@ impl CargoTomlParser {
                 if let Some(version) = self.extract_version(value) {
                     self.workspace_deps.insert(name.clone(), version);
                 }
+                self.workspace_sources.insert(name.clone(), Self::extract_source(value));
             }
         }
 
@@ -91,9 +105,17 @@ impl CargoTomlParser {
                     .unwrap_or("")
                     .to_string();
 
+                let source = if is_workspace_ref {
+                    self.workspace_sources.get(name).cloned().unwrap_or_default()
+                } else {
+                    Self::extract_source(value)
+                };
+
                 if let Ok(version_spec) = Self::parse_cargo_version(&version_str) {
                     deps.push(Dependency {
                         name: name.clone(),
+                        registry: source.registry,
+                        package: source.package,
                         version_spec,
                         source_file: effective_source.to_path_buf(),
                         line_number,
@@ -117,19 +139,7 @@ impl CargoTomlParser {
 
     /// Parse a Cargo version spec (bare versions are caret in Cargo semantics)
     fn parse_cargo_version(s: &str) -> Result<VersionSpec> {
-        let s = s.trim();
-
-        // If it has an operator, use standard parsing
-        if s.starts_with('^') || s.starts_with('~') || s.starts_with('>')
-            || s.starts_with('<') || s.starts_with('=') || s.contains('*')
-            || s.contains(',')
-        {
-            return VersionSpec::parse(s).map_err(|e| anyhow::anyhow!("{e}"));
-        }
-
-        // Bare version in Cargo means caret (^)
-        // e.g., "1.0" means "^1.0" which allows 1.x but not 2.0
-        VersionSpec::parse(&format!("^{s}")).map_err(|e| anyhow::anyhow!("{e}"))
+        CargoEcosystem.parse_spec(s).map_err(|e| anyhow::anyhow!("{e}"))
     }
 
     /// Extract version string from a dependency value, resolving `.workspace = true`
@@ -171,6 +181,16 @@ impl CargoTomlParser {
         }
     }
 
+    /// Extract the alternate registry (`registry = "name"`) and real package
+    /// name (`package = "name"`) of a dependency
+    fn extract_source(value: &Value) -> DepSource {
+        let field = |key| value.get(key).and_then(Value::as_str).map(String::from);
+        DepSource {
+            registry: field("registry"),
+            package: field("package"),
+        }
+    }
+
     /// Find the line number for a dependency
     fn find_line_number(&self, content: &str, name: &str, _version: &str) -> usize {
         for (idx, line) in content.lines().enumerate() {
@@ -275,7 +295,7 @@ tokio = {{ version = "1.0", features = ["full"] }}
         )?;
 
         let parser = CargoTomlParser::new();
-        let deps = parser.parse(&file.path().to_path_buf())?;
+        let deps = parser.parse(file.path())?;
 
         assert_eq!(deps.len(), 2);
 
@@ -302,7 +322,7 @@ local-crate = {{ path = "../local" }}
         )?;
 
         let parser = CargoTomlParser::new();
-        let deps = parser.parse(&file.path().to_path_buf())?;
+        let deps = parser.parse(file.path())?;
 
         // Should only have serde, not git/path deps
         assert_eq!(deps.len(), 1);
@@ -329,7 +349,7 @@ cc = "1.0"
         )?;
 
         let parser = CargoTomlParser::new();
-        let deps = parser.parse(&file.path().to_path_buf())?;
+        let deps = parser.parse(file.path())?;
 
         assert_eq!(deps.len(), 3);
         assert!(deps.iter().any(|d| d.name == "serde"));
@@ -414,7 +434,7 @@ direct = "1.0"
         )?;
 
         let parser = CargoTomlParser::new();
-        let deps = parser.parse(&file.path().to_path_buf())?;
+        let deps = parser.parse(file.path())?;
 
         // Only direct dep should be found
         assert_eq!(deps.len(), 1);
@@ -422,4 +442,71 @@ direct = "1.0"
 
         Ok(())
     }
+
+    #[test]
+    fn test_alternate_registry() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let root_toml = tmp.path().join("Cargo.toml");
+        fs::write(
+            &root_toml,
+            r#"
+[workspace.dependencies]
+acme-core = { version = "0.4", registry = "acme" }
+
+[dependencies]
+acme-core.workspace = true
+acme-utils = { version = "1.2", registry = "acme" }
+serde = "1.0"
+"#,
+        )?;
+
+        let mut parser = CargoTomlParser::new();
+        parser.load_workspace_deps(&root_toml)?;
+        let deps = parser.parse(&root_toml)?;
+
+        let registry = |name: &str| {
+            deps.iter()
+                .find(|d| d.name == name)
+                .and_then(|d| d.registry.as_deref())
+        };
+        assert_eq!(deps.len(), 4);
+        assert!(deps.iter().filter(|d| d.name == "acme-core").all(|d| d.registry.as_deref() == Some("acme")));
+        assert_eq!(registry("acme-utils"), Some("acme"));
+        assert_eq!(registry("serde"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_renamed_deps() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let root_toml = tmp.path().join("Cargo.toml");
+        fs::write(
+            &root_toml,
+            r#"
+[workspace.dependencies]
+http02 = { package = "http", version = "0.2" }
+
+[dependencies]
+tokio02 = { package = "tokio", version = "0.2" }
+tokio = "1.38"
+http02.workspace = true
+"#,
+        )?;
+
+        let mut parser = CargoTomlParser::new();
+        parser.load_workspace_deps(&root_toml)?;
+        let deps = parser.parse(&root_toml)?;
+
+        let renamed = deps.iter().find(|d| d.name == "tokio02").expect("tokio02");
+        assert_eq!(renamed.package_name(), "tokio");
+        assert_eq!(renamed.display_name(), "tokio02 (tokio)");
+        assert_eq!(renamed.version_spec.version_string().expect("version"), "0.2");
+
+        let tokio = deps.iter().find(|d| d.name == "tokio").expect("tokio");
+        assert_eq!(tokio.package, None);
+        assert!(deps.iter().filter(|d| d.name == "http02").all(|d| d.package_name() == "http"));
+
+        Ok(())
+    }
 }
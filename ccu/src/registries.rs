@@ -0,0 +1,405 @@
+use crate::sparse::CRATES_IO_INDEX;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+/// Cargo's name for the crates.io source
+const CRATES_IO: &str = "crates-io";
+
+/// Where a registry's crates are read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistrySource {
+    /// A sparse index (`sparse+https://...`)
+    Sparse {
+        name: String,
+        url: String,
+        token: Option<String>,
+    },
+    /// A git index, which ccu can't read
+    Git { name: String, url: String },
+    /// `local-registry`: an index and `.crate` files on disk
+    Local { name: String, path: PathBuf },
+    /// `directory`: unpacked crates, as `cargo vendor` leaves them
+    Directory { name: String, path: PathBuf },
+}
+
+/// Cargo's registry settings
+///
+/// Read from `.cargo/config.toml` in the project directory and each parent,
+/// then `$CARGO_HOME/config.toml` and `$CARGO_HOME/credentials.toml`; a key
+/// is taken from the first file that sets it. `CARGO_REGISTRIES_<NAME>_INDEX`,
+/// `CARGO_REGISTRIES_<NAME>_TOKEN` and `CARGO_REGISTRY_TOKEN` win over the
+/// files.
+#[derive(Debug, Clone, Default)]
+pub struct CargoConfig {
+    /// `[registries.<name>]`
+    registries: HashMap<String, RegistryEntry>,
+    /// `[source.<name>]`
+    sources: HashMap<String, SourceEntry>,
+    /// `[registry] token`, for crates.io
+    crates_io_token: Option<String>,
+    /// `CARGO_REGISTRIES_*` and `CARGO_REGISTRY_TOKEN`
+    env: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct RegistryEntry {
+    index: Option<String>,
+    token: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct SourceEntry {
+    replace_with: Option<String>,
+    registry: Option<String>,
+    local_registry: Option<PathBuf>,
+    directory: Option<PathBuf>,
+}
+
+impl CargoConfig {
+    /// Load the config files that apply to `project_dir` (only the ones in
+    /// `$CARGO_HOME` without it) and the environment
+    pub fn discover(project_dir: Option<&Path>) -> Result<Self> {
+        let cargo_home = env::var_os("CARGO_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cargo")));
+
+        let mut paths = Vec::new();
+        if let Some(dir) = project_dir {
+            let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+            for ancestor in dir.ancestors() {
+                paths.extend(first_existing(
+                    &ancestor.join(".cargo"),
+                    &["config.toml", "config"],
+                ));
+            }
+        }
+        if let Some(home) = &cargo_home {
+            for names in [
+                &["config.toml", "config"],
+                &["credentials.toml", "credentials"],
+            ] {
+                if let Some(path) = first_existing(home, names)
+                    && !paths.contains(&path)
+                {
+                    paths.push(path);
+                }
+            }
+        }
+
+        let env = env::vars()
+            .filter(|(key, _)| {
+                key.starts_with("CARGO_REGISTRIES_") || key == "CARGO_REGISTRY_TOKEN"
+            })
+            .collect();
+        Self::from_files(&paths, env)
+    }
+
+    /// Merge config files, most specific first
+    pub fn from_files(paths: &[PathBuf], env: HashMap<String, String>) -> Result<Self> {
+        let mut config = Self {
+            env,
+            ..Self::default()
+        };
+        for path in paths {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let parsed: Value = toml::from_str(&content)
+                .with_context(|| format!("Failed to parse TOML in {}", path.display()))?;
+            // Paths are relative to the directory holding `.cargo`
+            let base = path
+                .parent()
+                .and_then(Path::parent)
+                .unwrap_or(Path::new("."));
+            config.merge(&parsed, base);
+        }
+        Ok(config)
+    }
+
+    fn merge(&mut self, parsed: &Value, base: &Path) {
+        let string =
+            |table: &Value, key: &str| table.get(key).and_then(Value::as_str).map(String::from);
+
+        if let Some(token) = parsed.get("registry").and_then(|r| string(r, "token")) {
+            self.crates_io_token.get_or_insert(token);
+        }
+        for (name, table) in parsed
+            .get("registries")
+            .and_then(Value::as_table)
+            .into_iter()
+            .flatten()
+        {
+            let entry = self.registries.entry(name.clone()).or_default();
+            if entry.index.is_none() {
+                entry.index = string(table, "index");
+            }
+            if entry.token.is_none() {
+                entry.token = string(table, "token");
+            }
+        }
+        for (name, table) in parsed
+            .get("source")
+            .and_then(Value::as_table)
+            .into_iter()
+            .flatten()
+        {
+            let entry = self.sources.entry(name.clone()).or_default();
+            if entry.replace_with.is_none() {
+                entry.replace_with = string(table, "replace-with");
+            }
+            if entry.registry.is_none() {
+                entry.registry = string(table, "registry");
+            }
+            if entry.local_registry.is_none() {
+                entry.local_registry = string(table, "local-registry").map(|p| base.join(p));
+            }
+            if entry.directory.is_none() {
+                entry.directory = string(table, "directory").map(|p| base.join(p));
+            }
+        }
+    }
+
+    /// Where crates from `registry` (crates.io for `None`) are read from,
+    /// after source replacement
+    pub fn source(&self, registry: Option<&str>) -> Result<RegistrySource, String> {
+        let mut name = registry.unwrap_or(CRATES_IO).to_string();
+        let mut replaced = Vec::new();
+        while let Some(next) = self.sources.get(&name).and_then(|s| s.replace_with.clone()) {
+            if replaced.contains(&next) {
+                return Err(format!(
+                    "source replacement of '{name}' loops back to '{next}'"
+                ));
+            }
+            replaced.push(name);
+            name = next;
+        }
+
+        if let Some(source) = self.sources.get(&name) {
+            if let Some(path) = &source.local_registry {
+                return Ok(RegistrySource::Local {
+                    name,
+                    path: path.clone(),
+                });
+            }
+            if let Some(path) = &source.directory {
+                return Ok(RegistrySource::Directory {
+                    name,
+                    path: path.clone(),
+                });
+            }
+            if let Some(url) = &source.registry {
+                let token = self.token_for_index(url);
+                return Ok(remote(name, url, token));
+            }
+        }
+
+        if name == CRATES_IO {
+            let token = self
+                .env
+                .get("CARGO_REGISTRY_TOKEN")
+                .or(self.crates_io_token.as_ref());
+            return Ok(RegistrySource::Sparse {
+                name: "crates.io".to_string(),
+                url: CRATES_IO_INDEX.to_string(),
+                token: token.cloned(),
+            });
+        }
+
+        let key = env_key(&name);
+        let index = self
+            .env
+            .get(&format!("CARGO_REGISTRIES_{key}_INDEX"))
+            .or_else(|| self.registries.get(&name)?.index.as_ref())
+            .cloned();
+        match index {
+            Some(url) => {
+                let token = self.token(&name);
+                Ok(remote(name, &url, token))
+            }
+            None => Err(format!(
+                "registry '{name}' isn't configured: add `[registries.{name}] index = \"sparse+https://...\"` \
+                 to .cargo/config.toml or set CARGO_REGISTRIES_{key}_INDEX"
+            )),
+        }
+    }
+
+    /// Token for a registry named in `[registries]`
+    fn token(&self, name: &str) -> Option<String> {
+        self.env
+            .get(&format!("CARGO_REGISTRIES_{}_TOKEN", env_key(name)))
+            .or_else(|| self.registries.get(name)?.token.as_ref())
+            .cloned()
+    }
+
+    /// Token of the registry whose index is `url`, for a replacement source
+    fn token_for_index(&self, url: &str) -> Option<String> {
+        self.registries
+            .iter()
+            .find(|(_, entry)| entry.index.as_deref() == Some(url))
+            .and_then(|(name, _)| self.token(name))
+    }
+}
+
+fn remote(name: String, url: &str, token: Option<String>) -> RegistrySource {
+    match url.strip_prefix("sparse+") {
+        Some(url) => RegistrySource::Sparse {
+            name,
+            url: url.trim_end_matches('/').to_string(),
+            token,
+        },
+        None => RegistrySource::Git {
+            name,
+            url: url.strip_prefix("registry+").unwrap_or(url).to_string(),
+        },
+    }
+}
+
+/// `my-registry` → `MY_REGISTRY`, as in `CARGO_REGISTRIES_MY_REGISTRY_INDEX`
+fn env_key(name: &str) -> String {
+    name.to_ascii_uppercase().replace('-', "_")
+}
+
+fn first_existing(dir: &Path, names: &[&str]) -> Option<PathBuf> {
+    names
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(files: &[(&str, &str)], env: &[(&str, &str)]) -> (tempfile::TempDir, CargoConfig) {
+        let dir = tempfile::tempdir().unwrap();
+        let paths: Vec<PathBuf> = files
+            .iter()
+            .map(|(name, content)| {
+                let path = dir.path().join(name);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(&path, content).unwrap();
+                path
+            })
+            .collect();
+        let env = env
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect();
+        let config = CargoConfig::from_files(&paths, env).unwrap();
+        (dir, config)
+    }
+
+    #[test]
+    fn test_named_registries() {
+        let (_dir, config) = config(
+            &[
+                (
+                    "project/.cargo/config.toml",
+                    r#"
+                    [registries.acme]
+                    index = "sparse+https://cargo.acme.dev/index/"
+                    "#,
+                ),
+                (
+                    "home/config.toml",
+                    r#"
+                    [registries.acme]
+                    index = "sparse+https://ignored.example.com/"
+                    [registries.legacy]
+                    index = "https://git.acme.dev/crates-index.git"
+                    "#,
+                ),
+                (
+                    "home/credentials.toml",
+                    r#"
+                    [registries.acme]
+                    token = "from-credentials"
+                    "#,
+                ),
+            ],
+            &[(
+                "CARGO_REGISTRIES_MY_MIRROR_INDEX",
+                "sparse+https://mirror.example.com",
+            )],
+        );
+
+        assert_eq!(
+            config.source(Some("acme")),
+            Ok(RegistrySource::Sparse {
+                name: "acme".to_string(),
+                url: "https://cargo.acme.dev/index".to_string(),
+                token: Some("from-credentials".to_string()),
+            })
+        );
+        assert_eq!(
+            config.source(Some("my-mirror")),
+            Ok(RegistrySource::Sparse {
+                name: "my-mirror".to_string(),
+                url: "https://mirror.example.com".to_string(),
+                token: None,
+            })
+        );
+        assert!(matches!(
+            config.source(Some("legacy")),
+            Ok(RegistrySource::Git { .. })
+        ));
+        assert!(
+            config
+                .source(Some("nope"))
+                .unwrap_err()
+                .contains("CARGO_REGISTRIES_NOPE_INDEX")
+        );
+        assert!(matches!(
+            config.source(None),
+            Ok(RegistrySource::Sparse { ref url, .. }) if url == CRATES_IO_INDEX
+        ));
+    }
+
+    #[test]
+    fn test_source_replacement() {
+        let (dir, config) = config(
+            &[(
+                "project/.cargo/config.toml",
+                r#"
+                [source.crates-io]
+                replace-with = "vendored-sources"
+
+                [source.vendored-sources]
+                directory = "vendor"
+
+                [source.acme]
+                replace-with = "acme-local"
+
+                [source.acme-local]
+                local-registry = "/srv/acme-registry"
+
+                [source.loop-a]
+                replace-with = "loop-b"
+
+                [source.loop-b]
+                replace-with = "loop-a"
+                "#,
+            )],
+            &[("CARGO_REGISTRY_TOKEN", "unused")],
+        );
+
+        assert_eq!(
+            config.source(None),
+            Ok(RegistrySource::Directory {
+                name: "vendored-sources".to_string(),
+                path: dir.path().join("project/vendor"),
+            })
+        );
+        assert_eq!(
+            config.source(Some("acme")),
+            Ok(RegistrySource::Local {
+                name: "acme-local".to_string(),
+                path: PathBuf::from("/srv/acme-registry"),
+            })
+        );
+        assert!(config.source(Some("loop-a")).unwrap_err().contains("loops"));
+    }
+}
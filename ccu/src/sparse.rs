@@ -0,0 +1,83 @@
+use serde::Deserialize;
+
+/// The crates.io sparse index
+pub const CRATES_IO_INDEX: &str = "https://index.crates.io";
+
+/// `config.json` at the root of a sparse index
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct IndexConfig {
+    /// Where crate files are downloaded from
+    pub dl: String,
+    /// Base of the registry's web API, if it has one
+    pub api: Option<String>,
+    /// Whether every request needs a token (RFC 3139)
+    #[serde(default, rename = "auth-required")]
+    pub auth_required: bool,
+}
+
+/// One published version: a line of a crate's index file
+#[derive(Debug, Clone, Deserialize)]
+pub struct IndexEntry {
+    pub name: String,
+    pub vers: String,
+    #[serde(default)]
+    pub yanked: bool,
+    /// The version's `package.rust-version`
+    pub rust_version: Option<String>,
+    /// When the version was published; only in recent indexes
+    pub pubtime: Option<String>,
+}
+
+/// Path of a crate's file in the index: `1/a`, `2/ab`, `3/a/abc`, or
+/// `se/rd/serde`, all lowercase
+pub fn index_path(name: &str) -> String {
+    let name = name.to_ascii_lowercase();
+    let chars: Vec<char> = name.chars().collect();
+    let part = |range: std::ops::Range<usize>| chars[range].iter().collect::<String>();
+    match chars.len() {
+        0..=2 => format!("{}/{name}", chars.len()),
+        3 => format!("3/{}/{name}", part(0..1)),
+        _ => format!("{}/{}/{name}", part(0..2), part(2..4)),
+    }
+}
+
+/// The versions in a crate's index file, one JSON object per line
+///
+/// Lines that don't parse are skipped, as Cargo does, so entries written by
+/// a newer index format don't hide the rest.
+pub fn parse_entries(body: &str) -> Vec<IndexEntry> {
+    body.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_path() {
+        assert_eq!(index_path("a"), "1/a");
+        assert_eq!(index_path("xz"), "2/xz");
+        assert_eq!(index_path("Syn"), "3/s/syn");
+        assert_eq!(index_path("serde_json"), "se/rd/serde_json");
+    }
+
+    #[test]
+    fn test_parse_entries() {
+        let entries = parse_entries(concat!(
+            r#"{"name":"anyhow","vers":"1.0.99","deps":[],"cksum":"00","features":{},"yanked":true,"rust_version":"1.39"}"#,
+            "\n",
+            "not json\n",
+            r#"{"name":"anyhow","vers":"1.0.100","deps":[],"cksum":"00","features":{"std":[]},"yanked":false,"v":2,"pubtime":"2025-09-01T00:00:00Z"}"#,
+            "\n",
+        ));
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].yanked);
+        assert_eq!(entries[0].rust_version.as_deref(), Some("1.39"));
+        assert_eq!(entries[1].vers, "1.0.100");
+        assert_eq!(entries[1].rust_version, None);
+        assert_eq!(entries[1].pubtime.as_deref(), Some("2025-09-01T00:00:00Z"));
+    }
+}
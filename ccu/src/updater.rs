@@ -1,4 +1,5 @@
-use check_updates_core::{DependencyCheck, UpdateSeverity};
+use crate::ecosystem::CargoEcosystem;
+use check_updates_core::{DependencyCheck, Ecosystem, UpdateSeverity};
 use anyhow::{Context, Result};
 use std::collections::HashSet;
 use std::fs;
@@ -45,12 +46,9 @@ impl FileUpdater {
             if let Some(spec) = version_spec
                 && spec.is_rewritable() {
                 // Use Cargo-specific serialization (bare version = caret, = for pin, etc.)
-                // Strip build metadata (+...) since it's not valid in Cargo.toml version requirements
-                let new_version = spec.to_cargo_string().unwrap_or_else(|| spec.to_string());
-                let new_version = match new_version.find('+') {
-                    Some(idx) => new_version[..idx].to_string(),
-                    None => new_version,
-                };
+                let new_version = CargoEcosystem
+                    .format_spec(spec)
+                    .unwrap_or_else(|| spec.to_string());
                 file_updates
                     .entry(check.dependency.source_file.clone())
                     .or_default()
@@ -210,10 +208,12 @@ mod tests {
         DependencyCheck {
             dependency: Dependency {
                 name: name.to_string(),
+                registry: None,
+                package: None,
                 version_spec: VersionSpec::parse(spec_str).unwrap(),
                 source_file: path,
                 line_number: 2,
-                original_line: format!("{} = \"{}\"", name, spec_str),
+                original_line: format!("{name} = \"{spec_str}\""),
             },
             installed: Some(Version::from_str(spec_str).unwrap()),
             in_range: Some(target.clone()),
@@ -222,6 +222,12 @@ mod tests {
             target_spec: Some(VersionSpec::parse(target_version).unwrap()),
             severity: Some(severity),
             force_spec: Some(VersionSpec::parse(target_version).unwrap()),
+            held: None,
+            too_new: None,
+            blocked: None,
+            installed_released: None,
+            latest_released: None,
+            cached_at: None,
         }
     }
 
@@ -248,8 +254,8 @@ tokio = "1.0.0"
         updater.apply_updates(&checks, false, false)?; // patch only
 
         let content = fs::read_to_string(&temp_path)?;
-        assert!(content.contains("1.0.200"), "serde should be updated: {}", content);
-        assert!(!content.contains("1.5.0"), "tokio should NOT be updated: {}", content);
+        assert!(content.contains("1.0.200"), "serde should be updated: {content}");
+        assert!(!content.contains("1.5.0"), "tokio should NOT be updated: {content}");
 
         Ok(())
     }
@@ -277,8 +283,89 @@ tokio = "1.0.0"
         updater.apply_updates(&checks, true, false)?; // patch + minor
 
         let content = fs::read_to_string(&temp_path)?;
-        assert!(content.contains("1.0.200"), "serde should be updated: {}", content);
-        assert!(content.contains("1.5.0"), "tokio should be updated: {}", content);
+        assert!(content.contains("1.0.200"), "serde should be updated: {content}");
+        assert!(content.contains("1.5.0"), "tokio should be updated: {content}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_zero_major_minor_bump_needs_force() -> Result<()> {
+        use check_updates_core::{DependencyResolver, PackageInfo};
+        use std::str::FromStr;
+
+        let mut file = NamedTempFile::new()?;
+        writeln!(
+            file,
+            r#"[dependencies]
+tokio-util = "0.4"
+"#
+        )?;
+        file.flush()?;
+
+        let temp_path = file.path().to_path_buf();
+        let versions: Vec<Version> = ["0.4.3", "0.5.0"]
+            .iter()
+            .map(|v| Version::from_str(v))
+            .collect::<Result<_, _>>()?;
+        let info = PackageInfo {
+            name: "tokio-util".to_string(),
+            latest: versions[1].clone(),
+            latest_stable: Some(versions[1].clone()),
+            published: Default::default(),
+            requires: Default::default(),
+            cached_at: None,
+            versions: versions.clone(),
+        };
+        let dependency = Dependency {
+            name: "tokio-util".to_string(),
+            registry: None,
+            package: None,
+            version_spec: CargoEcosystem.parse_spec("0.4")?,
+            source_file: temp_path.clone(),
+            line_number: 2,
+            original_line: "tokio-util = \"0.4\"".to_string(),
+        };
+
+        let resolver = DependencyResolver::new().with_ecosystem(CargoEcosystem);
+        let check = resolver.resolve(&dependency, &info, Some(&versions[0]));
+        assert_eq!(check.severity, Some(UpdateSeverity::Major));
+
+        let updater = FileUpdater::new();
+        let result = updater.apply_updates(std::slice::from_ref(&check), true, false)?;
+        assert!(result.modified_files.is_empty(), "-um must not cross 0.4 → 0.5");
+
+        updater.apply_updates(&[check], true, true)?;
+        let content = fs::read_to_string(&temp_path)?;
+        assert!(content.contains("tokio-util = \"0.5.0\""), "force should update: {content}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_renamed_dependency() -> Result<()> {
+        let mut file = NamedTempFile::new()?;
+        writeln!(
+            file,
+            r#"[dependencies]
+tokio02 = {{ package = "tokio", version = "0.2.0" }}
+tokio = "1.0.0"
+"#
+        )?;
+        file.flush()?;
+
+        let temp_path = file.path().to_path_buf();
+        let mut check = create_check("tokio02", "0.2.0", temp_path.clone(), "0.2.25", UpdateSeverity::Patch);
+        check.dependency.package = Some("tokio".to_string());
+
+        FileUpdater::new().apply_updates(&[check], false, false)?;
+
+        let content = fs::read_to_string(&temp_path)?;
+        assert!(
+            content.lines().any(|l| l.starts_with("tokio02") && l.contains("0.2.25")),
+            "the alias should be updated: {content}"
+        );
+        assert!(content.contains(r#"tokio = "1.0.0""#), "tokio should NOT be updated: {content}");
 
         Ok(())
     }
@@ -0,0 +1,434 @@
+use crate::date::Date;
+use crate::ecosystem::Ecosystem;
+use crate::resolver::DependencyResolver;
+use crate::types::{Dependency, DependencyCheck, PackageInfo};
+use crate::version::VersionSpec;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Prefix that marks a comment as an annotation
+pub const MARKER: &str = "check-updates:";
+
+/// package.json has no comments, so annotations live under this top-level key:
+/// `"//check-updates": { "react": "hold <19" }`
+pub const PACKAGE_JSON_KEY: &str = "//check-updates";
+
+/// What an annotation asks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum Directive {
+    /// Don't check the dependency at all
+    Ignore,
+    /// Only consider versions matching this requirement (in the manifest's syntax)
+    Hold(String),
+}
+
+/// An inline `check-updates:` comment attached to a dependency
+///
+/// ```text
+/// requests>=2.28  # check-updates: ignore
+/// # check-updates: hold <3 until 2027-01-01
+/// serde = "1.0"
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub directive: Directive,
+    /// The annotation stops applying after this date
+    pub until: Option<Date>,
+}
+
+impl Annotation {
+    fn is_active(&self, today: Date) -> bool {
+        self.until.is_none_or(|until| today <= until)
+    }
+
+    /// Find an annotation in a trailing comment on `line_number` (1-indexed) or
+    /// on a comment-only line directly above it. Both `#` and `//` comments count.
+    ///
+    /// A marked comment whose directive doesn't parse gives the parse error.
+    pub fn find(content: &str, line_number: usize) -> Option<Result<Self, String>> {
+        let lines: Vec<&str> = content.lines().collect();
+        let line = lines.get(line_number.checked_sub(1)?)?;
+        if let Some(annotation) = Self::from_comment(line) {
+            return Some(annotation);
+        }
+
+        let above = lines.get(line_number.checked_sub(2)?)?.trim_start();
+        if above.starts_with('#') || above.starts_with("//") {
+            Self::from_comment(above)
+        } else {
+            None
+        }
+    }
+
+    fn from_comment(line: &str) -> Option<Result<Self, String>> {
+        let start = line.find(MARKER)?;
+        let before = &line[..start];
+        if !before.contains('#') && !before.contains("//") {
+            return None;
+        }
+        Some(line[start..].parse())
+    }
+}
+
+impl FromStr for Annotation {
+    type Err = String;
+
+    /// Parse `ignore`, `hold <SPEC>`, either optionally followed by `until YYYY-MM-DD`.
+    /// The `check-updates:` prefix is optional.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_prefix(MARKER).unwrap_or(s).trim();
+        let (body, until) = match s.rsplit_once(" until ") {
+            Some((body, date)) => (
+                body.trim(),
+                Some(date.trim().parse().map_err(|e| format!("{e}"))?),
+            ),
+            None => (s, None),
+        };
+
+        let directive = if body == "ignore" {
+            Directive::Ignore
+        } else if let Some(spec) = body.strip_prefix("hold ") {
+            let spec = spec.trim();
+            if spec.is_empty() {
+                return Err("hold needs a version requirement".to_string());
+            }
+            Directive::Hold(spec.to_string())
+        } else {
+            return Err(format!(
+                "unknown annotation '{body}' (expected ignore or hold <SPEC>)"
+            ));
+        };
+
+        Ok(Self { directive, until })
+    }
+}
+
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.directive {
+            Directive::Ignore => write!(f, "ignore")?,
+            Directive::Hold(spec) => write!(f, "hold {spec}")?,
+        }
+        if let Some(until) = self.until {
+            write!(f, " until {until}")?;
+        }
+        Ok(())
+    }
+}
+
+/// An annotation with its `hold` requirement parsed in the manifest's syntax
+#[derive(Debug)]
+struct Entry {
+    annotation: Annotation,
+    hold: Option<VersionSpec>,
+}
+
+impl Entry {
+    /// Fails for a `hold` whose requirement doesn't parse or can't be
+    /// evaluated, which would otherwise hold back every version
+    fn new(annotation: Annotation, ecosystem: &dyn Ecosystem) -> Result<Self, String> {
+        let hold = match &annotation.directive {
+            Directive::Ignore => None,
+            Directive::Hold(spec) => {
+                let parsed = ecosystem
+                    .parse_spec(spec)
+                    .or_else(|_| VersionSpec::parse(spec))
+                    .map_err(|e| format!("invalid hold '{spec}': {e}"))?;
+                if matches!(parsed, VersionSpec::Complex(_)) {
+                    return Err(format!("can't evaluate hold '{spec}'"));
+                }
+                Some(parsed)
+            }
+        };
+        Ok(Self { annotation, hold })
+    }
+}
+
+/// Annotations for a set of parsed dependencies, keyed by where they're declared
+#[derive(Debug)]
+pub struct Annotations {
+    by_line: HashMap<(PathBuf, usize), Entry>,
+    warnings: Vec<String>,
+    today: Date,
+}
+
+impl Annotations {
+    /// Read each dependency's source file once and collect its annotations,
+    /// parsing `hold` requirements with `ecosystem`
+    pub fn scan(dependencies: &[Dependency], ecosystem: &dyn Ecosystem) -> Self {
+        let mut files: HashMap<&Path, Option<String>> = HashMap::new();
+        let mut by_line = HashMap::new();
+        let mut warnings = Vec::new();
+
+        for dep in dependencies {
+            let content = files
+                .entry(dep.source_file.as_path())
+                .or_insert_with(|| fs::read_to_string(&dep.source_file).ok());
+            let Some(content) = content else {
+                continue;
+            };
+
+            let annotation = if dep.source_file.extension().is_some_and(|e| e == "json") {
+                Self::from_package_json(content, &dep.name)
+            } else {
+                Annotation::find(content, dep.line_number)
+            };
+            match annotation.map(|a| a.and_then(|a| Entry::new(a, ecosystem))) {
+                Some(Ok(entry)) => {
+                    by_line.insert((dep.source_file.clone(), dep.line_number), entry);
+                }
+                Some(Err(e)) => warnings.push(format!(
+                    "{}:{}: ignoring check-updates annotation: {e}",
+                    dep.source_file.display(),
+                    dep.line_number
+                )),
+                None => {}
+            }
+        }
+
+        Self {
+            by_line,
+            warnings,
+            today: Date::today(),
+        }
+    }
+
+    fn from_package_json(content: &str, name: &str) -> Option<Result<Annotation, String>> {
+        let root: serde_json::Value = serde_json::from_str(content).ok()?;
+        let value = root.get(PACKAGE_JSON_KEY)?.get(name)?;
+        Some(match value.as_str() {
+            Some(annotation) => annotation.parse(),
+            None => Err(format!("expected a string, found {value}")),
+        })
+    }
+
+    /// Annotations that were found but couldn't be parsed, as `file:line: error`
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    fn get(&self, dep: &Dependency) -> Option<&Entry> {
+        self.by_line
+            .get(&(dep.source_file.clone(), dep.line_number))
+            .filter(|e| e.annotation.is_active(self.today))
+    }
+
+    /// Check if a dependency is marked `ignore`
+    pub fn is_ignored(&self, dep: &Dependency) -> bool {
+        self.get(dep)
+            .is_some_and(|e| e.annotation.directive == Directive::Ignore)
+    }
+
+    /// Cap a check to the versions its `hold` allows
+    pub fn apply(
+        &self,
+        check: DependencyCheck,
+        info: &PackageInfo,
+        resolver: &DependencyResolver,
+    ) -> DependencyCheck {
+        let Some(Entry {
+            annotation,
+            hold: Some(spec),
+        }) = self.get(&check.dependency)
+        else {
+            return check;
+        };
+        let ecosystem = resolver.ecosystem();
+
+        resolver.restrict(
+            check,
+            info,
+            |v| ecosystem.satisfies(spec, v),
+            annotation.to_string(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecosystem::GenericEcosystem;
+    use crate::version::Version;
+
+    fn dependency(path: &Path, name: &str, line_number: usize) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            registry: None,
+            package: None,
+            version_spec: VersionSpec::parse(">=1.0.0").unwrap(),
+            source_file: path.to_path_buf(),
+            line_number,
+            original_line: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_annotation() {
+        let a: Annotation = "check-updates: ignore".parse().unwrap();
+        assert_eq!(a.directive, Directive::Ignore);
+        let a: Annotation = "hold <3 until 2027-01-01".parse().unwrap();
+        assert_eq!(a.directive, Directive::Hold("<3".to_string()));
+        assert_eq!(a.until, Some("2027-01-01".parse().unwrap()));
+        assert_eq!(a.to_string(), "hold <3 until 2027-01-01");
+        assert!("hold".parse::<Annotation>().is_err());
+        assert!("freeze".parse::<Annotation>().is_err());
+        assert!("ignore until someday".parse::<Annotation>().is_err());
+    }
+
+    #[test]
+    fn test_find_in_comments() {
+        let content = "\
+requests>=2.28  # check-updates: ignore
+# check-updates: hold <3
+urllib3>=1.26
+flask>=2.0  # pinned by ops
+serde = \"1\"  // check-updates: ignore until 2027-01-01
+version = \"check-updates: ignore\"
+";
+        assert_eq!(
+            Annotation::find(content, 1).unwrap().unwrap().directive,
+            Directive::Ignore
+        );
+        assert_eq!(
+            Annotation::find(content, 3).unwrap().unwrap().directive,
+            Directive::Hold("<3".to_string())
+        );
+        assert!(Annotation::find(content, 4).is_none());
+        assert!(Annotation::find(content, 5).unwrap().unwrap().until.is_some());
+        // Not inside a comment
+        assert!(Annotation::find(content, 6).is_none());
+        assert!(Annotation::find(content, 0).is_none());
+    }
+
+    #[test]
+    fn test_scan_and_apply() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let requirements = dir.path().join("requirements.txt");
+        fs::write(
+            &requirements,
+            "urllib3>=1.26.0  # check-updates: hold <2\nold>=1.0.0  # check-updates: ignore until 2020-01-01\n\
+             six>=1.0.0  # check-updates: hodl ^1\nidna>=3.0  # check-updates: ignore until soon\n",
+        )?;
+        let package_json = dir.path().join("package.json");
+        fs::write(
+            &package_json,
+            r#"{"dependencies": {"left-pad": "^1.0.0"}, "//check-updates": {"left-pad": "ignore"}}"#,
+        )?;
+
+        let urllib3 = dependency(&requirements, "urllib3", 1);
+        let old = dependency(&requirements, "old", 2);
+        let left_pad = dependency(&package_json, "left-pad", 1);
+        let six = dependency(&requirements, "six", 3);
+        let idna = dependency(&requirements, "idna", 4);
+        let annotations = Annotations::scan(
+            &[
+                urllib3.clone(),
+                old.clone(),
+                left_pad.clone(),
+                six.clone(),
+                idna,
+            ],
+            &GenericEcosystem,
+        );
+
+        assert!(annotations.is_ignored(&left_pad));
+        assert!(!annotations.is_ignored(&old), "expired annotation");
+        assert!(!annotations.is_ignored(&urllib3));
+        assert!(!annotations.is_ignored(&six));
+
+        // Marked comments that don't parse are reported, not dropped silently
+        let file = requirements.display();
+        assert_eq!(
+            annotations.warnings(),
+            [
+                format!(
+                    "{file}:3: ignoring check-updates annotation: unknown annotation 'hodl ^1' (expected ignore or hold <SPEC>)"
+                ),
+                format!(
+                    "{file}:4: ignoring check-updates annotation: Invalid date 'soon' (expected YYYY-MM-DD)"
+                ),
+            ]
+        );
+
+        let versions: Vec<Version> = ["1.26.0", "1.26.18", "2.2.1"]
+            .iter()
+            .map(|v| v.parse())
+            .collect::<Result<_, _>>()?;
+        let info = PackageInfo {
+            name: "urllib3".to_string(),
+            latest: versions[2].clone(),
+            latest_stable: Some(versions[2].clone()),
+            published: Default::default(),
+            requires: Default::default(),
+            cached_at: None,
+            versions,
+        };
+        let resolver = DependencyResolver::new();
+        let check = resolver.resolve(&urllib3, &info, None);
+        let check = annotations.apply(check, &info, &resolver);
+        assert_eq!(
+            check.target.map(|v| v.to_string()).as_deref(),
+            Some("1.26.18")
+        );
+        assert_eq!(
+            check.force_spec.map(|s| s.to_string()).as_deref(),
+            Some(">=1.26.18")
+        );
+        assert_eq!(check.held.as_deref(), Some("hold <2"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unusable_holds_are_skipped() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let requirements = dir.path().join("requirements.txt");
+        fs::write(
+            &requirements,
+            "six>=1.0.0  # check-updates: hold <one\nidna>=3.0  # check-updates: hold >=1.4,<2,!=1.5\n",
+        )?;
+        let six = dependency(&requirements, "six", 1);
+        let idna = dependency(&requirements, "idna", 2);
+        let annotations = Annotations::scan(&[six.clone(), idna.clone()], &GenericEcosystem);
+
+        let file = requirements.display();
+        assert_eq!(
+            annotations.warnings(),
+            [
+                format!(
+                    "{file}:1: ignoring check-updates annotation: invalid hold '<one': Invalid version string: one"
+                ),
+                format!(
+                    "{file}:2: ignoring check-updates annotation: can't evaluate hold '>=1.4,<2,!=1.5'"
+                ),
+            ]
+        );
+
+        // Neither hold is applied, so both resolve as usual
+        let versions: Vec<Version> = ["1.0.0", "1.4.0", "1.5.0", "3.1.0"]
+            .iter()
+            .map(|v| v.parse())
+            .collect::<Result<_, _>>()?;
+        let info = PackageInfo {
+            name: "idna".to_string(),
+            latest: versions[3].clone(),
+            latest_stable: Some(versions[3].clone()),
+            published: Default::default(),
+            requires: Default::default(),
+            cached_at: None,
+            versions,
+        };
+        let resolver = DependencyResolver::new();
+        for dep in [&six, &idna] {
+            let unheld = resolver.resolve(dep, &info, None);
+            let check = annotations.apply(unheld.clone(), &info, &resolver);
+            assert!(check.held.is_none());
+            assert!(check.target.is_some());
+            assert_eq!(check.target, unheld.target);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+use crate::date::{Age, Timestamp};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Overrides the cache location (mainly for tests and CI caching)
+pub const CACHE_DIR_ENV: &str = "CHECK_UPDATES_CACHE_DIR";
+
+/// How long a cached response is used without asking the registry
+pub const DEFAULT_TTL: Age = Age { seconds: 3600 };
+
+/// Cached metadata older than this is flagged as stale in the output
+pub const STALE_AFTER: Age = Age {
+    seconds: 7 * 86_400,
+};
+
+/// A cached registry response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// The request this answers, see `HttpCache::key`
+    pub key: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// When the registry last confirmed this body (seconds since the epoch)
+    pub fetched_at: i64,
+    pub body: String,
+}
+
+impl CacheEntry {
+    pub fn fetched(&self) -> Timestamp {
+        Timestamp(self.fetched_at)
+    }
+}
+
+/// On-disk HTTP response cache shared by pcu, ccu and ncu, keyed by request
+///
+/// Lives in `$XDG_CACHE_HOME/check-updates` (or the platform equivalent)
+/// unless `CHECK_UPDATES_CACHE_DIR` is set. Each entry keeps the
+/// `ETag`/`Last-Modified` validators so stale entries can be revalidated
+/// with a conditional request.
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    dir: PathBuf,
+    ttl: Age,
+    refresh: bool,
+    offline: bool,
+}
+
+impl HttpCache {
+    /// The default cache directory, or `None` if the platform has none
+    pub fn new() -> Option<Self> {
+        let dir = match std::env::var_os(CACHE_DIR_ENV) {
+            Some(dir) => PathBuf::from(dir),
+            None => dirs::cache_dir()?.join("check-updates"),
+        };
+        Some(Self::with_dir(dir))
+    }
+
+    pub fn with_dir(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl: DEFAULT_TTL,
+            refresh: false,
+            offline: false,
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: Age) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Revalidate every entry with the registry, ignoring the TTL
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Answer every request from the cache, however old, and never touch the network
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Whether an entry can be used without contacting the registry
+    pub fn is_fresh(&self, entry: &CacheEntry, now: Timestamp) -> bool {
+        self.offline || (!self.refresh && !self.ttl.has_passed(entry.fetched(), now))
+    }
+
+    /// Key for the response to a request
+    ///
+    /// The same URL can answer differently per `Accept` (JSON or HTML from
+    /// one index) and per credentials, so both are part of the key. Only a
+    /// hash of the credentials is kept.
+    pub fn key(url: &str, accept: Option<&str>, credentials: Option<&str>) -> String {
+        let accept = accept.map(|a| format!(" accept={a}")).unwrap_or_default();
+        let auth = credentials
+            .map(|c| format!(" auth={:016x}", fnv1a(c.as_bytes())))
+            .unwrap_or_default();
+        format!("{url}{accept}{auth}")
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a(key.as_bytes())))
+    }
+
+    /// Look up the entry for a key
+    pub fn get(&self, key: &str) -> Option<CacheEntry> {
+        let content = fs::read_to_string(self.path(key)).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&content).ok()?;
+        // Guard against hash collisions
+        (entry.key == key).then_some(entry)
+    }
+
+    /// Store an entry, replacing any previous one atomically
+    pub fn store(&self, entry: &CacheEntry) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path(&entry.key);
+        let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+        fs::write(&tmp, serde_json::to_vec(entry)?)?;
+        fs::rename(&tmp, &path)
+    }
+}
+
+/// 64-bit FNV-1a, stable across Rust releases (unlike `DefaultHasher`)
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str, fetched_at: i64) -> CacheEntry {
+        CacheEntry {
+            key: key.to_string(),
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+            fetched_at,
+            body: "{}".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_store_and_get() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let cache = HttpCache::with_dir(dir.path().join("nested"));
+        let serde = entry("https://crates.io/api/v1/crates/serde", 100);
+        cache.store(&serde)?;
+
+        assert_eq!(cache.get(&serde.key), Some(serde));
+        assert!(cache.get("https://crates.io/api/v1/crates/tokio").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_key() {
+        let url = "https://pypi.org/simple/requests/";
+        let json = Some("application/vnd.pypi.simple.v1+json");
+        assert_eq!(HttpCache::key(url, None, None), url);
+        assert_ne!(HttpCache::key(url, json, None), HttpCache::key(url, None, None));
+
+        let alice = HttpCache::key(url, None, Some("alice:secret"));
+        assert!(!alice.contains("secret"));
+        assert_ne!(alice, HttpCache::key(url, None, None));
+        assert_ne!(alice, HttpCache::key(url, None, Some("bob:secret")));
+    }
+
+    #[test]
+    fn test_freshness() {
+        let cache = HttpCache::with_dir("unused").with_ttl("1h".parse().unwrap());
+        let cached = entry("https://pypi.org/pypi/requests/json", 0);
+        assert!(cache.is_fresh(&cached, Timestamp(3599)));
+        assert!(!cache.is_fresh(&cached, Timestamp(3600)));
+        assert!(!cache.clone().with_refresh(true).is_fresh(&cached, Timestamp(1)));
+        assert!(cache.with_offline(true).is_fresh(&cached, Timestamp(1 << 40)));
+    }
+}
@@ -0,0 +1,440 @@
+use crate::date::{Age, Date};
+use crate::registry::RegistryUrl;
+use crate::resolver::DependencyResolver;
+use crate::types::{DependencyCheck, PackageInfo, UpdateSeverity};
+use crate::version::Version;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Standalone config file, looked up in the project root
+pub const CONFIG_FILE: &str = ".check-updates.toml";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid config in {path}: {message}")]
+    Invalid { path: PathBuf, message: String },
+}
+
+/// A manifest table that can hold the config instead of `.check-updates.toml`
+#[derive(Debug, Clone, Copy)]
+pub struct ManifestSection {
+    /// Manifest file name (`pyproject.toml`, `package.json`, ...)
+    pub file: &'static str,
+    /// Keys leading to the config table
+    pub path: &'static [&'static str],
+}
+
+/// Flags applied as if passed on the command line
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Defaults {
+    pub minor: bool,
+    pub force: bool,
+    pub pre_release: bool,
+    pub fail_on: Option<UpdateSeverity>,
+    pub min_age: Option<Age>,
+    pub cache_ttl: Option<Age>,
+    /// Registry or mirror to query instead of the public one
+    pub registry: Option<RegistryUrl>,
+}
+
+/// Policy for packages matching a name or glob
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PackageRule {
+    /// Never propose an update more severe than this
+    pub max_severity: Option<UpdateSeverity>,
+    /// Stay on this major version
+    pub hold: Option<u64>,
+    /// Shown next to held packages
+    pub reason: Option<String>,
+    /// The rule stops applying after this date
+    pub until: Option<Date>,
+}
+
+impl PackageRule {
+    fn is_active(&self, today: Date) -> bool {
+        self.until.is_none_or(|until| today <= until)
+    }
+
+    fn allows(
+        &self,
+        version: &Version,
+        current: Option<&Version>,
+        resolver: &DependencyResolver,
+    ) -> bool {
+        if let Some(major) = self.hold
+            && version.major() > major
+        {
+            return false;
+        }
+        if let Some(max) = self.max_severity
+            && let Some(current) = current
+            && let Some(severity) = resolver.ecosystem().severity(current, version)
+        {
+            return max.is_at_least(severity);
+        }
+        true
+    }
+
+    fn describe(&self) -> String {
+        let mut text = match (self.hold, self.max_severity) {
+            (Some(major), _) => format!("held at {major}.x"),
+            (None, Some(UpdateSeverity::Major)) | (None, None) => "held".to_string(),
+            (None, Some(UpdateSeverity::Minor)) => "minor updates only".to_string(),
+            (None, Some(UpdateSeverity::Patch)) => "patch updates only".to_string(),
+        };
+        if let Some(reason) = &self.reason {
+            text.push_str(": ");
+            text.push_str(reason);
+        }
+        if let Some(until) = self.until {
+            text.push_str(&format!(" (until {until})"));
+        }
+        text
+    }
+}
+
+/// Project configuration shared by pcu, ccu and ncu
+///
+/// ```toml
+/// ignore = ["internal-*"]
+///
+/// [defaults]
+/// minor = true
+///
+/// [packages.react]
+/// hold = 18
+/// reason = "waiting on next.js"
+/// until = 2027-01-01
+///
+/// [packages."@types/*"]
+/// max-severity = "minor"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// Packages to skip entirely (names or globs)
+    pub ignore: Vec<String>,
+    /// If non-empty, only packages matching one of these are checked
+    pub include: Vec<String>,
+    pub defaults: Defaults,
+    /// Rules keyed by package name or glob; exact names win over globs
+    pub packages: BTreeMap<String, PackageRule>,
+}
+
+impl Config {
+    /// Load `.check-updates.toml` from `dir`, falling back to the first manifest
+    /// section present. Returns the default (empty) config if neither exists.
+    pub fn load(dir: &Path, sections: &[ManifestSection]) -> Result<Self, ConfigError> {
+        let path = dir.join(CONFIG_FILE);
+        if path.exists() {
+            let content = read(&path)?;
+            return toml::from_str(&content).map_err(|e| invalid(&path, e));
+        }
+
+        for section in sections {
+            let path = dir.join(section.file);
+            if !path.exists() {
+                continue;
+            }
+            let content = read(&path)?;
+            let config = if section.file.ends_with(".json") {
+                let root: serde_json::Value =
+                    serde_json::from_str(&content).map_err(|e| invalid(&path, e))?;
+                section
+                    .path
+                    .iter()
+                    .try_fold(&root, |v, key| v.get(key))
+                    .map(Self::deserialize)
+                    .transpose()
+                    .map_err(|e| invalid(&path, e))?
+            } else {
+                let root: toml::Value = toml::from_str(&content).map_err(|e| invalid(&path, e))?;
+                section
+                    .path
+                    .iter()
+                    .try_fold(&root, |v, key| v.get(key))
+                    .map(|table| table.clone().try_into())
+                    .transpose()
+                    .map_err(|e| invalid(&path, e))?
+            };
+            if let Some(config) = config {
+                return Ok(config);
+            }
+        }
+
+        Ok(Self::default())
+    }
+
+    /// Check if a package is excluded by `ignore` or `include`
+    pub fn is_ignored(&self, name: &str) -> bool {
+        self.ignore.iter().any(|p| glob_match(p, name))
+            || (!self.include.is_empty() && !self.include.iter().any(|p| glob_match(p, name)))
+    }
+
+    /// The rule for a package: an exact name beats a glob
+    pub fn rule(&self, name: &str) -> Option<&PackageRule> {
+        self.packages.get(name).or_else(|| {
+            self.packages
+                .iter()
+                .find(|(pattern, _)| glob_match(pattern, name))
+                .map(|(_, rule)| rule)
+        })
+    }
+
+    /// Re-resolve a check with the versions its rule allows
+    ///
+    /// Both the displayed target and the specs written by `-u`/`-uf` are
+    /// capped. `latest` keeps the registry's latest so the table still shows
+    /// what is being held back.
+    pub fn apply(
+        &self,
+        check: DependencyCheck,
+        info: &PackageInfo,
+        resolver: &DependencyResolver,
+    ) -> DependencyCheck {
+        self.apply_on(check, info, resolver, Date::today())
+    }
+
+    fn apply_on(
+        &self,
+        check: DependencyCheck,
+        info: &PackageInfo,
+        resolver: &DependencyResolver,
+        today: Date,
+    ) -> DependencyCheck {
+        let Some(rule) = self.rule(&check.dependency.name).filter(|r| r.is_active(today)) else {
+            return check;
+        };
+
+        let current = check.current_version().cloned();
+        resolver.restrict(
+            check,
+            info,
+            |v| rule.allows(v, current.as_ref(), resolver),
+            rule.describe(),
+        )
+    }
+}
+
+fn read(path: &Path) -> Result<String, ConfigError> {
+    fs::read_to_string(path).map_err(|source| ConfigError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn invalid(path: &Path, error: impl std::fmt::Display) -> ConfigError {
+    ConfigError::Invalid {
+        path: path.to_path_buf(),
+        message: error.to_string(),
+    }
+}
+
+/// Match a package name against a pattern where `*` is any run of characters
+/// and `?` is any single character
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((bp, bn)) => {
+                    p = bp + 1;
+                    n = bn + 1;
+                    backtrack = Some((bp, bn + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Dependency;
+    use crate::version::VersionSpec;
+    use std::str::FromStr;
+
+    fn info(versions: &[&str]) -> PackageInfo {
+        let versions: Vec<Version> = versions.iter().map(|v| Version::from_str(v).unwrap()).collect();
+        PackageInfo {
+            name: "react".to_string(),
+            latest: versions.last().unwrap().clone(),
+            latest_stable: versions.last().cloned(),
+            published: Default::default(),
+            requires: BTreeMap::new(),
+            cached_at: None,
+            versions,
+        }
+    }
+
+    fn resolve(config: &Config, spec: &str, today: &str) -> DependencyCheck {
+        let dependency = Dependency {
+            name: "react".to_string(),
+            registry: None,
+            package: None,
+            version_spec: VersionSpec::parse(spec).unwrap(),
+            source_file: PathBuf::from("package.json"),
+            line_number: 1,
+            original_line: String::new(),
+        };
+        let info = info(&["17.0.0", "17.0.2", "18.0.0", "18.3.1", "19.1.0"]);
+        let resolver = DependencyResolver::new();
+        let check = resolver.resolve(&dependency, &info, None);
+        config.apply_on(check, &info, &resolver, today.parse().unwrap())
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("@types/*", "@types/node"));
+        assert!(glob_match("*-sys", "openssl-sys"));
+        assert!(glob_match("py?aml", "pyyaml"));
+        assert!(!glob_match("@types/*", "typescript"));
+        assert!(glob_match("*", ""));
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config: Config = toml::from_str(
+            r#"
+            ignore = ["internal-*"]
+            include = ["*"]
+
+            [defaults]
+            minor = true
+            fail-on = "major"
+            min-age = "7d"
+
+            [packages.react]
+            hold = 18
+            reason = "waiting on next.js"
+            until = 2027-01-01
+
+            [packages."@types/*"]
+            max-severity = "minor"
+            until = "2026-06-30"
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.defaults.minor);
+        assert_eq!(config.defaults.fail_on, Some(UpdateSeverity::Major));
+        assert_eq!(config.defaults.min_age.map(|a| a.seconds), Some(7 * 86_400));
+        assert!(config.is_ignored("internal-tools"));
+        assert!(!config.is_ignored("react"));
+        let react = config.rule("react").unwrap();
+        assert_eq!(react.until, Some("2027-01-01".parse().unwrap()));
+        assert_eq!(
+            config.rule("@types/node").unwrap().max_severity,
+            Some(UpdateSeverity::Minor)
+        );
+        assert!(toml::from_str::<Config>("ignor = []").is_err());
+    }
+
+    #[test]
+    fn test_include_filter() {
+        let config = Config {
+            include: vec!["serde*".to_string()],
+            ..Config::default()
+        };
+        assert!(!config.is_ignored("serde_json"));
+        assert!(config.is_ignored("tokio"));
+    }
+
+    #[test]
+    fn test_hold_caps_target() {
+        let mut config = Config::default();
+        config.packages.insert(
+            "react".to_string(),
+            PackageRule {
+                hold: Some(18),
+                reason: Some("waiting on next.js".to_string()),
+                until: Some("2027-01-01".parse().unwrap()),
+                ..PackageRule::default()
+            },
+        );
+
+        let check = resolve(&config, ">=17.0.0", "2026-10-16");
+        assert_eq!(check.target.unwrap().to_string(), "17.0.2");
+        assert_eq!(check.force_spec.unwrap().to_string(), ">=18.3.1");
+        assert_eq!(check.latest.to_string(), "19.1.0");
+        assert_eq!(
+            check.held.as_deref(),
+            Some("held at 18.x: waiting on next.js (until 2027-01-01)")
+        );
+
+        // Expired holds no longer apply
+        let check = resolve(&config, ">=17.0.0", "2027-01-02");
+        assert_eq!(check.force_spec.unwrap().to_string(), ">=19.1.0");
+        assert!(check.held.is_none());
+    }
+
+    #[test]
+    fn test_max_severity() {
+        let mut config = Config::default();
+        config.packages.insert(
+            "re*".to_string(),
+            PackageRule {
+                max_severity: Some(UpdateSeverity::Minor),
+                ..PackageRule::default()
+            },
+        );
+
+        let check = resolve(&config, "==18.0.0", "2026-10-16");
+        assert_eq!(check.target.unwrap().to_string(), "18.3.1");
+        assert_eq!(check.severity, Some(UpdateSeverity::Minor));
+        assert_eq!(check.held.as_deref(), Some("minor updates only"));
+    }
+
+    #[test]
+    fn test_load_from_manifest() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let sections = [
+            ManifestSection { file: "Cargo.toml", path: &["package", "metadata", "check-updates"] },
+            ManifestSection { file: "package.json", path: &["check-updates"] },
+        ];
+        assert!(Config::load(dir.path(), &sections)?.ignore.is_empty());
+
+        fs::write(dir.path().join("package.json"), r#"{"check-updates": {"ignore": ["left-pad"]}}"#)?;
+        assert_eq!(Config::load(dir.path(), &sections)?.ignore, vec!["left-pad"]);
+
+        // A manifest without the section falls through to the next one
+        fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"x\"\n")?;
+        assert_eq!(Config::load(dir.path(), &sections)?.ignore, vec!["left-pad"]);
+
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"x\"\n\n[package.metadata.check-updates]\nignore = [\"syn\"]\n",
+        )?;
+        assert_eq!(Config::load(dir.path(), &sections)?.ignore, vec!["syn"]);
+
+        fs::write(dir.path().join(CONFIG_FILE), "include = [\"serde\"]\n")?;
+        let config = Config::load(dir.path(), &sections)?;
+        assert!(config.ignore.is_empty());
+        assert_eq!(config.include, vec!["serde"]);
+        Ok(())
+    }
+}
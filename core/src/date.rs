@@ -0,0 +1,315 @@
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("Invalid date '{0}' (expected YYYY-MM-DD)")]
+pub struct DateError(String);
+
+/// A calendar date (UTC), as used for hold expiry and release ages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Today's date in UTC
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self::from_days(i64::try_from(secs / 86_400).unwrap_or(i64::MAX))
+    }
+
+    /// Date `days` days after 1970-01-01
+    pub fn from_days(days: i64) -> Self {
+        // Howard Hinnant's civil_from_days
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        Self {
+            year: i32::try_from(year).unwrap_or(i32::MAX),
+            month: u32::try_from(month).unwrap_or(1),
+            day: u32::try_from(day).unwrap_or(1),
+        }
+    }
+
+    /// Days since 1970-01-01
+    pub fn to_days(self) -> i64 {
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let month = i64::from(self.month);
+        let mp = if month > 2 { month - 3 } else { month + 9 };
+        let doy = (153 * mp + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    fn days_in_month(year: i32, month: u32) -> u32 {
+        match month {
+            4 | 6 | 9 | 11 => 30,
+            2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+            2 => 28,
+            _ => 31,
+        }
+    }
+}
+
+impl FromStr for Date {
+    type Err = DateError;
+
+    /// Parse `YYYY-MM-DD`, ignoring any trailing time (`2024-05-01T12:00:00Z`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || DateError(s.to_string());
+        let date = s.trim().get(..10).ok_or_else(err)?;
+        let mut parts = date.split('-');
+        let (Some(y), Some(m), Some(d), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(err());
+        };
+        let year: i32 = y.parse().map_err(|_| err())?;
+        let month: u32 = m.parse().map_err(|_| err())?;
+        let day: u32 = d.parse().map_err(|_| err())?;
+
+        if !(1..=12).contains(&month) || day == 0 || day > Self::days_in_month(year, month) {
+            return Err(err());
+        }
+        Ok(Self { year, month, day })
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Seconds since the Unix epoch, for release publish times
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(pub i64);
+
+impl Timestamp {
+    pub fn now() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self(i64::try_from(secs).unwrap_or(i64::MAX))
+    }
+
+    /// Parse an RFC 3339 / ISO 8601 time as returned by registries:
+    /// `2024-05-01T12:00:00Z`, `2024-05-01T12:00:00.123456+00:00`, or
+    /// `2024-05-01T12:00:00` (taken as UTC)
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let date: Date = s.get(..10)?.parse().ok()?;
+        let rest = s.get(10..)?;
+        if rest.is_empty() {
+            return Some(Self(date.to_days() * 86_400));
+        }
+
+        let time = rest.strip_prefix(['T', ' '])?;
+        let hms: Vec<i64> = time
+            .get(..8)?
+            .split(':')
+            .map(|p| p.parse().ok())
+            .collect::<Option<_>>()?;
+        let [h, m, sec] = hms[..] else {
+            return None;
+        };
+
+        // Skip fractional seconds, then read the offset
+        let zone = time[8..].trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+        let offset = match zone {
+            "" | "Z" | "z" => 0,
+            _ => {
+                let sign = match zone.as_bytes()[0] {
+                    b'+' => 1,
+                    b'-' => -1,
+                    _ => return None,
+                };
+                let (oh, om) = zone[1..].split_once(':').unwrap_or((&zone[1..], "0"));
+                sign * (oh.parse::<i64>().ok()? * 3600 + om.parse::<i64>().ok()? * 60)
+            }
+        };
+
+        Some(Self(date.to_days() * 86_400 + h * 3600 + m * 60 + sec - offset))
+    }
+
+    /// Parse an HTTP date (`Retry-After`, `Last-Modified`):
+    /// `Wed, 21 Oct 2015 07:28:00 GMT`
+    pub fn parse_http_date(s: &str) -> Option<Self> {
+        const MONTHS: [&str; 12] = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+        let [_, day, month, year, time, "GMT"] = s.split_whitespace().collect::<Vec<_>>()[..] else {
+            return None;
+        };
+        let month = MONTHS.iter().position(|m| *m == month)? + 1;
+        Self::parse(&format!("{year}-{month:02}-{day:0>2}T{time}Z"))
+    }
+
+    /// The UTC calendar date
+    pub fn date(self) -> Date {
+        Date::from_days(self.0.div_euclid(86_400))
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.date())
+    }
+}
+
+/// A minimum release age such as `7d`, `12h` or `2w`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Age {
+    pub seconds: i64,
+}
+
+impl Age {
+    /// Whether something published at `published` is at least this old at `now`
+    pub fn has_passed(self, published: Timestamp, now: Timestamp) -> bool {
+        now.0 - published.0 >= self.seconds
+    }
+}
+
+impl FromStr for Age {
+    type Err = String;
+
+    /// A number with an optional unit: `m` (minutes), `h` (hours), `d` (days,
+    /// the default) or `w` (weeks)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+            Some(i) => s.split_at(i),
+            None => (s, "d"),
+        };
+        let n: i64 = number
+            .parse()
+            .map_err(|_| format!("invalid age '{s}' (expected e.g. 7d, 12h or 2w)"))?;
+        let unit_seconds = match unit {
+            "m" => 60,
+            "h" => 3600,
+            "d" => 86_400,
+            "w" => 7 * 86_400,
+            _ => return Err(format!("invalid age unit in '{s}' (expected m, h, d or w)")),
+        };
+        Ok(Self {
+            seconds: n.saturating_mul(unit_seconds),
+        })
+    }
+}
+
+impl fmt::Display for Age {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.seconds % 86_400 == 0 {
+            write!(f, "{}d", self.seconds / 86_400)
+        } else if self.seconds % 3600 == 0 {
+            write!(f, "{}h", self.seconds / 3600)
+        } else {
+            write!(f, "{}m", self.seconds / 60)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Age {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct DateVisitor;
+
+        impl<'de> Visitor<'de> for DateVisitor {
+            type Value = Date;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a YYYY-MM-DD date")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Date, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            // TOML dates (`until = 2027-01-01`) arrive as a single-entry map
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Date, A::Error> {
+                let (_, value): (String, String) = map
+                    .next_entry()?
+                    .ok_or_else(|| de::Error::custom("empty date"))?;
+                value.parse().map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_any(DateVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date() {
+        let date: Date = "2027-01-01".parse().unwrap();
+        assert_eq!((date.year, date.month, date.day), (2027, 1, 1));
+        assert_eq!("2024-05-01T12:00:00.000Z".parse::<Date>().unwrap().to_string(), "2024-05-01");
+        assert!("2023-02-29".parse::<Date>().is_err());
+        assert!("2024-13-01".parse::<Date>().is_err());
+        assert!("soon".parse::<Date>().is_err());
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let ts = |s| Timestamp::parse(s).unwrap().0;
+        assert_eq!(ts("1970-01-02T00:00:00Z"), 86_400);
+        assert_eq!(ts("2024-05-01T12:00:00.123456+00:00"), ts("2024-05-01T12:00:00"));
+        assert_eq!(ts("2024-05-01T14:00:00+02:00"), ts("2024-05-01T12:00:00Z"));
+        assert_eq!(Timestamp(ts("2024-05-01T23:59:59Z")).to_string(), "2024-05-01");
+        assert!(Timestamp::parse("yesterday").is_none());
+
+        let http = Timestamp::parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT");
+        assert_eq!(http, Timestamp::parse("2015-10-21T07:28:00Z"));
+        assert!(Timestamp::parse_http_date("Wed, 21 Oct 2015 07:28:00 CEST").is_none());
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!("7d".parse::<Age>().unwrap().seconds, 7 * 86_400);
+        assert_eq!("12h".parse::<Age>().unwrap().seconds, 12 * 3600);
+        assert_eq!("2w".parse::<Age>().unwrap().to_string(), "14d");
+        assert_eq!("3".parse::<Age>().unwrap().to_string(), "3d");
+        assert_eq!("90m".parse::<Age>().unwrap().to_string(), "90m");
+        assert!("7y".parse::<Age>().is_err());
+        assert!("soon".parse::<Age>().is_err());
+
+        let week: Age = "7d".parse().unwrap();
+        assert!(week.has_passed(Timestamp(0), Timestamp(7 * 86_400)));
+        assert!(!week.has_passed(Timestamp(1), Timestamp(7 * 86_400)));
+    }
+
+    #[test]
+    fn test_days_round_trip() {
+        assert_eq!(Date::from_days(0).to_string(), "1970-01-01");
+        let date: Date = "2024-02-29".parse().unwrap();
+        assert_eq!(date.to_days(), 19_782);
+        assert_eq!(Date::from_days(date.to_days()), date);
+        assert!(Date::today().year >= 2024);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::types::UpdateSeverity;
+use crate::version::{Version, VersionError, VersionSpec};
+use std::str::FromStr;
+
+/// Version semantics of a package ecosystem (Cargo, npm, PyPI, ...)
+///
+/// Ecosystems disagree about what the same text means: bare `1.2` is `^1.2`
+/// in Cargo, `1.2.x` in npm and `==1.2` under PEP 440. Each tool implements
+/// this trait and hands it to the `DependencyResolver`; the defaults follow
+/// the generic `VersionSpec` grammar.
+pub trait Ecosystem: Send + Sync {
+    /// Short identifier (`cargo`, `npm`, `pypi`)
+    fn name(&self) -> &'static str;
+
+    /// Parse a version string as published on the registry
+    fn parse_version(&self, s: &str) -> Result<Version, VersionError> {
+        Version::from_str(s)
+    }
+
+    /// Parse a version requirement as written in a manifest
+    fn parse_spec(&self, s: &str) -> Result<VersionSpec, VersionError> {
+        VersionSpec::parse(s)
+    }
+
+    /// Check if a version satisfies a requirement
+    fn satisfies(&self, spec: &VersionSpec, version: &Version) -> bool {
+        spec.satisfies(version)
+    }
+
+    /// Serialize a requirement back into manifest syntax
+    fn format_spec(&self, spec: &VersionSpec) -> Option<String> {
+        Some(spec.to_string())
+    }
+
+    /// Severity of moving from `current` to `target`
+    fn severity(&self, current: &Version, target: &Version) -> Option<UpdateSeverity> {
+        semver_severity(current, target)
+    }
+}
+
+/// Plain major/minor/patch severity, with anything below the patch segment
+/// (4th segments, post releases, pre → final) counted as a patch
+pub fn semver_severity(current: &Version, target: &Version) -> Option<UpdateSeverity> {
+    if target <= current {
+        None
+    } else if !target.same_major(current) {
+        Some(UpdateSeverity::Major)
+    } else if target.minor() != current.minor() {
+        Some(UpdateSeverity::Minor)
+    } else {
+        Some(UpdateSeverity::Patch)
+    }
+}
+
+/// Severity relative to the leftmost non-zero component, for ecosystems whose
+/// caret treats that component as the breaking one (Cargo, npm):
+/// `0.4.3 → 0.5.0` and `0.0.3 → 0.0.4` are major, `0.4.3 → 0.4.5` stays a patch
+pub fn leftmost_nonzero_severity(current: &Version, target: &Version) -> Option<UpdateSeverity> {
+    let severity = semver_severity(current, target)?;
+    let breaking = current.major() == 0
+        && match severity {
+            UpdateSeverity::Minor => true,
+            UpdateSeverity::Patch => current.minor() == 0 && target.patch() != current.patch(),
+            UpdateSeverity::Major => false,
+        };
+    Some(if breaking { UpdateSeverity::Major } else { severity })
+}
+
+/// The generic `VersionSpec` grammar with no ecosystem-specific rules
+pub struct GenericEcosystem;
+
+impl Ecosystem for GenericEcosystem {
+    fn name(&self) -> &'static str {
+        "generic"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> Version {
+        Version::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_semver_severity() {
+        assert_eq!(semver_severity(&v("0.4.3"), &v("0.5.0")), Some(UpdateSeverity::Minor));
+        assert_eq!(semver_severity(&v("1.0.0"), &v("1.0.0")), None);
+    }
+
+    #[test]
+    fn test_leftmost_nonzero_severity() {
+        let sev = leftmost_nonzero_severity;
+        assert_eq!(sev(&v("0.4.3"), &v("0.5.0")), Some(UpdateSeverity::Major));
+        assert_eq!(sev(&v("0.4.3"), &v("0.4.5")), Some(UpdateSeverity::Patch));
+        assert_eq!(sev(&v("0.0.3"), &v("0.0.4")), Some(UpdateSeverity::Major));
+        assert_eq!(sev(&v("0.0.3-beta.1"), &v("0.0.3")), Some(UpdateSeverity::Patch));
+        assert_eq!(sev(&v("1.2.3"), &v("1.3.0")), Some(UpdateSeverity::Minor));
+        assert_eq!(sev(&v("1.2.3"), &v("2.0.0")), Some(UpdateSeverity::Major));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::types::UpdateSeverity;
+
+/// Process exit statuses shared by pcu, ccu and ncu
+///
+/// | Code | Meaning |
+/// |------|---------|
+/// | 0 | Every lookup succeeded and nothing is at or above `--fail-on` |
+/// | 1 | The run aborted (bad path, unparseable manifest, ...) |
+/// | 2 | An update at or above the `--fail-on` severity was found |
+/// | 3 | Some registry lookups failed, with or without `--fail-on` |
+///
+/// A package the registry doesn't have isn't a failed lookup.
+pub mod code {
+    pub const SUCCESS: u8 = 0;
+    pub const ERROR: u8 = 1;
+    pub const OUTDATED: u8 = 2;
+    pub const FETCH_FAILED: u8 = 3;
+}
+
+/// Exit status for a finished run
+///
+/// Failed lookups win over everything else, `--fail-on` or not: a run that
+/// couldn't see some packages can't vouch for them being up to date.
+pub fn exit_status(
+    fail_on: Option<UpdateSeverity>,
+    found: impl IntoIterator<Item = UpdateSeverity>,
+    has_fetch_errors: bool,
+) -> u8 {
+    if has_fetch_errors {
+        return code::FETCH_FAILED;
+    }
+
+    match fail_on {
+        Some(threshold) if found.into_iter().any(|s| s.is_at_least(threshold)) => {
+            code::OUTDATED
+        }
+        _ => code::SUCCESS,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_status_threshold() {
+        use UpdateSeverity::{Major, Minor, Patch};
+
+        assert_eq!(exit_status(None, [Major], false), code::SUCCESS);
+        assert_eq!(exit_status(Some(Minor), [Patch], false), code::SUCCESS);
+        assert_eq!(exit_status(Some(Minor), [Patch, Major], false), code::OUTDATED);
+        assert_eq!(exit_status(Some(Major), [Minor], true), code::FETCH_FAILED);
+    }
+
+    #[test]
+    fn test_fetch_errors_take_precedence() {
+        use UpdateSeverity::{Major, Patch};
+
+        // Without --fail-on, failed lookups still fail the run
+        assert_eq!(exit_status(None, [Major], true), code::FETCH_FAILED);
+        assert_eq!(exit_status(None, [], true), code::FETCH_FAILED);
+        // ... and aren't hidden by an outdated package
+        assert_eq!(exit_status(Some(Patch), [Patch], true), code::FETCH_FAILED);
+    }
+
+    #[test]
+    fn test_parse_severity() {
+        assert_eq!("Minor".parse::<UpdateSeverity>(), Ok(UpdateSeverity::Minor));
+        assert!("huge".parse::<UpdateSeverity>().is_err());
+    }
+}
@@ -0,0 +1,484 @@
+use crate::cache::{CacheEntry, HttpCache};
+use crate::date::Timestamp;
+use crate::retry::{classify, AdaptiveLimit, RetryPolicy, Verdict};
+use reqwest::header::{
+    HeaderMap, ACCEPT, AUTHORIZATION, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::StatusCode;
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Requests in flight per client unless `with_concurrency` says otherwise
+pub const DEFAULT_CONCURRENCY: usize = 10;
+
+#[derive(Debug, Error)]
+pub enum HttpError {
+    #[error(transparent)]
+    Request(#[from] reqwest::Error),
+    /// `--offline` and nothing cached for the URL
+    #[error("not in the offline cache")]
+    NotCached { url: String },
+    /// Still throttled after every retry, or asked to wait longer than we will
+    #[error("rate limited by the registry{}", retry_hint(*retry_after))]
+    RateLimited {
+        url: String,
+        retry_after: Option<Duration>,
+    },
+    /// A `5xx` (or `408`) on every attempt
+    #[error("server error {status} after {}", attempts_text(*attempts))]
+    Server {
+        url: String,
+        status: StatusCode,
+        attempts: u32,
+    },
+    /// Connection failures or timeouts on every attempt
+    #[error("{source} (after {})", attempts_text(*attempts))]
+    Unreachable {
+        source: reqwest::Error,
+        attempts: u32,
+    },
+}
+
+fn retry_hint(retry_after: Option<Duration>) -> String {
+    retry_after
+        .map(|d| format!(" (retry after {}s)", d.as_secs()))
+        .unwrap_or_default()
+}
+
+fn attempts_text(attempts: u32) -> String {
+    if attempts == 1 {
+        "1 attempt".to_string()
+    } else {
+        format!("{attempts} attempts")
+    }
+}
+
+/// Whether an error (or anything in its source chain) is `HttpError::NotCached`
+///
+/// Takes an iterator so that `anyhow::Error::chain()` can be passed directly.
+pub fn is_not_cached<'a>(chain: impl IntoIterator<Item = &'a (dyn Error + 'static)>) -> bool {
+    chain
+        .into_iter()
+        .any(|e| matches!(e.downcast_ref::<HttpError>(), Some(HttpError::NotCached { .. })))
+}
+
+/// Credentials for a private registry
+#[derive(Clone, PartialEq, Eq)]
+pub enum Auth {
+    /// `Authorization: Bearer <token>`
+    Bearer(String),
+    /// `Authorization: Basic <base64(username:password)>`
+    Basic {
+        username: String,
+        password: Option<String>,
+    },
+    /// A complete `Authorization` header value, sent as is
+    Header(String),
+}
+
+impl Auth {
+    fn apply(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self {
+            Self::Bearer(token) => request.bearer_auth(token),
+            Self::Basic { username, password } => request.basic_auth(username, password.as_ref()),
+            Self::Header(value) => match reqwest::header::HeaderValue::from_str(value) {
+                Ok(mut value) => {
+                    value.set_sensitive(true);
+                    request.header(AUTHORIZATION, value)
+                }
+                Err(_) => request,
+            },
+        }
+    }
+
+    /// The secret that identifies these credentials, for cache keys
+    fn credentials(&self) -> String {
+        match self {
+            Self::Bearer(token) => format!("bearer:{token}"),
+            Self::Basic { username, password } => {
+                format!("basic:{username}:{}", password.as_deref().unwrap_or_default())
+            }
+            Self::Header(value) => format!("header:{value}"),
+        }
+    }
+}
+
+/// Keeps secrets out of logs and error messages
+impl fmt::Debug for Auth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bearer(_) => f.write_str("Bearer(***)"),
+            Self::Basic { username, .. } => write!(f, "Basic({username}:***)"),
+            Self::Header(_) => f.write_str("Header(***)"),
+        }
+    }
+}
+
+/// A registry response body, possibly served from the cache
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub body: String,
+    /// For bodies served from the cache (fresh hit or `304 Not Modified`),
+    /// when the registry last confirmed them
+    pub cached_at: Option<Timestamp>,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        self.status.is_success()
+    }
+
+    pub fn from_cache(&self) -> bool {
+        self.cached_at.is_some()
+    }
+}
+
+/// `reqwest::Client` wrapper that goes through the on-disk cache and
+/// retries transient failures
+///
+/// Fresh entries are returned without a request. Stale ones are revalidated
+/// with `If-None-Match`/`If-Modified-Since`; a `304` refreshes the entry.
+/// Only successful responses are cached.
+///
+/// Requests that reach the network share an `AdaptiveLimit`, so when the
+/// registry starts throttling, every clone of the client backs off together.
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    cache: Option<Arc<HttpCache>>,
+    retry: RetryPolicy,
+    limit: Arc<AdaptiveLimit>,
+}
+
+impl HttpClient {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            cache: None,
+            retry: RetryPolicy::default(),
+            limit: Arc::new(AdaptiveLimit::new(DEFAULT_CONCURRENCY)),
+        }
+    }
+
+    pub fn with_cache(mut self, cache: Option<HttpCache>) -> Self {
+        self.cache = cache.map(Arc::new);
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Most requests in flight at once; lowered automatically while throttled
+    pub fn with_concurrency(mut self, max: usize) -> Self {
+        self.limit = Arc::new(AdaptiveLimit::new(max));
+        self
+    }
+
+    /// GET a URL, optionally with an `Accept` header
+    pub async fn get(&self, url: &str, accept: Option<&str>) -> Result<HttpResponse, HttpError> {
+        self.get_authorized(url, accept, None).await
+    }
+
+    /// GET a URL from a registry that needs credentials
+    ///
+    /// Responses are cached per `Accept` and credentials, so a private
+    /// registry's body never answers a request made without them.
+    pub async fn get_authorized(
+        &self,
+        url: &str,
+        accept: Option<&str>,
+        auth: Option<&Auth>,
+    ) -> Result<HttpResponse, HttpError> {
+        let now = Timestamp::now();
+        let key = HttpCache::key(url, accept, auth.map(Auth::credentials).as_deref());
+        let cached = self.cache.as_ref().and_then(|c| c.get(&key));
+        if let (Some(cache), Some(entry)) = (&self.cache, &cached)
+            && cache.is_fresh(entry, now)
+        {
+            return Ok(HttpResponse {
+                status: StatusCode::OK,
+                body: entry.body.clone(),
+                cached_at: Some(entry.fetched()),
+            });
+        }
+        if self.cache.as_ref().is_some_and(|c| c.is_offline()) {
+            return Err(HttpError::NotCached {
+                url: url.to_string(),
+            });
+        }
+
+        let response = self.send(url, accept, auth, cached.as_ref()).await?;
+        let status = response.status();
+
+        if status == StatusCode::NOT_MODIFIED
+            && let Some(entry) = cached
+        {
+            let entry = CacheEntry {
+                fetched_at: now.0,
+                ..entry
+            };
+            self.store(&entry);
+            return Ok(HttpResponse {
+                status: StatusCode::OK,
+                body: entry.body,
+                cached_at: Some(now),
+            });
+        }
+
+        let headers = response.headers().clone();
+        let body = response.text().await?;
+        if status.is_success() {
+            self.store(&CacheEntry {
+                key,
+                etag: header(&headers, ETAG),
+                last_modified: header(&headers, LAST_MODIFIED),
+                fetched_at: now.0,
+                body: body.clone(),
+            });
+        }
+
+        Ok(HttpResponse {
+            status,
+            body,
+            cached_at: None,
+        })
+    }
+
+    /// Send a GET, retrying as the policy allows
+    async fn send(
+        &self,
+        url: &str,
+        accept: Option<&str>,
+        auth: Option<&Auth>,
+        cached: Option<&CacheEntry>,
+    ) -> Result<reqwest::Response, HttpError> {
+        let mut retry = 0;
+        loop {
+            let mut request = self.client.get(url);
+            if let Some(accept) = accept {
+                request = request.header(ACCEPT, accept);
+            }
+            if let Some(auth) = auth {
+                request = auth.apply(request);
+            }
+            if let Some(entry) = cached {
+                if let Some(etag) = &entry.etag {
+                    request = request.header(IF_NONE_MATCH, etag);
+                }
+                if let Some(last_modified) = &entry.last_modified {
+                    request = request.header(IF_MODIFIED_SINCE, last_modified);
+                }
+            }
+
+            let result = {
+                let _permit = self.limit.acquire().await;
+                request.send().await
+            };
+            let attempts = retry + 1;
+            let can_retry = retry < self.retry.max_retries;
+
+            let delay = match result {
+                Ok(response) => match classify(response.status(), response.headers(), Timestamp::now()) {
+                    Verdict::Done => {
+                        self.limit.succeeded();
+                        return Ok(response);
+                    }
+                    Verdict::Throttled(wait) => {
+                        self.limit.throttled();
+                        if !can_retry || wait.is_some_and(|w| w > self.retry.max_wait) {
+                            return Err(HttpError::RateLimited {
+                                url: url.to_string(),
+                                retry_after: wait,
+                            });
+                        }
+                        wait.unwrap_or_else(|| self.retry.backoff(retry))
+                    }
+                    Verdict::Retry(wait) => {
+                        if !can_retry {
+                            return Err(HttpError::Server {
+                                url: url.to_string(),
+                                status: response.status(),
+                                attempts,
+                            });
+                        }
+                        wait.filter(|w| *w <= self.retry.max_wait)
+                            .unwrap_or_else(|| self.retry.backoff(retry))
+                    }
+                },
+                Err(e) if e.is_timeout() || e.is_connect() || e.is_request() => {
+                    if !can_retry {
+                        return Err(HttpError::Unreachable {
+                            source: e,
+                            attempts,
+                        });
+                    }
+                    self.retry.backoff(retry)
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            tokio::time::sleep(delay).await;
+            retry += 1;
+        }
+    }
+
+    fn store(&self, entry: &CacheEntry) {
+        // A read-only or full cache dir shouldn't fail the run
+        if let Some(cache) = &self.cache {
+            let _ = cache.store(entry);
+        }
+    }
+}
+
+fn header(headers: &HeaderMap, name: reqwest::header::HeaderName) -> Option<String> {
+    headers.get(name)?.to_str().ok().map(ToString::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header as has_header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_cached_and_conditional_requests() -> Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/serde"))
+            .and(has_header("if-none-match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/serde"))
+            .respond_with(ResponseTemplate::new(200).insert_header("etag", "\"v1\"").set_body_string("body"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir()?;
+        let url = format!("{}/serde", server.uri());
+        let client = |refresh| {
+            HttpClient::new(reqwest::Client::new())
+                .with_cache(Some(HttpCache::with_dir(dir.path()).with_refresh(refresh)))
+        };
+
+        // Miss, then a fresh hit that never reaches the server
+        let first = client(false).get(&url, None).await?;
+        assert!(!first.from_cache());
+        let second = client(false).get(&url, None).await?;
+        assert!(second.from_cache());
+        assert_eq!(second.body, "body");
+
+        // --refresh revalidates and gets a 304
+        let third = client(true).get(&url, None).await?;
+        assert!(third.from_cache());
+        assert_eq!(third.body, "body");
+
+        // --offline answers from the cache and never asks for unknown URLs
+        let offline = HttpClient::new(reqwest::Client::new())
+            .with_cache(Some(HttpCache::with_dir(dir.path()).with_offline(true)));
+        assert_eq!(offline.get(&url, None).await?.body, "body");
+        let missing = offline.get(&format!("{}/tokio", server.uri()), None).await;
+        let err = missing.expect_err("nothing cached");
+        assert!(is_not_cached([&err as &(dyn Error + 'static)]));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cache_keeps_authorized_responses_apart() -> Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/private"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("private"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir()?;
+        let url = format!("{}/private", server.uri());
+        let token = Auth::Bearer("s3cret".to_string());
+        HttpClient::new(reqwest::Client::new())
+            .with_cache(Some(HttpCache::with_dir(dir.path())))
+            .get_authorized(&url, None, Some(&token))
+            .await?;
+
+        let offline = HttpClient::new(reqwest::Client::new())
+            .with_cache(Some(HttpCache::with_dir(dir.path()).with_offline(true)));
+        assert_eq!(offline.get_authorized(&url, None, Some(&token)).await?.body, "private");
+        assert!(offline.get(&url, None).await.is_err());
+        let other = Auth::Bearer("other".to_string());
+        assert!(offline.get_authorized(&url, None, Some(&other)).await.is_err());
+        assert!(offline.get_authorized(&url, Some("text/html"), Some(&token)).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retries_and_throttling() -> Result<(), Box<dyn std::error::Error>> {
+        let server = MockServer::start().await;
+        // A transient 502, then success
+        Mock::given(path("/flaky"))
+            .respond_with(ResponseTemplate::new(502))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/flaky"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .mount(&server)
+            .await;
+        // Throttled with a short Retry-After, then success
+        Mock::given(path("/throttled"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/throttled"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/down"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(3)
+            .mount(&server)
+            .await;
+        Mock::given(path("/later"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "3600"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/missing"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = HttpClient::new(reqwest::Client::new())
+            .with_retry(RetryPolicy::default().with_max_retries(2).with_base_delay(Duration::from_millis(1)))
+            .with_concurrency(4);
+        let url = |p: &str| format!("{}/{p}", server.uri());
+
+        assert_eq!(client.get(&url("flaky"), None).await?.body, "ok");
+        assert_eq!(client.get(&url("throttled"), None).await?.body, "ok");
+        assert_eq!(client.limit.current(), 2);
+
+        // Genuine 404s are returned as responses, not retried
+        assert_eq!(client.get(&url("missing"), None).await?.status, StatusCode::NOT_FOUND);
+
+        let down = client.get(&url("down"), None).await.expect_err("always 503");
+        assert!(matches!(down, HttpError::Server { attempts: 3, .. }), "{down:?}");
+        assert_eq!(down.to_string(), "server error 503 Service Unavailable after 3 attempts");
+
+        // Not worth waiting an hour for
+        let later = client.get(&url("later"), None).await.expect_err("throttled");
+        assert_eq!(later.to_string(), "rate limited by the registry (retry after 3600s)");
+        Ok(())
+    }
+}
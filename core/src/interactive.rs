@@ -0,0 +1,406 @@
+use crate::ecosystem::Ecosystem;
+use crate::types::{DependencyCheck, UpdateSeverity};
+use crate::version::Version;
+use colored::Colorize;
+use console::{Key, Term};
+use std::io;
+
+/// Which version a selected package moves to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Choice {
+    /// The default target (`-u` / `-um`)
+    InRange,
+    /// The absolute latest (`-uf`)
+    Latest,
+}
+
+/// A deduplicated package in the picker
+#[derive(Debug)]
+struct Entry<'a> {
+    /// First check for the package; the others share its name and target
+    check: &'a DependencyCheck,
+    severity: Option<UpdateSeverity>,
+    latest_severity: Option<UpdateSeverity>,
+    selected: bool,
+    choice: Choice,
+}
+
+impl Entry<'_> {
+    fn has_in_range(&self) -> bool {
+        self.check.target_spec.is_some()
+    }
+
+    fn has_latest(&self) -> bool {
+        self.check.force_spec.is_some() && self.check.target.as_ref() != Some(&self.check.latest)
+    }
+
+    fn toggle_choice(&mut self) {
+        self.choice = match self.choice {
+            Choice::InRange if self.has_latest() => Choice::Latest,
+            Choice::Latest if self.has_in_range() => Choice::InRange,
+            choice => choice,
+        };
+    }
+}
+
+/// What a key press did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Continue,
+    Confirm,
+    Cancel,
+}
+
+/// Checkbox list of available updates, grouped by severity
+///
+/// Patch and minor updates start selected, majors don't. Each package can
+/// move to its in-range target or to the latest version.
+#[derive(Debug)]
+pub struct Picker<'a> {
+    checks: &'a [DependencyCheck],
+    entries: Vec<Entry<'a>>,
+    cursor: usize,
+}
+
+const GROUPS: [(Option<UpdateSeverity>, &str); 4] = [
+    (Some(UpdateSeverity::Major), "Major"),
+    (Some(UpdateSeverity::Minor), "Minor"),
+    (Some(UpdateSeverity::Patch), "Patch"),
+    (None, "Other"),
+];
+
+impl<'a> Picker<'a> {
+    pub fn new(checks: &'a [DependencyCheck], ecosystem: &dyn Ecosystem) -> Self {
+        let mut entries: Vec<Entry<'a>> = Vec::new();
+        for check in checks
+            .iter()
+            .filter(|c| c.target_spec.is_some() || c.force_spec.is_some())
+        {
+            if entries.iter().any(|e| same_update(e.check, check)) {
+                continue;
+            }
+            let latest_severity = check
+                .current_version()
+                .and_then(|current| ecosystem.severity(current, &check.latest));
+            let choice = if check.target_spec.is_some() {
+                Choice::InRange
+            } else {
+                Choice::Latest
+            };
+            let severity = match choice {
+                Choice::InRange => check.severity,
+                Choice::Latest => latest_severity,
+            };
+            entries.push(Entry {
+                check,
+                severity,
+                latest_severity,
+                selected: severity.is_some_and(|s| !s.is_at_least(UpdateSeverity::Major)),
+                choice,
+            });
+        }
+
+        // Group by the severity of the initial choice, in table order
+        entries.sort_by_key(|e| {
+            GROUPS
+                .iter()
+                .position(|(s, _)| *s == e.severity)
+                .unwrap_or(GROUPS.len())
+        });
+        Self {
+            checks,
+            entries,
+            cursor: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Apply a key press
+    ///
+    /// ↑/↓ (or k/j) move, space toggles, ←/→ or tab switch between in-range
+    /// and latest, `a` toggles everything, enter confirms, esc, q or ^C cancels.
+    pub fn handle(&mut self, key: &Key) -> Outcome {
+        let last = self.entries.len().saturating_sub(1);
+        match key {
+            Key::ArrowUp | Key::Char('k') => self.cursor = self.cursor.saturating_sub(1),
+            Key::ArrowDown | Key::Char('j') => self.cursor = (self.cursor + 1).min(last),
+            Key::Char(' ') => {
+                if let Some(entry) = self.entries.get_mut(self.cursor) {
+                    entry.selected = !entry.selected;
+                }
+            }
+            Key::ArrowLeft | Key::ArrowRight | Key::Tab | Key::Char('h' | 'l') => {
+                if let Some(entry) = self.entries.get_mut(self.cursor) {
+                    entry.toggle_choice();
+                }
+            }
+            Key::Char('a') => {
+                let select = !self.entries.iter().all(|e| e.selected);
+                for entry in &mut self.entries {
+                    entry.selected = select;
+                }
+            }
+            Key::Enter => return Outcome::Confirm,
+            Key::Escape | Key::CtrlC | Key::Char('q') => return Outcome::Cancel,
+            _ => {}
+        }
+        Outcome::Continue
+    }
+
+    /// Lines to draw for the current state
+    pub fn lines(&self, colors: bool) -> Vec<String> {
+        let name_width = self
+            .entries
+            .iter()
+            .map(|e| e.check.dependency.display_name().len())
+            .max()
+            .unwrap_or(0);
+
+        let mut lines = Vec::new();
+        let mut group = None;
+        for (i, entry) in self.entries.iter().enumerate() {
+            let label = GROUPS
+                .iter()
+                .find(|(s, _)| *s == entry.severity)
+                .map_or("Other", |(_, label)| label);
+            if group != Some(label) {
+                group = Some(label);
+                let header = format!("{label}:");
+                lines.push(if colors {
+                    header.bold().to_string()
+                } else {
+                    header
+                });
+            }
+
+            let pointer = if i == self.cursor { '>' } else { ' ' };
+            let checkbox = if entry.selected { "[x]" } else { "[ ]" };
+            let current = entry
+                .check
+                .current_version()
+                .map(ToString::to_string)
+                .unwrap_or_default();
+            let in_range = entry
+                .has_in_range()
+                .then_some(entry.check.target.as_ref())
+                .flatten();
+            let versions = format_options(in_range, entry, colors);
+
+            lines.push(format!(
+                "{pointer} {checkbox} {:<name_width$}  {current} → {versions}",
+                entry.check.dependency.display_name(),
+            ));
+        }
+        lines.push(String::new());
+        let help = "↑/↓ move  space select  ←/→ in-range/latest  a all  enter apply  esc cancel";
+        lines.push(if colors {
+            help.dimmed().to_string()
+        } else {
+            help.to_string()
+        });
+        lines
+    }
+
+    /// Every check covered by a selected entry, rewritten so that
+    /// `force_spec` holds the chosen requirement
+    ///
+    /// Pass the result to `FileUpdater::apply_updates` with `force` set.
+    pub fn selection(&self) -> Vec<DependencyCheck> {
+        let mut selected = Vec::new();
+        for entry in self.entries.iter().filter(|e| e.selected) {
+            for check in self.checks.iter().filter(|c| same_update(entry.check, c)) {
+                let (target, spec) = match entry.choice {
+                    Choice::InRange => (check.target.clone(), check.target_spec.clone()),
+                    Choice::Latest => (Some(check.latest.clone()), check.force_spec.clone()),
+                };
+                if spec.is_none() {
+                    continue;
+                }
+                selected.push(DependencyCheck {
+                    target,
+                    target_spec: spec.clone(),
+                    severity: match entry.choice {
+                        Choice::InRange => check.severity,
+                        Choice::Latest => entry.latest_severity,
+                    },
+                    force_spec: spec,
+                    ..check.clone()
+                });
+            }
+        }
+        selected
+    }
+
+    /// Run the picker on stderr; `None` if the user cancelled
+    pub fn run(mut self) -> io::Result<Option<Vec<DependencyCheck>>> {
+        let term = Term::stderr();
+        if !term.is_term() {
+            return Err(io::Error::other("interactive mode needs a terminal"));
+        }
+
+        term.hide_cursor()?;
+        let _cursor = CursorGuard(&term);
+        let mut drawn = 0;
+        let outcome = loop {
+            term.clear_last_lines(drawn)?;
+            let lines = self.lines(true);
+            for line in &lines {
+                term.write_line(line)?;
+            }
+            drawn = lines.len();
+
+            // Raw, so ^C comes back as a key instead of killing the process
+            match self.handle(&term.read_key_raw()?) {
+                Outcome::Continue => {}
+                outcome => break outcome,
+            }
+        };
+        term.clear_last_lines(drawn)?;
+
+        Ok(match outcome {
+            Outcome::Confirm => Some(self.selection()),
+            _ => None,
+        })
+    }
+}
+
+/// Shows the cursor again however the picker exits
+struct CursorGuard<'a>(&'a Term);
+
+impl Drop for CursorGuard<'_> {
+    fn drop(&mut self) {
+        let _ = self.0.show_cursor();
+    }
+}
+
+/// Whether two checks are the same package moving to the same target
+fn same_update(a: &DependencyCheck, b: &DependencyCheck) -> bool {
+    a.dependency.name == b.dependency.name && a.target == b.target && a.latest == b.latest
+}
+
+/// `1.4.0 / 2.0.0 (major)` with the chosen option highlighted
+fn format_options(in_range: Option<&Version>, entry: &Entry, colors: bool) -> String {
+    let highlight = |text: String, chosen: bool| match (chosen, colors) {
+        (true, true) => text.cyan().bold().to_string(),
+        (true, false) => format!("<{text}>"),
+        (false, true) => text.dimmed().to_string(),
+        (false, false) => text,
+    };
+
+    let mut options = Vec::new();
+    if let Some(version) = in_range {
+        options.push(highlight(
+            version.to_string(),
+            entry.choice == Choice::InRange,
+        ));
+    }
+    if entry.has_latest() {
+        let severity = entry
+            .latest_severity
+            .map(|s| format!(" ({s})"))
+            .unwrap_or_default();
+        options.push(highlight(
+            format!("{}{severity}", entry.check.latest),
+            entry.choice == Choice::Latest,
+        ));
+    }
+    options.join(" / ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DependencyResolver;
+    use crate::ecosystem::GenericEcosystem;
+    use crate::types::{Dependency, PackageInfo};
+    use crate::version::VersionSpec;
+    use std::path::PathBuf;
+
+    fn check(
+        name: &str,
+        spec: &str,
+        installed: &str,
+        versions: &[&str],
+        file: &str,
+    ) -> DependencyCheck {
+        let versions: Vec<Version> = versions.iter().map(|v| v.parse().unwrap()).collect();
+        let info = PackageInfo {
+            name: name.to_string(),
+            latest: versions.last().unwrap().clone(),
+            latest_stable: versions.last().cloned(),
+            published: Default::default(),
+            requires: Default::default(),
+            cached_at: None,
+            versions,
+        };
+        let dep = Dependency {
+            name: name.to_string(),
+            registry: None,
+            package: None,
+            version_spec: VersionSpec::parse(spec).unwrap(),
+            source_file: PathBuf::from(file),
+            line_number: 1,
+            original_line: String::new(),
+        };
+        DependencyResolver::new().resolve(&dep, &info, Some(&installed.parse().unwrap()))
+    }
+
+    #[test]
+    fn test_picker_selection() {
+        let checks = vec![
+            check(
+                "flask",
+                ">=2.0.0,<3.0.0",
+                "2.0.0",
+                &["2.0.0", "2.3.0", "3.1.0"],
+                "a.txt",
+            ),
+            check(
+                "flask",
+                ">=2.0.0,<3.0.0",
+                "2.0.0",
+                &["2.0.0", "2.3.0", "3.1.0"],
+                "b.txt",
+            ),
+            check("django", ">=4.0.0", "4.0.0", &["4.0.0", "5.0.0"], "a.txt"),
+            check("six", ">=1.16.0", "1.16.0", &["1.16.0"], "a.txt"),
+        ];
+        let mut picker = Picker::new(&checks, &GenericEcosystem);
+
+        // Up-to-date packages are left out and duplicates are merged
+        let lines = picker.lines(false);
+        assert_eq!(lines[0], "Major:");
+        assert!(lines[1].starts_with("> [ ] django"));
+        assert_eq!(lines[2], "Minor:");
+        assert!(
+            lines[3].ends_with("2.0.0 → <2.3.0> / 3.1.0 (major)"),
+            "{}",
+            lines[3]
+        );
+
+        // Only flask starts selected; pick django too and move flask to latest
+        assert_eq!(picker.selection().len(), 2);
+        assert_eq!(picker.handle(&Key::Char(' ')), Outcome::Continue);
+        picker.handle(&Key::ArrowDown);
+        picker.handle(&Key::ArrowRight);
+        assert_eq!(picker.handle(&Key::Enter), Outcome::Confirm);
+
+        let selection = picker.selection();
+        assert_eq!(selection.len(), 3);
+        let specs: Vec<String> = selection
+            .iter()
+            .map(|c| c.force_spec.as_ref().unwrap().to_string())
+            .collect();
+        assert_eq!(specs, [">=5.0.0", ">=3.1.0,<4.0.0", ">=3.1.0,<4.0.0"]);
+        assert_eq!(selection[1].severity, Some(UpdateSeverity::Major));
+
+        // Everything is selected, so `a` clears the lot
+        picker.handle(&Key::Char('a'));
+        assert!(picker.selection().is_empty());
+        assert_eq!(picker.handle(&Key::Escape), Outcome::Cancel);
+        assert_eq!(picker.handle(&Key::CtrlC), Outcome::Cancel);
+    }
+}
@@ -1,10 +1,32 @@
+pub mod annotation;
+pub mod cache;
+pub mod config;
+pub mod date;
+pub mod ecosystem;
+pub mod exit;
+pub mod http;
+pub mod interactive;
+pub mod npm_range;
 pub mod output;
+pub mod registry;
+pub mod report;
 pub mod resolver;
+pub mod retry;
+pub mod summary;
 pub mod types;
 pub mod version;
 
 // Re-export commonly used types at crate root
+pub use annotation::Annotations;
+pub use config::Config;
+pub use date::Date;
+pub use ecosystem::{leftmost_nonzero_severity, semver_severity, Ecosystem, GenericEcosystem};
+pub use exit::exit_status;
+pub use npm_range::NpmRange;
 pub use output::TableRenderer;
+pub use registry::{FixtureRegistry, GetPackagesResult, RegistryClient, RegistryError};
+pub use report::{OutputFormat, Report, ReportMode};
 pub use resolver::DependencyResolver;
-pub use types::{Dependency, DependencyCheck, PackageInfo, UpdateSeverity};
+pub use summary::Summary;
+pub use types::{Blocked, Dependency, DependencyCheck, PackageInfo, UpdateSeverity};
 pub use version::{Version, VersionError, VersionSpec};
@@ -0,0 +1,646 @@
+use crate::version::{Version, VersionError, VersionSpec};
+use std::fmt;
+use std::str::FromStr;
+
+/// A node-semver range as found in package.json
+///
+/// Covers the full grammar: unions (`^1 || ^2`), comparator sets (`>=1 <2`),
+/// hyphen ranges (`1.2 - 1.4`), x-ranges (`1.x`, `1.2.*`, `1`), and partial
+/// caret/tilde ranges (`^0.2`, `~1`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NpmRange {
+    alternatives: Vec<Vec<Term>>,
+    include_prerelease: bool,
+    /// Lower bound of the highest alternative, used as the "current" version
+    base: Version,
+}
+
+/// A single range term, kept as written so it can be rewritten in place
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Term {
+    /// Bare version or x-range: `1.2.3`, `1.x`, `*`
+    Partial(Partial),
+    Caret(Partial),
+    Tilde(Partial),
+    /// `>=1.2`, `<2`, `=1.2.3`
+    Primitive(Op, Partial),
+    /// `1.2.3 - 2.3`
+    Hyphen(Partial, Partial),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+/// A possibly incomplete version (`1`, `1.2.x`, `1.2.3-beta.1`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Partial {
+    /// Numeric components that were written (`1.x` has one)
+    parts: Vec<u64>,
+    /// Pre-release identifiers, only valid with all three parts
+    pre: Option<String>,
+    /// Trailing wildcards as written (`x`, `X` or `*`), for round-tripping
+    wildcards: Vec<char>,
+}
+
+/// A desugared comparator (`>=1.2.0`, `<2.0.0-0`)
+struct Comparator {
+    op: Op,
+    version: Version,
+}
+
+impl NpmRange {
+    /// Parse a node-semver range
+    pub fn parse(s: &str) -> Result<Self, VersionError> {
+        let invalid = || VersionError::InvalidSpecifier(s.to_string());
+
+        let alternatives = s
+            .split("||")
+            .map(|alt| parse_alternative(alt.trim()).ok_or_else(invalid))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::from_alternatives(alternatives, false))
+    }
+
+    /// Apply npm's `includePrerelease` option: pre-releases may satisfy the
+    /// range even when no comparator opts into that release's pre-releases
+    pub fn with_prerelease(mut self, include_prerelease: bool) -> Self {
+        self.include_prerelease = include_prerelease;
+        self
+    }
+
+    fn from_alternatives(alternatives: Vec<Vec<Term>>, include_prerelease: bool) -> Self {
+        let base = alternatives
+            .iter()
+            .map(|alt| lower_bound(alt))
+            .max()
+            .unwrap_or_else(|| Version::new(0, 0, 0));
+        Self {
+            alternatives,
+            include_prerelease,
+            base,
+        }
+    }
+
+    /// Map onto a plain `VersionSpec` variant when the range is a single
+    /// fully specified term (`^1.2.3`, `>=1.0.0`, `1.2.3`), so that it is
+    /// resolved and rewritten like any other ecosystem's spec
+    pub fn into_spec(self) -> VersionSpec {
+        if let [alternative] = self.alternatives.as_slice()
+            && let [term] = alternative.as_slice()
+        {
+            let full = |p: &Partial| (p.parts.len() == 3).then(|| p.to_version());
+            let simple = match term {
+                Term::Partial(p) if p.parts.is_empty() => Some(VersionSpec::Any),
+                Term::Partial(p) | Term::Primitive(Op::Eq, p) => full(p).map(VersionSpec::Pinned),
+                Term::Caret(p) => full(p).map(VersionSpec::Caret),
+                Term::Tilde(p) => full(p).map(VersionSpec::Tilde),
+                Term::Primitive(Op::Ge, p) => full(p).map(VersionSpec::Minimum),
+                Term::Primitive(Op::Gt, p) => full(p).map(VersionSpec::GreaterThan),
+                Term::Primitive(Op::Lt, p) => full(p).map(VersionSpec::LessThan),
+                Term::Primitive(Op::Le, p) => full(p).map(VersionSpec::Maximum),
+                Term::Hyphen(..) => None,
+            };
+            if let Some(spec) = simple {
+                return spec;
+            }
+        }
+        VersionSpec::NpmRange(self)
+    }
+
+    /// Lower bound of the highest alternative
+    pub fn base_version(&self) -> &Version {
+        &self.base
+    }
+
+    /// Lowest version any alternative admits (`18.18.0` for `^18.18 || >=20`)
+    pub fn lowest_version(&self) -> Version {
+        self.alternatives
+            .iter()
+            .map(|alt| lower_bound(alt))
+            .min()
+            .unwrap_or_else(|| Version::new(0, 0, 0))
+    }
+
+    /// Check if a version satisfies any alternative of the range
+    pub fn satisfies(&self, version: &Version) -> bool {
+        self.alternatives
+            .iter()
+            .any(|alt| alternative_satisfies(alt, version, self.include_prerelease))
+    }
+
+    /// Rewrite the range to admit `version`, keeping operators and precision.
+    ///
+    /// Only one alternative of a union is touched: the one that already
+    /// contains `version`, or else the highest one (`^1 || ^2` → `^1 || ^3`).
+    /// Lower bounds move up to `version`; upper bounds only move if they
+    /// would exclude it.
+    pub fn with_version(&self, version: &Version) -> NpmRange {
+        let index = self
+            .alternatives
+            .iter()
+            .position(|alt| alternative_satisfies(alt, version, true))
+            .or_else(|| {
+                (0..self.alternatives.len()).max_by_key(|&i| lower_bound(&self.alternatives[i]))
+            });
+
+        let mut alternatives = self.alternatives.clone();
+        if let Some(alternative) = index.and_then(|i| alternatives.get_mut(i)) {
+            for term in alternative.iter_mut() {
+                *term = rewrite_term(term, version);
+            }
+        }
+        Self::from_alternatives(alternatives, self.include_prerelease)
+    }
+}
+
+impl fmt::Display for NpmRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let alternatives: Vec<String> = self
+            .alternatives
+            .iter()
+            .map(|alt| {
+                alt.iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect();
+        write!(f, "{}", alternatives.join(" || "))
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Term::Partial(p) => write!(f, "{p}"),
+            Term::Caret(p) => write!(f, "^{p}"),
+            Term::Tilde(p) => write!(f, "~{p}"),
+            Term::Primitive(op, p) => write!(f, "{op}{p}"),
+            Term::Hyphen(from, to) => write!(f, "{from} - {to}"),
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            Op::Eq => "=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+        };
+        write!(f, "{op}")
+    }
+}
+
+impl fmt::Display for Partial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut components: Vec<String> = self.parts.iter().map(ToString::to_string).collect();
+        components.extend(self.wildcards.iter().map(ToString::to_string));
+        write!(f, "{}", components.join("."))?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{pre}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Partial {
+    fn parse(s: &str) -> Option<Self> {
+        let s = s.trim_start_matches(['v', 'V', '=']);
+        // Build metadata never affects matching
+        let s = s.split('+').next().unwrap_or(s);
+
+        let (main, pre) = match s.split_once('-') {
+            Some((main, pre)) if !pre.is_empty() => (main, Some(pre.to_string())),
+            Some(_) => return None,
+            None => (s, None),
+        };
+
+        let mut parts = Vec::new();
+        let mut wildcards = Vec::new();
+        if !main.is_empty() {
+            for component in main.split('.') {
+                match component {
+                    "x" | "X" | "*" => wildcards.push(component.chars().next()?),
+                    // A number after a wildcard (`1.x.3`) is meaningless
+                    _ if !wildcards.is_empty() => return None,
+                    _ => parts.push(component.parse().ok()?),
+                }
+            }
+        }
+
+        if parts.len() + wildcards.len() > 3 || (pre.is_some() && parts.len() < 3) {
+            return None;
+        }
+        Some(Self {
+            parts,
+            pre,
+            wildcards,
+        })
+    }
+
+    fn part(&self, index: usize) -> u64 {
+        self.parts.get(index).copied().unwrap_or(0)
+    }
+
+    /// The version with missing parts filled in as 0
+    fn to_version(&self) -> Version {
+        let version = Version::new(self.part(0), self.part(1), self.part(2));
+        match &self.pre {
+            // Parse rather than build, so `-dev.1` gets the same ordering as
+            // the registry's `1.2.3-dev.1`
+            Some(pre) => Version::from_str(&format!("{version}-{pre}")).unwrap_or(version),
+            None => version,
+        }
+    }
+
+    /// Same precision and wildcards, new numbers
+    fn with_version(&self, version: &Version) -> Partial {
+        let numbers = [version.major(), version.minor(), version.patch()];
+        let parts = numbers[..self.parts.len()].to_vec();
+        let pre = (parts.len() == 3).then(|| semver_pre(version)).flatten();
+        Partial {
+            parts,
+            pre,
+            wildcards: self.wildcards.clone(),
+        }
+    }
+
+    /// Next value at this precision, for exclusive upper bounds
+    /// (`<2` → `<3`, `<1.3` → `<1.4`, `<2.0.0` → `<3.0.0`)
+    fn bumped_past(&self, version: &Version) -> Partial {
+        let parts = match self.parts.len() {
+            2 => vec![version.major(), version.minor() + 1],
+            len => {
+                let mut parts = vec![version.major() + 1, 0, 0];
+                parts.truncate(len);
+                parts
+            }
+        };
+        Partial {
+            parts,
+            pre: None,
+            wildcards: self.wildcards.clone(),
+        }
+    }
+}
+
+/// The semver pre-release identifiers of a version, as originally written
+fn semver_pre(version: &Version) -> Option<String> {
+    if !version.is_prerelease() {
+        return None;
+    }
+    let without_build = version.original.split('+').next()?;
+    without_build
+        .split_once('-')
+        .map(|(_, pre)| pre.to_string())
+}
+
+fn parse_alternative(s: &str) -> Option<Vec<Term>> {
+    // Hyphen ranges need whitespace around the dash (`1.2 - 1.4`)
+    if let Some((from, to)) = s.split_once(" - ") {
+        return Some(vec![Term::Hyphen(
+            Partial::parse(from.trim())?,
+            Partial::parse(to.trim())?,
+        )]);
+    }
+
+    // Re-attach operators separated from their version (`>= 1.2`)
+    let mut tokens: Vec<String> = Vec::new();
+    let mut pending = String::new();
+    for token in s.split_whitespace() {
+        pending.push_str(token);
+        if !token
+            .chars()
+            .all(|c| matches!(c, '<' | '>' | '=' | '^' | '~'))
+        {
+            tokens.push(std::mem::take(&mut pending));
+        }
+    }
+    if !pending.is_empty() {
+        return None;
+    }
+    if tokens.is_empty() {
+        // An empty range (or empty union member) means any version
+        return Some(vec![Term::Partial(Partial::parse("*")?)]);
+    }
+
+    tokens.iter().map(|token| parse_term(token)).collect()
+}
+
+fn parse_term(token: &str) -> Option<Term> {
+    let term = if let Some(rest) = token.strip_prefix("~>").or_else(|| token.strip_prefix('~')) {
+        Term::Tilde(Partial::parse(rest)?)
+    } else if let Some(rest) = token.strip_prefix('^') {
+        Term::Caret(Partial::parse(rest)?)
+    } else if let Some(rest) = token.strip_prefix(">=") {
+        Term::Primitive(Op::Ge, Partial::parse(rest)?)
+    } else if let Some(rest) = token.strip_prefix("<=") {
+        Term::Primitive(Op::Le, Partial::parse(rest)?)
+    } else if let Some(rest) = token.strip_prefix('>') {
+        Term::Primitive(Op::Gt, Partial::parse(rest)?)
+    } else if let Some(rest) = token.strip_prefix('<') {
+        Term::Primitive(Op::Lt, Partial::parse(rest)?)
+    } else if let Some(rest) = token.strip_prefix('=') {
+        Term::Primitive(Op::Eq, Partial::parse(rest)?)
+    } else {
+        Term::Partial(Partial::parse(token)?)
+    };
+    Some(term)
+}
+
+/// `major.minor.patch-0`, the lowest version of that release
+fn lowest_prerelease(major: u64, minor: u64, patch: u64) -> Version {
+    let mut version = Version::new(major, minor, patch);
+    version.pre_release = Some("0".to_string());
+    version.original = format!("{}-0", version.original);
+    version
+}
+
+/// Exclusive upper bound of an x-range (`1` → `<2.0.0-0`, `1.2` → `<1.3.0-0`)
+fn x_range_upper(p: &Partial) -> Comparator {
+    let version = match p.parts.len() {
+        1 => lowest_prerelease(p.part(0) + 1, 0, 0),
+        _ => lowest_prerelease(p.part(0), p.part(1) + 1, 0),
+    };
+    Comparator {
+        op: Op::Lt,
+        version,
+    }
+}
+
+fn comparator(op: Op, version: Version) -> Comparator {
+    Comparator { op, version }
+}
+
+/// Desugar a term into primitive comparators, per node-semver
+fn comparators(term: &Term) -> Vec<Comparator> {
+    match term {
+        Term::Partial(p) | Term::Primitive(Op::Eq, p) => match p.parts.len() {
+            0 => Vec::new(),
+            3 => vec![comparator(Op::Eq, p.to_version())],
+            _ => vec![comparator(Op::Ge, p.to_version()), x_range_upper(p)],
+        },
+        Term::Tilde(p) => match p.parts.len() {
+            0 => Vec::new(),
+            1 => vec![comparator(Op::Ge, p.to_version()), x_range_upper(p)],
+            _ => vec![
+                comparator(Op::Ge, p.to_version()),
+                comparator(Op::Lt, lowest_prerelease(p.part(0), p.part(1) + 1, 0)),
+            ],
+        },
+        Term::Caret(p) => {
+            if p.parts.is_empty() {
+                return Vec::new();
+            }
+            let (major, minor, patch) = (p.part(0), p.part(1), p.part(2));
+            let upper = if major > 0 || p.parts.len() == 1 {
+                lowest_prerelease(major + 1, 0, 0)
+            } else if minor > 0 || p.parts.len() == 2 {
+                lowest_prerelease(0, minor + 1, 0)
+            } else {
+                lowest_prerelease(0, 0, patch + 1)
+            };
+            vec![
+                comparator(Op::Ge, p.to_version()),
+                comparator(Op::Lt, upper),
+            ]
+        }
+        Term::Primitive(op, p) => {
+            if p.parts.is_empty() {
+                // `<*` and `>*` match nothing; `>=*` and `<=*` match anything
+                return match op {
+                    Op::Lt | Op::Gt => vec![comparator(Op::Lt, lowest_prerelease(0, 0, 0))],
+                    _ => Vec::new(),
+                };
+            }
+            if p.parts.len() == 3 {
+                return vec![comparator(*op, p.to_version())];
+            }
+            let next = |p: &Partial| match p.parts.len() {
+                1 => Version::new(p.part(0) + 1, 0, 0),
+                _ => Version::new(p.part(0), p.part(1) + 1, 0),
+            };
+            match op {
+                Op::Gt => vec![comparator(Op::Ge, next(p))],
+                Op::Ge => vec![comparator(Op::Ge, p.to_version())],
+                Op::Lt => {
+                    let lower = p.to_version();
+                    vec![comparator(
+                        Op::Lt,
+                        lowest_prerelease(lower.major(), lower.minor(), 0),
+                    )]
+                }
+                Op::Le => vec![x_range_upper(p)],
+                Op::Eq => comparators(&Term::Partial(p.clone())),
+            }
+        }
+        Term::Hyphen(from, to) => {
+            let mut result = Vec::new();
+            if !from.parts.is_empty() {
+                result.push(comparator(Op::Ge, from.to_version()));
+            }
+            result.extend(hyphen_upper(to));
+            result
+        }
+    }
+}
+
+/// Upper bound of a hyphen range (`- 2.3.4` → `<=2.3.4`, `- 2.3` → `<2.4.0-0`)
+fn hyphen_upper(to: &Partial) -> Option<Comparator> {
+    match to.parts.len() {
+        0 => None,
+        3 => Some(comparator(Op::Le, to.to_version())),
+        _ => Some(x_range_upper(to)),
+    }
+}
+
+fn comparator_matches(c: &Comparator, version: &Version) -> bool {
+    match c.op {
+        Op::Eq => version == &c.version,
+        Op::Gt => version > &c.version,
+        Op::Ge => version >= &c.version,
+        Op::Lt => version < &c.version,
+        Op::Le => version <= &c.version,
+    }
+}
+
+fn alternative_satisfies(terms: &[Term], version: &Version, include_prerelease: bool) -> bool {
+    let comparators: Vec<Comparator> = terms.iter().flat_map(comparators).collect();
+    if !comparators.iter().all(|c| comparator_matches(c, version)) {
+        return false;
+    }
+
+    // A pre-release only matches if some comparator opts into pre-releases
+    // of the same major.minor.patch (`>=1.2.3-beta.1` admits `1.2.3-beta.2`,
+    // but not `1.3.0-beta.1`)
+    if version.is_prerelease() && !include_prerelease {
+        return comparators.iter().any(|c| {
+            c.version.is_prerelease()
+                && (0..3).all(|i| c.version.release_segment(i) == version.release_segment(i))
+        });
+    }
+    true
+}
+
+fn lower_bound(terms: &[Term]) -> Version {
+    terms
+        .iter()
+        .flat_map(comparators)
+        .filter(|c| matches!(c.op, Op::Eq | Op::Ge | Op::Gt))
+        .map(|c| c.version)
+        .max()
+        .unwrap_or_else(|| Version::new(0, 0, 0))
+}
+
+fn rewrite_term(term: &Term, version: &Version) -> Term {
+    let upper_excludes = |term: &Term| {
+        !comparators(term)
+            .iter()
+            .all(|c| comparator_matches(c, version))
+    };
+    match term {
+        Term::Partial(p) if p.parts.is_empty() => term.clone(),
+        Term::Partial(p) => Term::Partial(p.with_version(version)),
+        Term::Caret(p) => Term::Caret(p.with_version(version)),
+        Term::Tilde(p) => Term::Tilde(p.with_version(version)),
+        // `>1.2.3` rewritten to `>1.5.0` would exclude 1.5.0 itself
+        Term::Primitive(Op::Ge | Op::Gt, p) => Term::Primitive(Op::Ge, p.with_version(version)),
+        Term::Primitive(Op::Eq, p) => Term::Primitive(Op::Eq, p.with_version(version)),
+        Term::Primitive(Op::Le, p) if upper_excludes(term) => {
+            Term::Primitive(Op::Le, p.with_version(version))
+        }
+        Term::Primitive(Op::Lt, p) if upper_excludes(term) => {
+            Term::Primitive(Op::Lt, p.bumped_past(version))
+        }
+        Term::Primitive(..) => term.clone(),
+        Term::Hyphen(from, to) => {
+            let excluded = hyphen_upper(to).is_some_and(|c| !comparator_matches(&c, version));
+            let to = if excluded {
+                to.with_version(version)
+            } else {
+                to.clone()
+            };
+            Term::Hyphen(from.with_version(version), to)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> Version {
+        Version::from_str(s).unwrap()
+    }
+
+    fn range(s: &str) -> NpmRange {
+        NpmRange::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_x_ranges_and_partials() {
+        let r = range("1.x");
+        assert!(r.satisfies(&v("1.0.0")));
+        assert!(r.satisfies(&v("1.99.3")));
+        assert!(!r.satisfies(&v("2.0.0")));
+
+        let r = range("1.2");
+        assert!(r.satisfies(&v("1.2.9")));
+        assert!(!r.satisfies(&v("1.3.0")));
+
+        assert!(range("*").satisfies(&v("42.0.0")));
+        assert!(range("").satisfies(&v("0.0.1")));
+        assert!(!range(">2").satisfies(&v("2.9.9")));
+        assert!(range(">2").satisfies(&v("3.0.0")));
+        assert!(range("<=1.2").satisfies(&v("1.2.9")));
+    }
+
+    #[test]
+    fn test_hyphen_and_comparator_sets() {
+        let r = range("1.2 - 1.4");
+        assert!(r.satisfies(&v("1.2.0")));
+        assert!(r.satisfies(&v("1.4.7")));
+        assert!(!r.satisfies(&v("1.5.0")));
+
+        let r = range(">=1 <2");
+        assert!(r.satisfies(&v("1.9.0")));
+        assert!(!r.satisfies(&v("2.0.0")));
+
+        let r = range(">= 1.2.3 < 1.5");
+        assert!(r.satisfies(&v("1.4.0")));
+        assert!(!r.satisfies(&v("1.5.0")));
+    }
+
+    #[test]
+    fn test_unions_and_partial_carets() {
+        let r = range("^1 || ^2");
+        assert!(r.satisfies(&v("1.5.0")));
+        assert!(r.satisfies(&v("2.1.0")));
+        assert!(!r.satisfies(&v("3.0.0")));
+        assert_eq!(r.base_version(), &v("2.0.0"));
+        assert_eq!(r.lowest_version(), v("1.0.0"));
+
+        assert!(range("^0.2").satisfies(&v("0.2.5")));
+        assert!(!range("^0.2").satisfies(&v("0.3.0")));
+        assert!(range("~1").satisfies(&v("1.9.0")));
+    }
+
+    #[test]
+    fn test_prerelease_rules() {
+        let r = range(">=1.2.3-beta.1 <2");
+        assert!(r.satisfies(&v("1.2.3-beta.2")));
+        assert!(!r.satisfies(&v("1.3.0-beta.1")));
+        assert!(!range("1.x").satisfies(&v("1.3.0-rc.1")));
+
+        let r = range("1.x").with_prerelease(true);
+        assert!(r.satisfies(&v("1.3.0-rc.1")));
+    }
+
+    #[test]
+    fn test_with_version_rewrites() {
+        assert_eq!(
+            range("^1 || ^2").with_version(&v("3.1.0")).to_string(),
+            "^1 || ^3"
+        );
+        assert_eq!(
+            range("^1.0.0 || ^2.0.0")
+                .with_version(&v("1.4.0"))
+                .to_string(),
+            "^1.4.0 || ^2.0.0"
+        );
+        assert_eq!(range("1.x").with_version(&v("2.3.0")).to_string(), "2.x");
+        assert_eq!(
+            range(">=1 <2").with_version(&v("3.1.0")).to_string(),
+            ">=3 <4"
+        );
+        assert_eq!(
+            range(">=1.2 <1.5").with_version(&v("1.4.0")).to_string(),
+            ">=1.4 <1.5"
+        );
+        assert_eq!(
+            range("1.2 - 1.4").with_version(&v("1.6.2")).to_string(),
+            "1.6 - 1.6"
+        );
+    }
+
+    #[test]
+    fn test_into_spec_simplifies() {
+        assert!(matches!(range("^1.2.3").into_spec(), VersionSpec::Caret(_)));
+        assert!(matches!(range("1.2.3").into_spec(), VersionSpec::Pinned(_)));
+        assert!(matches!(range("*").into_spec(), VersionSpec::Any));
+        assert!(matches!(
+            range("^1.2").into_spec(),
+            VersionSpec::NpmRange(_)
+        ));
+        assert!(NpmRange::parse("not-a-range").is_err());
+    }
+}
@@ -1,5 +1,8 @@
+use crate::date::Timestamp;
+use crate::summary::Summary;
 use crate::types::{DependencyCheck, UpdateSeverity};
 use colored::Colorize;
+use std::collections::BTreeSet;
 
 /// Renders the dependency check results in a table format
 pub struct TableRenderer {
@@ -19,6 +22,63 @@ impl TableRenderer {
             .collect();
 
         self.render_deduped(&checks_with_updates, header);
+        self.render_too_new(checks);
+        self.render_blocked(checks);
+        self.render_summary(checks);
+    }
+
+    /// Print the project's libyears, oldest dependency and severity counts
+    pub fn render_summary(&self, checks: &[DependencyCheck]) {
+        if checks.is_empty() {
+            return;
+        }
+        let line = format!("\nSummary: {}", Summary::from_checks(checks));
+        if self.show_colors {
+            println!("{}", line.dimmed());
+        } else {
+            println!("{line}");
+        }
+    }
+
+    /// List versions skipped by `--min-age` for packages not already in the table
+    pub fn render_too_new(&self, checks: &[DependencyCheck]) {
+        let skipped: BTreeSet<String> = checks
+            .iter()
+            .filter(|c| !c.has_update())
+            .filter_map(|c| Some(format!("{} {}", c.dependency.display_name(), c.too_new.as_ref()?)))
+            .collect();
+        if skipped.is_empty() {
+            return;
+        }
+
+        let line = format!("\nToo new for --min-age: {}", Vec::from_iter(skipped).join(", "));
+        if self.show_colors {
+            println!("{}", line.dimmed());
+        } else {
+            println!("{line}");
+        }
+    }
+
+    /// List versions the project can't use, for packages not already in the table
+    pub fn render_blocked(&self, checks: &[DependencyCheck]) {
+        let blocked: BTreeSet<String> = checks
+            .iter()
+            .filter(|c| !c.has_update())
+            .filter_map(|c| {
+                let blocked = c.blocked.as_ref()?;
+                Some(format!("{} {} ({})", c.dependency.display_name(), blocked.version, blocked.reason))
+            })
+            .collect();
+        if blocked.is_empty() {
+            return;
+        }
+
+        let line = format!("\nHeld back by compatibility: {}", Vec::from_iter(blocked).join(", "));
+        if self.show_colors {
+            println!("{}", line.dimmed());
+        } else {
+            println!("{line}");
+        }
     }
 
     /// Render a deduplicated list of checks
@@ -31,7 +91,7 @@ impl TableRenderer {
         // Calculate column widths
         let max_name = checks
             .iter()
-            .map(|c| c.dependency.name.len())
+            .map(|c| c.dependency.display_name().len())
             .max()
             .unwrap_or(0);
 
@@ -76,15 +136,49 @@ impl TableRenderer {
 
         let severity_str = self.format_severity(check.severity);
 
-        let available_hint = if check.has_newer_available() {
+        let mut available_hint = if check.has_newer_available() {
             format!("  ({} available)", check.latest)
         } else {
             String::new()
         };
+        if let Some(too_new) = &check.too_new {
+            let too_new = format!("  ({too_new} too new)");
+            if self.show_colors {
+                available_hint.push_str(&too_new.dimmed().to_string());
+            } else {
+                available_hint.push_str(&too_new);
+            }
+        }
+        if let Some(blocked) = &check.blocked {
+            let blocked = format!("  ({} {})", blocked.version, blocked.reason);
+            if self.show_colors {
+                available_hint.push_str(&blocked.dimmed().to_string());
+            } else {
+                available_hint.push_str(&blocked);
+            }
+        }
+        if check.is_stale(Timestamp::now())
+            && let Some(cached) = check.cached_at
+        {
+            let stale = format!("  (cached {cached})");
+            if self.show_colors {
+                available_hint.push_str(&stale.dimmed().to_string());
+            } else {
+                available_hint.push_str(&stale);
+            }
+        }
+        if let Some(held) = &check.held {
+            let held = format!("  [{held}]");
+            if self.show_colors {
+                available_hint.push_str(&held.dimmed().to_string());
+            } else {
+                available_hint.push_str(&held);
+            }
+        }
 
         println!(
             "  {:<name_w$}  {:>from_w$} → {:<to_w$}  {}{}",
-            check.dependency.name,
+            check.dependency.display_name(),
             from,
             to,
             severity_str,
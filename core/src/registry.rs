@@ -0,0 +1,347 @@
+use crate::date::Timestamp;
+use crate::http::is_not_cached;
+use crate::types::PackageInfo;
+use crate::version::Version;
+use serde::{de, Deserialize, Deserializer};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::future::Future;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use thiserror::Error;
+
+/// Base URL of a registry or mirror, from `--registry`, the tool's
+/// `*_REGISTRY` variable or `registry` in the config's `[defaults]`
+///
+/// Must be an absolute `http` or `https` URL; a trailing slash is dropped so
+/// paths can be appended with `format!("{url}/{name}")`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryUrl(String);
+
+impl RegistryUrl {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for RegistryUrl {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let url = reqwest::Url::parse(s.trim())
+            .map_err(|e| format!("invalid registry URL '{s}': {e}"))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("invalid registry URL '{s}': expected http or https"));
+        }
+        Ok(Self(url.as_str().trim_end_matches('/').to_string()))
+    }
+}
+
+impl fmt::Display for RegistryUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for RegistryUrl {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// The registry answered that the package doesn't exist
+///
+/// Clients return this (possibly wrapped in context) for a `404`, so that it
+/// can be told apart from lookups that failed for other reasons.
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct NotFound(pub String);
+
+/// A failed package lookup
+///
+/// Wraps whatever the client produced (usually an `anyhow::Error` with
+/// context), keeping its source chain. Displays the whole chain, so the
+/// underlying cause (`rate limited`, `server error 502`) isn't lost behind
+/// the context.
+#[derive(Debug)]
+pub struct RegistryError(Box<dyn Error + Send + Sync>);
+
+impl RegistryError {
+    pub fn new(error: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        Self(error.into())
+    }
+
+    /// The wrapped error followed by its sources
+    fn chain(&self) -> Vec<&(dyn Error + 'static)> {
+        let mut chain = Vec::new();
+        let mut next: Option<&(dyn Error + 'static)> = Some(&*self.0);
+        while let Some(e) = next {
+            chain.push(e);
+            next = e.source();
+        }
+        chain
+    }
+
+    /// Whether the lookup failed only because `--offline` had nothing cached
+    pub fn is_not_cached(&self) -> bool {
+        is_not_cached(self.chain())
+    }
+
+    /// Whether the registry said the package doesn't exist
+    pub fn is_not_found(&self) -> bool {
+        self.chain().iter().any(|e| e.is::<NotFound>())
+    }
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, e) in self.chain().iter().enumerate() {
+            if i > 0 {
+                f.write_str(": ")?;
+            }
+            write!(f, "{e}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for RegistryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.source()
+    }
+}
+
+/// Result of fetching multiple packages
+#[derive(Debug, Clone, Default)]
+pub struct GetPackagesResult {
+    pub packages: HashMap<String, PackageInfo>,
+    /// `"name: message"` for each package the registry doesn't have
+    pub not_found: Vec<String>,
+    /// `"name: message"` for lookups that failed for any other reason
+    /// (network errors, server errors or throttling that outlasted the retries)
+    pub errors: Vec<String>,
+    /// Packages with nothing in the cache (`--offline` only)
+    pub uncached: Vec<String>,
+}
+
+impl GetPackagesResult {
+    /// Whether every lookup failed (not just missed the cache)
+    pub fn all_failed(&self) -> bool {
+        self.packages.is_empty() && !(self.errors.is_empty() && self.not_found.is_empty())
+    }
+
+    /// Every failure, not-found ones first, for an error message
+    pub fn failures(&self) -> String {
+        [&self.not_found[..], &self.errors[..]].concat().join("\n")
+    }
+}
+
+/// A package registry: PyPI, crates.io, npm, or a test fixture
+///
+/// Concurrency and retries are the client's business (see
+/// `http::HttpClient`); `get_packages` starts every lookup at once.
+pub trait RegistryClient: Clone + Send + Sync + 'static {
+    /// Fetch one package's versions
+    fn get_package(
+        &self,
+        name: &str,
+    ) -> impl Future<Output = Result<PackageInfo, RegistryError>> + Send;
+
+    /// Fetch many packages concurrently, calling `progress(done, total)` as
+    /// each one finishes
+    fn get_packages(
+        &self,
+        names: &[String],
+        progress: impl Fn(usize, usize) + Send + Sync + 'static,
+    ) -> impl Future<Output = GetPackagesResult> + Send {
+        let client = self.clone();
+        let names = names.to_vec();
+        async move {
+            let total = names.len();
+            let progress = Arc::new(progress);
+            let done = Arc::new(AtomicUsize::new(0));
+
+            let tasks: Vec<_> = names
+                .into_iter()
+                .map(|name| {
+                    let client = client.clone();
+                    let progress = Arc::clone(&progress);
+                    let done = Arc::clone(&done);
+                    tokio::spawn(async move {
+                        let result = client.get_package(&name).await;
+                        progress(done.fetch_add(1, Ordering::SeqCst) + 1, total);
+                        (name, result)
+                    })
+                })
+                .collect();
+
+            let mut result = GetPackagesResult::default();
+            for task in tasks {
+                match task.await {
+                    Ok((name, Ok(info))) => {
+                        result.packages.insert(name, info);
+                    }
+                    Ok((name, Err(e))) if e.is_not_cached() => result.uncached.push(name),
+                    Ok((name, Err(e))) if e.is_not_found() => {
+                        result.not_found.push(format!("{name}: {e}"));
+                    }
+                    Ok((name, Err(e))) => result.errors.push(format!("{name}: {e}")),
+                    Err(e) => result.errors.push(format!("unknown: Task failed: {e}")),
+                }
+            }
+            result
+        }
+    }
+}
+
+/// A package in a fixture file: `{"versions": {"1.0.0": "2020-01-01T00:00:00Z", "1.1.0": null}}`
+#[derive(Debug, Deserialize)]
+struct FixturePackage {
+    name: Option<String>,
+    versions: BTreeMap<String, Option<String>>,
+}
+
+/// In-memory registry for tests, optionally loaded from a directory of
+/// `<package>.json` files
+#[derive(Debug, Clone, Default)]
+pub struct FixtureRegistry {
+    packages: Arc<HashMap<String, PackageInfo>>,
+}
+
+impl FixtureRegistry {
+    pub fn new(packages: impl IntoIterator<Item = PackageInfo>) -> Self {
+        Self {
+            packages: Arc::new(
+                packages
+                    .into_iter()
+                    .map(|p| (p.name.to_lowercase(), p))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Load every `*.json` fixture in `dir`; pre-releases are dropped unless
+    /// `include_prerelease` is set, as the real clients do
+    pub fn from_dir(dir: &Path, include_prerelease: bool) -> io::Result<Self> {
+        let mut packages = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|e| e != "json") {
+                continue;
+            }
+            let fixture: FixturePackage = serde_json::from_str(&fs::read_to_string(&path)?)
+                .map_err(|e| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{}: {e}", path.display()),
+                    )
+                })?;
+            let stem = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default();
+            let name = fixture.name.clone().unwrap_or_else(|| stem.to_string());
+            if let Some(info) = fixture.into_package_info(name, include_prerelease) {
+                packages.push(info);
+            }
+        }
+        Ok(Self::new(packages))
+    }
+}
+
+impl FixturePackage {
+    fn into_package_info(self, name: String, include_prerelease: bool) -> Option<PackageInfo> {
+        let mut published = BTreeMap::new();
+        let mut versions: Vec<Version> = Vec::new();
+        for (version, time) in self.versions {
+            let Ok(version) = version.parse::<Version>() else {
+                continue;
+            };
+            if !include_prerelease && version.is_prerelease() {
+                continue;
+            }
+            if let Some(time) = time.as_deref().and_then(Timestamp::parse) {
+                published.insert(version.clone(), time);
+            }
+            versions.push(version);
+        }
+        versions.sort();
+
+        let latest = versions.last()?.clone();
+        let latest_stable = versions.iter().rfind(|v| !v.is_prerelease()).cloned();
+        Some(PackageInfo {
+            name,
+            versions,
+            latest,
+            latest_stable,
+            published,
+            requires: BTreeMap::new(),
+            cached_at: None,
+        })
+    }
+}
+
+impl RegistryClient for FixtureRegistry {
+    async fn get_package(&self, name: &str) -> Result<PackageInfo, RegistryError> {
+        self.packages
+            .get(&name.to_lowercase())
+            .cloned()
+            .ok_or_else(|| {
+                RegistryError::new(NotFound(format!("Package '{name}' not found in fixtures")))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fixture_registry() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("requests.json"),
+            r#"{"versions": {"2.28.0": "2022-06-29T00:00:00Z", "2.32.3": null, "3.0.0b1": null}}"#,
+        )?;
+        fs::write(dir.path().join("notes.txt"), "ignored")?;
+
+        let registry = FixtureRegistry::from_dir(dir.path(), false)?;
+        let info = registry.get_package("Requests").await?;
+        assert_eq!(info.latest.to_string(), "2.32.3");
+        assert_eq!(info.versions.len(), 2);
+        assert_eq!(info.published.len(), 1);
+
+        let result = registry
+            .get_packages(&["requests".to_string(), "nope".to_string()], |_, _| {})
+            .await;
+        assert_eq!(result.packages.len(), 1);
+        assert_eq!(
+            result.not_found,
+            ["nope: Package 'nope' not found in fixtures"]
+        );
+        assert!(result.errors.is_empty());
+        assert!(!result.all_failed());
+        Ok(())
+    }
+
+    #[test]
+    fn test_registry_url() {
+        let url: RegistryUrl = "https://artifactory.example.com/api/npm/npm-remote/"
+            .parse()
+            .unwrap();
+        assert_eq!(url.as_str(), "https://artifactory.example.com/api/npm/npm-remote");
+        assert_eq!(
+            "http://localhost:8080".parse::<RegistryUrl>().unwrap().to_string(),
+            "http://localhost:8080"
+        );
+        assert!("registry.npmjs.org".parse::<RegistryUrl>().is_err());
+        assert!("ftp://mirror.example.com".parse::<RegistryUrl>().is_err());
+    }
+}
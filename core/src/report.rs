@@ -0,0 +1,429 @@
+use crate::date::Timestamp;
+use crate::ecosystem::Ecosystem;
+use crate::summary::{round_libyears, Summary};
+use crate::types::{DependencyCheck, UpdateSeverity};
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// Version of the machine-readable report schema
+///
+/// Bumped whenever a field is renamed, removed or changes meaning. Adding a
+/// new field does not bump it, so consumers should ignore unknown keys.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// How results are written to stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Human-readable colored table
+    #[default]
+    Table,
+    /// A single pretty-printed JSON document
+    Json,
+    /// One JSON object per line, each tagged with a `type`
+    Ndjson,
+}
+
+impl OutputFormat {
+    /// Whether human-oriented output (tables, hints, headers) should be printed
+    pub fn is_human(self) -> bool {
+        self == OutputFormat::Table
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" | "jsonl" => Ok(OutputFormat::Ndjson),
+            _ => Err(format!("unknown format '{s}' (expected table, json or ndjson)")),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Table => write!(f, "table"),
+            OutputFormat::Json => write!(f, "json"),
+            OutputFormat::Ndjson => write!(f, "ndjson"),
+        }
+    }
+}
+
+/// Whether a report covers a project's manifests or globally installed packages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportMode {
+    Project,
+    Global,
+}
+
+/// A project dependency as written to JSON
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyRecord {
+    pub name: String,
+    /// Real package name, for a dependency renamed in the manifest
+    pub package: Option<String>,
+    pub source_file: String,
+    pub line: usize,
+    /// Requirement as written in the manifest
+    pub spec: String,
+    pub installed: Option<String>,
+    /// Latest version satisfying `spec`
+    pub in_range: Option<String>,
+    pub latest: String,
+    /// Version a default update would move to
+    pub target: Option<String>,
+    pub severity: Option<UpdateSeverity>,
+    pub has_update: bool,
+    /// Requirement written by a default update
+    pub proposed_spec: Option<String>,
+    /// Requirement written by a forced update to latest
+    pub force_spec: Option<String>,
+    /// Config rule that capped the target, if any
+    pub held: Option<String>,
+    /// Newest version skipped for being younger than `--min-age`
+    pub too_new: Option<String>,
+    /// Newest version the project can't use
+    pub blocked: Option<String>,
+    /// Why it can't, e.g. `requires Rust 1.80`
+    pub blocked_reason: Option<String>,
+    /// Publish date of the current version (`YYYY-MM-DD`)
+    pub installed_released: Option<String>,
+    /// Publish date of the latest version (`YYYY-MM-DD`)
+    pub latest_released: Option<String>,
+    /// Years between those two releases
+    pub libyears: Option<f64>,
+    /// When the registry data was cached, if it came from the cache
+    pub cached_at: Option<String>,
+    /// Cached data older than `cache::STALE_AFTER`
+    pub stale: bool,
+}
+
+impl DependencyRecord {
+    /// Build a record, formatting specs in the ecosystem's manifest syntax
+    pub fn from_check(check: &DependencyCheck, ecosystem: &dyn Ecosystem) -> Self {
+        let dep = &check.dependency;
+        let format = |spec| ecosystem.format_spec(spec);
+        Self {
+            name: dep.name.clone(),
+            package: dep.package.clone(),
+            source_file: dep.source_file.display().to_string(),
+            line: dep.line_number,
+            spec: format(&dep.version_spec).unwrap_or_else(|| dep.version_spec.to_string()),
+            installed: check.installed.as_ref().map(ToString::to_string),
+            in_range: check.in_range.as_ref().map(ToString::to_string),
+            latest: check.latest.to_string(),
+            target: check.target.as_ref().map(ToString::to_string),
+            severity: check.severity,
+            has_update: check.has_update(),
+            proposed_spec: check.target_spec.as_ref().and_then(format),
+            force_spec: check.force_spec.as_ref().and_then(format),
+            held: check.held.clone(),
+            too_new: check.too_new.as_ref().map(ToString::to_string),
+            blocked: check.blocked.as_ref().map(|b| b.version.to_string()),
+            blocked_reason: check.blocked.as_ref().map(|b| b.reason.clone()),
+            installed_released: check.installed_released.map(|t| t.to_string()),
+            latest_released: check.latest_released.map(|t| t.to_string()),
+            libyears: check.libyears().map(round_libyears),
+            cached_at: check.cached_at.map(|t| t.to_string()),
+            stale: check.is_stale(Timestamp::now()),
+        }
+    }
+}
+
+/// A globally installed package as written to JSON
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalRecord {
+    pub name: String,
+    /// Where the package was installed from (`uv`, `pipx`, `npm`, `crates.io`, `git`, ...)
+    pub source: String,
+    pub installed: String,
+    pub latest: Option<String>,
+    pub severity: Option<UpdateSeverity>,
+    pub has_update: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub python_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commits_behind: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_dirty_changes: Option<bool>,
+}
+
+impl GlobalRecord {
+    pub fn new(name: &str, source: &str, installed: &str, latest: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            source: source.to_string(),
+            installed: installed.to_string(),
+            latest,
+            severity: None,
+            has_update: false,
+            python_version: None,
+            latest_hash: None,
+            commits_behind: None,
+            has_dirty_changes: None,
+        }
+    }
+
+    pub fn with_update(mut self, has_update: bool, severity: Option<UpdateSeverity>) -> Self {
+        self.has_update = has_update;
+        self.severity = severity;
+        self
+    }
+}
+
+/// Why a registry lookup failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FetchErrorKind {
+    /// The registry says the package doesn't exist
+    NotFound,
+    /// Network errors, server errors or throttling that outlasted the retries
+    Failed,
+}
+
+/// A package whose registry lookup failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FetchError {
+    pub name: String,
+    pub kind: FetchErrorKind,
+    pub message: String,
+}
+
+impl FetchError {
+    pub fn new(name: &str, message: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind: FetchErrorKind::Failed,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn with_kind(mut self, kind: FetchErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Parse the `"name: message"` strings produced by the registry clients
+    pub fn from_formatted(error: &str) -> Self {
+        match error.split_once(": ") {
+            Some((name, message)) => Self::new(name, message),
+            None => Self::new("", error),
+        }
+    }
+}
+
+/// Machine-readable results of a single run
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub schema_version: u32,
+    /// Tool that produced the report (`pcu`, `ccu`, `ncu`)
+    pub tool: String,
+    pub mode: ReportMode,
+    pub dependencies: Vec<DependencyRecord>,
+    pub global: Vec<GlobalRecord>,
+    /// Project totals (project mode only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<Summary>,
+    pub errors: Vec<FetchError>,
+    /// Packages with no cached data in `--offline` mode
+    pub uncached: Vec<String>,
+    /// Files rewritten by `--update`
+    pub updated_files: Vec<String>,
+}
+
+/// A single NDJSON line
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Line<'a> {
+    Header {
+        schema_version: u32,
+        tool: &'a str,
+        mode: ReportMode,
+    },
+    Dependency(&'a DependencyRecord),
+    Global(&'a GlobalRecord),
+    Summary(&'a Summary),
+    Error(&'a FetchError),
+    Uncached {
+        name: &'a str,
+    },
+    Updated {
+        file: &'a str,
+    },
+}
+
+impl Report {
+    pub fn new(tool: &str, mode: ReportMode) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            tool: tool.to_string(),
+            mode,
+            dependencies: Vec::new(),
+            global: Vec::new(),
+            summary: None,
+            errors: Vec::new(),
+            uncached: Vec::new(),
+            updated_files: Vec::new(),
+        }
+    }
+
+    /// Add every check, including those already up to date, and summarize them
+    pub fn add_checks(&mut self, checks: &[DependencyCheck], ecosystem: &dyn Ecosystem) {
+        self.dependencies
+            .extend(checks.iter().map(|c| DependencyRecord::from_check(c, ecosystem)));
+        self.summary = Some(Summary::from_checks(checks));
+    }
+
+    /// Add fetch errors in the `"name: message"` form
+    pub fn add_formatted_errors(&mut self, errors: &[String]) {
+        self.errors
+            .extend(errors.iter().map(|e| FetchError::from_formatted(e)));
+    }
+
+    /// Add packages the registry doesn't have, in the `"name: message"` form
+    pub fn add_not_found(&mut self, not_found: &[String]) {
+        self.errors.extend(
+            not_found
+                .iter()
+                .map(|e| FetchError::from_formatted(e).with_kind(FetchErrorKind::NotFound)),
+        );
+    }
+
+    /// Serialize as a single JSON document
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Serialize as NDJSON: a header line followed by one line per record
+    pub fn to_ndjson(&self) -> serde_json::Result<String> {
+        let header = Line::Header {
+            schema_version: self.schema_version,
+            tool: &self.tool,
+            mode: self.mode,
+        };
+        let lines = std::iter::once(header)
+            .chain(self.dependencies.iter().map(Line::Dependency))
+            .chain(self.global.iter().map(Line::Global))
+            .chain(self.summary.iter().map(Line::Summary))
+            .chain(self.errors.iter().map(Line::Error))
+            .chain(self.uncached.iter().map(|n| Line::Uncached { name: n }))
+            .chain(self.updated_files.iter().map(|f| Line::Updated { file: f }));
+
+        let mut out = String::new();
+        for line in lines {
+            out.push_str(&serde_json::to_string(&line)?);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// Print to stdout in the given format (no-op for `Table`)
+    pub fn print(&self, format: OutputFormat) -> serde_json::Result<()> {
+        match format {
+            OutputFormat::Table => {}
+            OutputFormat::Json => println!("{}", self.to_json()?),
+            OutputFormat::Ndjson => print!("{}", self.to_ndjson()?),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecosystem::GenericEcosystem;
+    use crate::types::Dependency;
+    use crate::version::{Version, VersionSpec};
+    use std::path::PathBuf;
+
+    fn check() -> DependencyCheck {
+        let v = |s: &str| s.parse::<Version>().unwrap();
+        DependencyCheck {
+            dependency: Dependency {
+                name: "requests".to_string(),
+                registry: None,
+                package: None,
+                version_spec: VersionSpec::parse(">=2.28.0").unwrap(),
+                source_file: PathBuf::from("requirements.txt"),
+                line_number: 3,
+                original_line: "requests>=2.28.0".to_string(),
+            },
+            installed: Some(v("2.28.1")),
+            in_range: Some(v("3.0.0")),
+            latest: v("3.0.0"),
+            target: Some(v("3.0.0")),
+            target_spec: Some(VersionSpec::parse(">=3.0.0").unwrap()),
+            severity: Some(UpdateSeverity::Major),
+            force_spec: None,
+            held: None,
+            too_new: None,
+            blocked: None,
+            installed_released: None,
+            latest_released: None,
+            cached_at: None,
+        }
+    }
+
+    #[test]
+    fn test_output_format_parse() {
+        assert_eq!("JSON".parse::<OutputFormat>(), Ok(OutputFormat::Json));
+        assert_eq!("ndjson".parse::<OutputFormat>(), Ok(OutputFormat::Ndjson));
+        assert!("yaml".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn test_json_report() {
+        let mut report = Report::new("pcu", ReportMode::Project);
+        report.add_checks(&[check()], &GenericEcosystem);
+        report.add_not_found(&["nope: not found".to_string()]);
+        report.add_formatted_errors(&["slow: server error 502 Bad Gateway after 4 attempts".to_string()]);
+
+        let value: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(value["schema_version"], SCHEMA_VERSION);
+        assert_eq!(value["mode"], "project");
+        let dep = &value["dependencies"][0];
+        assert_eq!(dep["name"], "requests");
+        assert_eq!(dep["line"], 3);
+        assert_eq!(dep["spec"], ">=2.28.0");
+        assert_eq!(dep["severity"], "major");
+        assert_eq!(dep["proposed_spec"], ">=3.0.0");
+        assert!(dep["force_spec"].is_null());
+        assert_eq!(value["errors"][0]["name"], "nope");
+        assert_eq!(value["errors"][0]["message"], "not found");
+        assert_eq!(value["errors"][0]["kind"], "not_found");
+        assert_eq!(value["errors"][1]["kind"], "failed");
+        assert_eq!(value["summary"]["dependencies"], 1);
+        assert_eq!(value["summary"]["major"], 1);
+        assert!(dep["libyears"].is_null());
+    }
+
+    #[test]
+    fn test_ndjson_report() {
+        let mut report = Report::new("ncu", ReportMode::Global);
+        report.global.push(
+            GlobalRecord::new("typescript", "npm", "5.4.5", Some("5.6.3".to_string()))
+                .with_update(true, Some(UpdateSeverity::Minor)),
+        );
+
+        let out = report.to_ndjson().unwrap();
+        let lines: Vec<serde_json::Value> = out
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["type"], "header");
+        assert_eq!(lines[0]["tool"], "ncu");
+        assert_eq!(lines[1]["type"], "global");
+        assert_eq!(lines[1]["severity"], "minor");
+        assert!(lines[1].get("commits_behind").is_none());
+        assert!(lines.iter().all(|l| l["type"] != "summary"));
+    }
+}
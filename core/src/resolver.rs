@@ -1,12 +1,57 @@
-use crate::types::{Dependency, DependencyCheck, PackageInfo, UpdateSeverity};
+use crate::date::{Age, Timestamp};
+use crate::ecosystem::{Ecosystem, GenericEcosystem};
+use crate::types::{Blocked, Dependency, DependencyCheck, PackageInfo};
 use crate::version::{Version, VersionSpec};
+use std::collections::BTreeMap;
 
 /// Resolves dependencies and determines what updates are available
-pub struct DependencyResolver;
+pub struct DependencyResolver {
+    ecosystem: Box<dyn Ecosystem>,
+    min_age: Option<Age>,
+    now: Timestamp,
+    filter: Option<Box<VersionFilter>>,
+}
+
+/// Why a version can't be used, or `None` if it can
+type VersionFilter = dyn Fn(&Dependency, &PackageInfo, &Version) -> Option<String>;
 
 impl DependencyResolver {
     pub fn new() -> Self {
-        Self
+        Self {
+            ecosystem: Box::new(GenericEcosystem),
+            min_age: None,
+            now: Timestamp::now(),
+            filter: None,
+        }
+    }
+
+    /// Use an ecosystem's native spec and severity semantics
+    pub fn with_ecosystem(mut self, ecosystem: impl Ecosystem + 'static) -> Self {
+        self.ecosystem = Box::new(ecosystem);
+        self
+    }
+
+    /// Skip versions published less than `min_age` ago (versions without a
+    /// publish time are kept)
+    pub fn with_min_age(mut self, min_age: Option<Age>) -> Self {
+        self.min_age = min_age;
+        self
+    }
+
+    /// Leave out versions the project can't use: `filter` returns why a
+    /// version of a dependency is incompatible (`requires Rust 1.80`), or
+    /// `None` if it's fine
+    pub fn with_filter(
+        mut self,
+        filter: impl Fn(&Dependency, &PackageInfo, &Version) -> Option<String> + 'static,
+    ) -> Self {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// The ecosystem whose rules this resolver applies
+    pub fn ecosystem(&self) -> &dyn Ecosystem {
+        self.ecosystem.as_ref()
     }
 
     /// Resolve a single dependency
@@ -15,6 +60,70 @@ impl DependencyResolver {
         dependency: &Dependency,
         package_info: &PackageInfo,
         installed: Option<&Version>,
+    ) -> DependencyCheck {
+        let old_enough = |v: &Version| {
+            self.min_age.is_none_or(|min_age| {
+                package_info
+                    .published
+                    .get(v)
+                    .is_none_or(|&published| min_age.has_passed(published, self.now))
+            })
+        };
+        // Pre-releases only count where the registry would offer one as latest
+        let too_new = package_info
+            .versions
+            .iter()
+            .filter(|v| !old_enough(v))
+            .filter(|v| package_info.latest.is_prerelease() || !v.is_prerelease())
+            .max()
+            .cloned();
+
+        // Why each version old enough to be a target can't be used
+        let incompatible: BTreeMap<&Version, String> = match &self.filter {
+            Some(filter) => package_info
+                .versions
+                .iter()
+                .filter(|v| old_enough(v))
+                .filter_map(|v| Some((v, filter(dependency, package_info, v)?)))
+                .collect(),
+            None => BTreeMap::new(),
+        };
+        if too_new.is_none() && incompatible.is_empty() {
+            return self.resolve_versions(dependency, package_info, installed);
+        }
+
+        let allow = |v: &Version| old_enough(v) && !incompatible.contains_key(v);
+        let newest_allowed = package_info.versions.iter().filter(|v| allow(v)).max();
+        let blocked = incompatible
+            .iter()
+            .next_back()
+            .filter(|(v, _)| newest_allowed.is_none_or(|allowed| **v > allowed))
+            .map(|(v, reason)| Blocked {
+                version: (*v).clone(),
+                reason: reason.clone(),
+            });
+
+        let mut check = match narrow(package_info, allow) {
+            Some(info) => self.resolve_versions(dependency, &info, installed),
+            None => DependencyCheck {
+                target: None,
+                target_spec: None,
+                severity: None,
+                force_spec: None,
+                ..self.resolve_versions(dependency, package_info, installed)
+            },
+        };
+        // Only worth mentioning if they would have been updates
+        check.too_new = too_new.filter(|v| check.current_version().is_none_or(|c| v > c));
+        check.blocked = blocked.filter(|b| check.current_version().is_none_or(|c| b.version > *c));
+        check
+    }
+
+    fn resolve_versions(
+        &self,
+        dependency: &Dependency,
+        package_info: &PackageInfo,
+        installed: Option<&Version>,
     ) -> DependencyCheck {
         let latest = package_info.latest.clone();
 
@@ -36,7 +145,9 @@ impl DependencyResolver {
         );
 
         // Calculate severity based on current → target
-        let severity = Self::calculate_severity(current, target.as_ref());
+        let severity = current
+            .zip(target.as_ref())
+            .and_then(|(current, target)| self.ecosystem.severity(current, target));
 
         // Calculate force spec (to absolute latest)
         let force_spec = self.calculate_force_spec(
@@ -45,6 +156,9 @@ impl DependencyResolver {
             current,
         );
 
+        let installed_released = current.and_then(|v| package_info.published.get(v).copied());
+        let latest_released = package_info.published.get(&latest).copied();
+
         DependencyCheck {
             dependency: dependency.clone(),
             installed: installed.cloned(),
@@ -54,9 +168,51 @@ impl DependencyResolver {
             target_spec,
             severity,
             force_spec,
+            held: None,
+            too_new: None,
+            blocked: None,
+            installed_released,
+            latest_released,
+            cached_at: package_info.cached_at,
         }
     }
 
+    /// Re-resolve a check against only the versions `allow` accepts
+    ///
+    /// Used for holds and other policies. `held` records why; `latest` is
+    /// kept from the original check so the table can still show what's held
+    /// back. The check is returned unchanged if nothing was excluded.
+    pub fn restrict(
+        &self,
+        check: DependencyCheck,
+        info: &PackageInfo,
+        allow: impl Fn(&Version) -> bool,
+        held: String,
+    ) -> DependencyCheck {
+        if info.versions.iter().all(&allow) {
+            return check;
+        }
+
+        let Some(restricted) = narrow(info, allow) else {
+            return DependencyCheck {
+                target: None,
+                target_spec: None,
+                severity: None,
+                force_spec: None,
+                held: Some(held),
+                ..check
+            };
+        };
+
+        let latest = check.latest.clone();
+        let latest_released = check.latest_released;
+        let mut check = self.resolve(&check.dependency, &restricted, check.installed.as_ref());
+        check.latest = latest;
+        check.latest_released = latest_released;
+        check.held = Some(held);
+        check
+    }
+
     /// Calculate the target version and spec for display
     fn calculate_target(
         &self,
@@ -113,25 +269,6 @@ impl DependencyResolver {
         }
     }
 
-    /// Calculate the severity of an update
-    pub fn calculate_severity(
-        current: Option<&Version>,
-        target: Option<&Version>,
-    ) -> Option<UpdateSeverity> {
-        let current = current?;
-        let target = target?;
-
-        if target.major > current.major {
-            Some(UpdateSeverity::Major)
-        } else if target.minor > current.minor {
-            Some(UpdateSeverity::Minor)
-        } else if target.patch > current.patch {
-            Some(UpdateSeverity::Patch)
-        } else {
-            None
-        }
-    }
-
     /// Calculate the latest version "in range" for the constraint
     fn calculate_in_range(
         &self,
@@ -143,7 +280,7 @@ impl DependencyResolver {
             .iter()
             .filter(|v| {
                 // Must satisfy the spec
-                if !spec.satisfies(v) {
+                if !self.ecosystem.satisfies(spec, v) {
                     return false;
                 }
 
@@ -151,11 +288,11 @@ impl DependencyResolver {
                 match spec {
                     VersionSpec::Minimum(base) | VersionSpec::GreaterThan(base) => {
                         let target_major = if let Some(inst) = installed {
-                            base.major.max(inst.major)
+                            base.major().max(inst.major())
                         } else {
-                            base.major
+                            base.major()
                         };
-                        v.major == target_major
+                        v.major() == target_major
                     }
                     _ => true,
                 }
@@ -165,6 +302,28 @@ impl DependencyResolver {
     }
 }
 
+/// `info` narrowed to the versions `allow` accepts, or `None` if none remain
+fn narrow(info: &PackageInfo, allow: impl Fn(&Version) -> bool) -> Option<PackageInfo> {
+    let versions: Vec<Version> = info.versions.iter().filter(|v| allow(v)).cloned().collect();
+
+    // Don't promote a pre-release the registry wouldn't offer as latest
+    let latest = versions
+        .iter()
+        .filter(|v| info.latest.is_prerelease() || !v.is_prerelease())
+        .max()
+        .cloned()?;
+
+    Some(PackageInfo {
+        name: info.name.clone(),
+        latest_stable: versions.iter().filter(|v| !v.is_prerelease()).max().cloned(),
+        latest,
+        published: info.published.clone(),
+        requires: info.requires.clone(),
+        cached_at: info.cached_at,
+        versions,
+    })
+}
+
 impl Default for DependencyResolver {
     fn default() -> Self {
         Self::new()
@@ -174,20 +333,23 @@ impl Default for DependencyResolver {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::UpdateSeverity;
     use std::path::PathBuf;
     use std::str::FromStr;
 
     fn create_test_dependency(name: &str, spec_str: &str) -> Dependency {
         Dependency {
             name: name.to_string(),
+            registry: None,
+            package: None,
             version_spec: VersionSpec::parse(spec_str).unwrap(),
             source_file: PathBuf::from("test.txt"),
             line_number: 1,
-            original_line: format!("{}=={}", name, spec_str),
+            original_line: format!("{name}=={spec_str}"),
         }
     }
 
-    fn create_package_info(name: &str, versions: Vec<&str>) -> PackageInfo {
+    fn create_package_info(name: &str, versions: &[&str]) -> PackageInfo {
         let version_objects: Vec<Version> = versions
             .iter()
             .map(|v| Version::from_str(v).unwrap())
@@ -199,6 +361,9 @@ mod tests {
             versions: version_objects,
             latest: latest.clone(),
             latest_stable: Some(latest),
+            published: Default::default(),
+            requires: Default::default(),
+            cached_at: None,
         }
     }
 
@@ -206,7 +371,7 @@ mod tests {
     fn test_in_range_update() {
         let resolver = DependencyResolver::new();
         let dep = create_test_dependency("requests", ">=2.28.0,<3.0.0");
-        let pkg_info = create_package_info("requests", vec!["2.28.0", "2.32.3", "3.1.0"]);
+        let pkg_info = create_package_info("requests", &["2.28.0", "2.32.3", "3.1.0"]);
 
         let installed = Version::from_str("2.28.0").unwrap();
         let result = resolver.resolve(&dep, &pkg_info, Some(&installed));
@@ -224,7 +389,7 @@ mod tests {
     fn test_force_only_update() {
         let resolver = DependencyResolver::new();
         let dep = create_test_dependency("flask", "^2.0.0");
-        let pkg_info = create_package_info("flask", vec!["2.0.0", "2.3.3", "3.0.0"]);
+        let pkg_info = create_package_info("flask", &["2.0.0", "2.3.3", "3.0.0"]);
 
         // Installed at latest in-range (2.3.3)
         let installed = Version::from_str("2.3.3").unwrap();
@@ -239,11 +404,32 @@ mod tests {
         assert!(!result.has_newer_available());
     }
 
+    #[test]
+    fn test_severity_beyond_patch_segment() {
+        let resolver = DependencyResolver::new();
+        let dep = create_test_dependency("tzdata", ">=2024.1.0.3");
+        let severity = |versions: &[&str], installed: &str| {
+            let pkg_info = create_package_info("tzdata", versions);
+            let installed = Version::from_str(installed).unwrap();
+            resolver.resolve(&dep, &pkg_info, Some(&installed)).severity
+        };
+
+        assert_eq!(
+            severity(&["2024.1.0.3", "2024.1.0.4"], "2024.1.0.3"),
+            Some(UpdateSeverity::Patch)
+        );
+        assert_eq!(
+            severity(&["2024.1.0.3", "2024.1.0.3.post1"], "2024.1.0.3"),
+            Some(UpdateSeverity::Patch)
+        );
+        assert_eq!(severity(&["2024.1.0.3"], "2024.1.0.3.post1"), None);
+    }
+
     #[test]
     fn test_no_update_needed() {
         let resolver = DependencyResolver::new();
         let dep = create_test_dependency("flask", ">=2.3.3");
-        let pkg_info = create_package_info("flask", vec!["2.0.0", "2.3.3"]);
+        let pkg_info = create_package_info("flask", &["2.0.0", "2.3.3"]);
 
         let installed = Version::from_str("2.3.3").unwrap();
         let result = resolver.resolve(&dep, &pkg_info, Some(&installed));
@@ -252,4 +438,82 @@ mod tests {
         assert!(result.target.is_none());
         assert!(!result.has_update());
     }
+
+    #[test]
+    fn test_min_age_skips_fresh_versions() {
+        let dep = create_test_dependency("requests", ">=2.28.0");
+        let mut pkg_info = create_package_info("requests", &["2.28.0", "2.32.3", "3.0.0"]);
+        let now = Timestamp::now();
+        let days_ago = |d: i64| Timestamp(now.0 - d * 86_400);
+        pkg_info.published.insert(Version::from_str("2.32.3").unwrap(), days_ago(30));
+        pkg_info.published.insert(Version::from_str("3.0.0").unwrap(), days_ago(2));
+
+        let installed = Version::from_str("2.28.0").unwrap();
+        let resolver = DependencyResolver::new().with_min_age(Some("7d".parse().unwrap()));
+        let result = resolver.resolve(&dep, &pkg_info, Some(&installed));
+        assert_eq!(result.target.unwrap().to_string(), "2.32.3");
+        assert_eq!(result.latest.to_string(), "2.32.3");
+        assert_eq!(result.too_new.unwrap().to_string(), "3.0.0");
+
+        // Nothing old enough to move to
+        let resolver = DependencyResolver::new().with_min_age(Some("90d".parse().unwrap()));
+        let result = resolver.resolve(&dep, &pkg_info, Some(&installed));
+        assert!(result.target.is_none());
+        assert!(result.force_spec.is_none());
+        assert_eq!(result.too_new.unwrap().to_string(), "3.0.0");
+
+        // The installed version is the fresh one
+        let installed = Version::from_str("3.0.0").unwrap();
+        let result = resolver.resolve(&dep, &pkg_info, Some(&installed));
+        assert!(result.target.is_none());
+        assert!(result.too_new.is_none());
+    }
+
+    #[test]
+    fn test_min_age_ignores_fresh_backports() {
+        let dep = create_test_dependency("django", ">=2.0.0");
+        let mut pkg_info =
+            create_package_info("django", &["1.9.8", "2.0.0", "1.9.9", "2.1.0rc1"]);
+        pkg_info.latest = Version::from_str("2.0.0").unwrap();
+        let now = Timestamp::now();
+        pkg_info.published.insert(Version::from_str("1.9.9").unwrap(), now);
+        pkg_info.published.insert(Version::from_str("2.1.0rc1").unwrap(), now);
+
+        // A fresh 1.9.9 backport isn't held back from a 2.0.0 install, and the
+        // fresh pre-release isn't an update for a stable dependency
+        let installed = Version::from_str("2.0.0").unwrap();
+        let resolver = DependencyResolver::new().with_min_age(Some("7d".parse().unwrap()));
+        let result = resolver.resolve(&dep, &pkg_info, Some(&installed));
+        assert!(result.target.is_none());
+        assert!(result.too_new.is_none());
+    }
+
+    #[test]
+    fn test_filter_blocks_incompatible_versions() {
+        let dep = create_test_dependency("clap", "4.4.0");
+        let mut pkg_info = create_package_info("clap", &["4.4.0", "4.4.18", "4.5.0", "4.5.20"]);
+        for (version, rust) in [("4.4.18", "1.70"), ("4.5.0", "1.74"), ("4.5.20", "1.74")] {
+            pkg_info
+                .requires
+                .insert(Version::from_str(version).unwrap(), rust.to_string());
+        }
+        let resolver = DependencyResolver::new().with_filter(|_, info, version| {
+            let required = info.requires.get(version)?;
+            (required.as_str() > "1.70").then(|| format!("requires Rust {required}"))
+        });
+
+        let installed = Version::from_str("4.4.0").unwrap();
+        let result = resolver.resolve(&dep, &pkg_info, Some(&installed));
+        assert_eq!(result.target.unwrap().to_string(), "4.4.18");
+        assert_eq!(result.latest.to_string(), "4.4.18");
+        let blocked = result.blocked.unwrap();
+        assert_eq!(blocked.version.to_string(), "4.5.20");
+        assert_eq!(blocked.reason, "requires Rust 1.74");
+
+        // Already past the newest compatible version: nothing to report
+        let installed = Version::from_str("4.5.20").unwrap();
+        let result = resolver.resolve(&dep, &pkg_info, Some(&installed));
+        assert!(result.target.is_none());
+        assert!(result.blocked.is_none());
+    }
 }
@@ -0,0 +1,320 @@
+use crate::date::Timestamp;
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// How failed registry requests are retried
+///
+/// Connection errors, timeouts, `408` and `5xx` responses are retried with
+/// jittered exponential backoff. Throttling (`429`, or a rate-limit header
+/// saying the quota is used up) waits as long as the registry asks, up to
+/// `max_wait`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Backoff before the first retry; doubled for each one after
+    pub base_delay: Duration,
+    /// Upper bound on a single backoff
+    pub max_backoff: Duration,
+    /// Longest `Retry-After` or rate-limit reset worth waiting for
+    pub max_wait: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+            max_wait: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A single attempt, no retries
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Delay before retry number `retry` (0-based): a random point in the
+    /// upper half of `base_delay * 2^retry`, capped at `max_backoff`
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let ceiling = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff);
+        ceiling / 2 + (ceiling / 2).mul_f64(jitter())
+    }
+}
+
+/// A uniformly distributed number in `[0, 1)`
+fn jitter() -> f64 {
+    // Every RandomState is seeded with fresh random keys, so this is enough
+    // to spread out retries without a dependency on `rand`
+    let bits = RandomState::new().build_hasher().finish() >> 11;
+    bits as f64 / (1u64 << 53) as f64
+}
+
+/// What to do with a response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// Success or a definitive failure (`404`, `401`, ...)
+    Done,
+    /// The registry is throttling us; wait as told if it said how long
+    Throttled(Option<Duration>),
+    /// A transient server error
+    Retry(Option<Duration>),
+}
+
+/// Classify a response by status and rate-limit headers
+pub fn classify(status: StatusCode, headers: &HeaderMap, now: Timestamp) -> Verdict {
+    let wait = retry_after(headers, now);
+    let exhausted = header(headers, "x-ratelimit-remaining")
+        .or_else(|| header(headers, "ratelimit-remaining"))
+        .is_some_and(|remaining| remaining.trim() == "0");
+
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => Verdict::Throttled(wait),
+        // GitHub answers 403 once the hourly quota is gone
+        StatusCode::FORBIDDEN | StatusCode::SERVICE_UNAVAILABLE if exhausted => {
+            Verdict::Throttled(wait)
+        }
+        StatusCode::REQUEST_TIMEOUT
+        | StatusCode::INTERNAL_SERVER_ERROR
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::SERVICE_UNAVAILABLE
+        | StatusCode::GATEWAY_TIMEOUT => Verdict::Retry(wait),
+        _ => Verdict::Done,
+    }
+}
+
+/// How long the registry asked us to wait
+///
+/// Reads `Retry-After` (seconds or an HTTP date), then the reset time of an
+/// exhausted quota: `x-ratelimit-reset` (Unix time, as GitHub sends it) or
+/// `ratelimit-reset` (seconds from now).
+pub fn retry_after(headers: &HeaderMap, now: Timestamp) -> Option<Duration> {
+    let until = |at: Timestamp| Duration::from_secs(u64::try_from(at.0 - now.0).unwrap_or(0));
+
+    if let Some(value) = headers.get(RETRY_AFTER).and_then(|v| v.to_str().ok()) {
+        return match value.trim().parse::<u64>() {
+            Ok(seconds) => Some(Duration::from_secs(seconds)),
+            Err(_) => Timestamp::parse_http_date(value).map(until),
+        };
+    }
+    if let Some(reset) = header(headers, "x-ratelimit-reset").and_then(|v| v.trim().parse().ok()) {
+        return Some(until(Timestamp(reset)));
+    }
+    header(headers, "ratelimit-reset")
+        .and_then(|v| v.trim().parse().ok())
+        .map(Duration::from_secs)
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name)?.to_str().ok()
+}
+
+/// Concurrency limit that halves whenever the registry throttles and grows
+/// back by one after a run of successful requests
+#[derive(Debug)]
+pub struct AdaptiveLimit {
+    semaphore: Semaphore,
+    max: usize,
+    state: Mutex<LimitState>,
+}
+
+#[derive(Debug)]
+struct LimitState {
+    limit: usize,
+    /// Permits to retire as they are released, after a cut
+    owed: usize,
+    /// Successes since the last change
+    successes: usize,
+}
+
+/// A slot from `AdaptiveLimit::acquire`, released on drop
+#[derive(Debug)]
+pub struct LimitPermit<'a> {
+    limit: &'a AdaptiveLimit,
+    permit: Option<SemaphorePermit<'a>>,
+}
+
+impl AdaptiveLimit {
+    pub fn new(max: usize) -> Self {
+        let max = max.max(1);
+        Self {
+            semaphore: Semaphore::new(max),
+            max,
+            state: Mutex::new(LimitState {
+                limit: max,
+                owed: 0,
+                successes: 0,
+            }),
+        }
+    }
+
+    /// Current number of requests allowed at once
+    pub fn current(&self) -> usize {
+        self.lock().limit
+    }
+
+    pub async fn acquire(&self) -> LimitPermit<'_> {
+        LimitPermit {
+            limit: self,
+            permit: self.semaphore.acquire().await.ok(),
+        }
+    }
+
+    /// The registry throttled a request: halve the limit
+    ///
+    /// Idle permits are dropped at once; those in use are retired as their
+    /// requests finish.
+    pub fn throttled(&self) {
+        let mut state = self.lock();
+        let cut = state.limit - (state.limit / 2).max(1);
+        state.limit -= cut;
+        state.owed += cut - self.semaphore.forget_permits(cut);
+        state.successes = 0;
+    }
+
+    /// A request went through: after `limit` of them in a row, allow one more
+    pub fn succeeded(&self) {
+        let mut state = self.lock();
+        if state.limit >= self.max {
+            return;
+        }
+        state.successes += 1;
+        if state.successes >= state.limit {
+            state.successes = 0;
+            state.limit += 1;
+            if state.owed > 0 {
+                state.owed -= 1;
+            } else {
+                self.semaphore.add_permits(1);
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LimitState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Drop for LimitPermit<'_> {
+    fn drop(&mut self) {
+        let Some(permit) = self.permit.take() else {
+            return;
+        };
+        let mut state = self.limit.lock();
+        if state.owed > 0 {
+            state.owed -= 1;
+            permit.forget();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_backoff_grows_and_is_capped() {
+        let policy = RetryPolicy::default();
+        for retry in 0..6 {
+            let delay = policy.backoff(retry);
+            let ceiling = (Duration::from_millis(500) * 2u32.pow(retry)).min(policy.max_backoff);
+            assert!(delay >= ceiling / 2 && delay <= ceiling, "{delay:?}");
+        }
+    }
+
+    #[test]
+    fn test_classify_and_retry_after() {
+        let now = Timestamp(1_000);
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            classify(StatusCode::NOT_FOUND, &headers, now),
+            Verdict::Done
+        );
+        assert_eq!(
+            classify(StatusCode::BAD_GATEWAY, &headers, now),
+            Verdict::Retry(None)
+        );
+        assert_eq!(
+            classify(StatusCode::FORBIDDEN, &headers, now),
+            Verdict::Done
+        );
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        assert_eq!(
+            classify(StatusCode::TOO_MANY_REQUESTS, &headers, now),
+            Verdict::Throttled(Some(Duration::from_secs(7)))
+        );
+
+        // GitHub: quota exhausted, reset at a Unix time
+        let mut github = HeaderMap::new();
+        github.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        github.insert("x-ratelimit-reset", HeaderValue::from_static("1030"));
+        assert_eq!(
+            classify(StatusCode::FORBIDDEN, &github, now),
+            Verdict::Throttled(Some(Duration::from_secs(30)))
+        );
+
+        let mut dated = HeaderMap::new();
+        dated.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Thu, 01 Jan 1970 00:17:00 GMT"),
+        );
+        assert_eq!(retry_after(&dated, now), Some(Duration::from_secs(20)));
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_limit() {
+        let limit = AdaptiveLimit::new(8);
+        let held = limit.acquire().await;
+        limit.throttled();
+        limit.throttled();
+        assert_eq!(limit.current(), 2);
+        assert_eq!(limit.semaphore.available_permits(), 1);
+        drop(held);
+        assert_eq!(limit.semaphore.available_permits(), 2);
+
+        // One more slot after `limit` successes in a row
+        limit.succeeded();
+        assert_eq!(limit.current(), 2);
+        limit.succeeded();
+        assert_eq!(limit.current(), 3);
+        assert_eq!(limit.semaphore.available_permits(), 3);
+
+        // Permits in use when the limit drops are retired on release
+        let busy = AdaptiveLimit::new(4);
+        let mut permits = Vec::new();
+        for _ in 0..4 {
+            permits.push(busy.acquire().await);
+        }
+        busy.throttled();
+        drop(permits);
+        assert_eq!(busy.semaphore.available_permits(), 2);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::types::{DependencyCheck, UpdateSeverity};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Seconds in an average Gregorian year
+pub const SECONDS_PER_YEAR: f64 = 365.2425 * 86_400.0;
+
+/// The dependency whose current version was released longest ago
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OldestDependency {
+    pub name: String,
+    pub version: String,
+    /// Release date (`YYYY-MM-DD`)
+    pub released: String,
+}
+
+/// Freshness of a whole project
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Summary {
+    /// Distinct dependencies checked
+    pub dependencies: usize,
+    /// Sum of every dependency's libyears, rounded to two decimals
+    pub libyears: f64,
+    pub oldest: Option<OldestDependency>,
+    pub major: usize,
+    pub minor: usize,
+    pub patch: usize,
+}
+
+impl Summary {
+    /// Summarize checks, counting a package declared in several manifests
+    /// (workspace members, requirements files) once per current version
+    pub fn from_checks<'a>(checks: impl IntoIterator<Item = &'a DependencyCheck>) -> Self {
+        let mut summary = Self::default();
+        let mut seen = HashSet::new();
+        let mut oldest: Option<&DependencyCheck> = None;
+
+        for check in checks {
+            let key = (
+                check.dependency.name.as_str(),
+                check.current_version().map(ToString::to_string),
+            );
+            if !seen.insert(key) {
+                continue;
+            }
+
+            summary.dependencies += 1;
+            summary.libyears += check.libyears().unwrap_or(0.0);
+            match check.severity {
+                Some(UpdateSeverity::Major) => summary.major += 1,
+                Some(UpdateSeverity::Minor) => summary.minor += 1,
+                Some(UpdateSeverity::Patch) => summary.patch += 1,
+                None => {}
+            }
+            if check.installed_released.is_some()
+                && oldest.is_none_or(|o| check.installed_released < o.installed_released)
+            {
+                oldest = Some(check);
+            }
+        }
+
+        summary.libyears = round_libyears(summary.libyears);
+        summary.oldest = oldest.and_then(|check| {
+            Some(OldestDependency {
+                name: check.dependency.display_name(),
+                version: check.current_version()?.to_string(),
+                released: check.installed_released?.to_string(),
+            })
+        });
+        summary
+    }
+}
+
+/// Round to two decimals for display and JSON
+pub fn round_libyears(years: f64) -> f64 {
+    (years * 100.0).round() / 100.0
+}
+
+impl fmt::Display for Summary {
+    /// `12 dependencies, 3.41 libyears behind, oldest: six 1.16.0 (2021-05-05); 1 major, 2 minor, 0 patch`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let noun = if self.dependencies == 1 {
+            "dependency"
+        } else {
+            "dependencies"
+        };
+        write!(
+            f,
+            "{} {noun}, {:.2} libyears behind",
+            self.dependencies, self.libyears
+        )?;
+        if let Some(oldest) = &self.oldest {
+            write!(
+                f,
+                ", oldest: {} {} ({})",
+                oldest.name, oldest.version, oldest.released
+            )?;
+        }
+        write!(
+            f,
+            "; {} major, {} minor, {} patch",
+            self.major, self.minor, self.patch
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DependencyResolver;
+    use crate::date::Timestamp;
+    use crate::types::{Dependency, PackageInfo};
+    use crate::version::{Version, VersionSpec};
+    use std::path::PathBuf;
+
+    fn check(name: &str, installed: &str, published: &[(&str, &str)]) -> DependencyCheck {
+        let versions: Vec<Version> = published.iter().map(|(v, _)| v.parse().unwrap()).collect();
+        let info = PackageInfo {
+            name: name.to_string(),
+            latest: versions.last().unwrap().clone(),
+            latest_stable: versions.last().cloned(),
+            published: published
+                .iter()
+                .map(|(v, t)| (v.parse().unwrap(), Timestamp::parse(t).unwrap()))
+                .collect(),
+            requires: Default::default(),
+            cached_at: None,
+            versions,
+        };
+        let dep = Dependency {
+            name: name.to_string(),
+            registry: None,
+            package: None,
+            version_spec: VersionSpec::parse(&format!(">={installed}")).unwrap(),
+            source_file: PathBuf::from("requirements.txt"),
+            line_number: 1,
+            original_line: String::new(),
+        };
+        DependencyResolver::new().resolve(&dep, &info, Some(&installed.parse().unwrap()))
+    }
+
+    #[test]
+    fn test_libyears_and_summary() {
+        let requests = check(
+            "requests",
+            "2.0.0",
+            &[
+                ("2.0.0", "2020-01-01T00:00:00Z"),
+                ("2.1.0", "2022-01-01T00:00:00Z"),
+            ],
+        );
+        let six = check("six", "1.16.0", &[("1.16.0", "2021-05-05T00:00:00Z")]);
+        assert!((requests.libyears().unwrap() - 2.0).abs() < 0.01);
+        assert_eq!(six.libyears(), Some(0.0));
+
+        let summary = Summary::from_checks([&requests, &six, &requests]);
+        assert_eq!(summary.dependencies, 2);
+        assert!((summary.libyears - 2.0).abs() < f64::EPSILON);
+        assert_eq!(summary.oldest.as_ref().unwrap().name, "requests");
+        assert_eq!(summary.minor, 1);
+        assert_eq!(
+            summary.to_string(),
+            "2 dependencies, 2.00 libyears behind, oldest: requests 2.0.0 (2020-01-01); 0 major, 1 minor, 0 patch"
+        );
+    }
+}
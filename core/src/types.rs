@@ -1,11 +1,24 @@
+use crate::cache::STALE_AFTER;
+use crate::date::Timestamp;
+use crate::summary::SECONDS_PER_YEAR;
 use crate::version::{Version, VersionSpec};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 /// A dependency as parsed from a file (generic across ecosystems)
 #[derive(Debug, Clone)]
 pub struct Dependency {
     /// Package name (normalized)
     pub name: String,
+    /// Registry the package is published on, when it isn't the ecosystem's
+    /// default (Cargo's `registry = "..."`)
+    pub registry: Option<String>,
+    /// Real package name when `name` is a local alias (Cargo's
+    /// `package = "..."`)
+    pub package: Option<String>,
     /// Version specification as parsed
     pub version_spec: VersionSpec,
     /// Source file this dependency was found in
@@ -27,16 +40,71 @@ pub struct PackageInfo {
     pub latest: Version,
     /// Latest stable version (no pre-release)
     pub latest_stable: Option<Version>,
+    /// Publish time per version, where the registry reports it
+    pub published: BTreeMap<Version, Timestamp>,
+    /// What each version needs to run, where the registry says:
+    /// `requires-python` on PyPI (e.g. `>=3.9`), `rust-version` on crates.io
+    /// (e.g. `1.70`)
+    pub requires: BTreeMap<Version, String>,
+    /// When the registry last confirmed this data, if it came from the cache
+    pub cached_at: Option<Timestamp>,
 }
 
 /// Severity of an update
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum UpdateSeverity {
     Major,
     Minor,
     Patch,
 }
 
+impl UpdateSeverity {
+    fn rank(self) -> u8 {
+        match self {
+            UpdateSeverity::Patch => 0,
+            UpdateSeverity::Minor => 1,
+            UpdateSeverity::Major => 2,
+        }
+    }
+
+    /// Check if this update is at least as severe as `threshold`
+    pub fn is_at_least(self, threshold: UpdateSeverity) -> bool {
+        self.rank() >= threshold.rank()
+    }
+}
+
+impl FromStr for UpdateSeverity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "major" => Ok(UpdateSeverity::Major),
+            "minor" => Ok(UpdateSeverity::Minor),
+            "patch" => Ok(UpdateSeverity::Patch),
+            _ => Err(format!("unknown severity '{s}' (expected patch, minor or major)")),
+        }
+    }
+}
+
+impl fmt::Display for UpdateSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdateSeverity::Major => write!(f, "major"),
+            UpdateSeverity::Minor => write!(f, "minor"),
+            UpdateSeverity::Patch => write!(f, "patch"),
+        }
+    }
+}
+
+/// A version left out of the targets because the project can't use it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Blocked {
+    pub version: Version,
+    /// What the version needs that the project lacks, e.g. `requires Rust 1.80`
+    pub reason: String,
+}
+
 /// Result of checking a dependency for updates
 #[derive(Debug, Clone)]
 pub struct DependencyCheck {
@@ -56,6 +124,33 @@ pub struct DependencyCheck {
     pub severity: Option<UpdateSeverity>,
     /// The VersionSpec to write when force updating to latest
     pub force_spec: Option<VersionSpec>,
+    /// Why a config rule capped the target below what the registry offers
+    pub held: Option<String>,
+    /// Newest version skipped for being younger than `--min-age`
+    pub too_new: Option<Version>,
+    /// Newest version the project can't use, and why
+    pub blocked: Option<Blocked>,
+    /// When the current version (installed, or the spec's base) was published
+    pub installed_released: Option<Timestamp>,
+    /// When the latest version was published
+    pub latest_released: Option<Timestamp>,
+    /// When the package's registry data was cached, if it came from the cache
+    pub cached_at: Option<Timestamp>,
+}
+
+impl Dependency {
+    /// Name the registry knows the package by
+    pub fn package_name(&self) -> &str {
+        self.package.as_deref().unwrap_or(&self.name)
+    }
+
+    /// How the dependency is shown: `alias (package)` when renamed
+    pub fn display_name(&self) -> String {
+        match &self.package {
+            Some(package) => format!("{} ({package})", self.name),
+            None => self.name.clone(),
+        }
+    }
 }
 
 impl DependencyCheck {
@@ -72,6 +167,18 @@ impl DependencyCheck {
         }
     }
 
+    /// Whether the registry data is older than `cache::STALE_AFTER`
+    pub fn is_stale(&self, now: Timestamp) -> bool {
+        self.cached_at
+            .is_some_and(|cached| STALE_AFTER.has_passed(cached, now))
+    }
+
+    /// Years between the current and latest releases ("libyears")
+    pub fn libyears(&self) -> Option<f64> {
+        let drift = self.latest_released?.0 - self.installed_released?.0;
+        Some(drift.max(0) as f64 / SECONDS_PER_YEAR)
+    }
+
     /// Get the current version (installed or from spec)
     pub fn current_version(&self) -> Option<&Version> {
         self.installed
@@ -1,3 +1,4 @@
+use crate::npm_range::NpmRange;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::fmt;
@@ -12,13 +13,25 @@ pub enum VersionError {
     InvalidSpecifier(String),
 }
 
-/// A parsed semantic version
+/// A parsed version.
+///
+/// Models the full PEP 440 scheme (epoch, any number of release segments,
+/// pre/post/dev releases, local segment), which is a superset of what Cargo
+/// and npm need. Semver pre-releases (`1.0.0-alpha`, `2.0.0-0`) are kept
+/// verbatim in `pre_release` rather than normalized the PEP 440 way.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Version {
-    pub major: u64,
-    pub minor: u64,
-    pub patch: u64,
+    /// Epoch (`1!2.0`), 0 when absent
+    pub epoch: u64,
+    /// All release segments (`major()`, `minor()` and `patch()` are the first three)
+    pub release: Vec<u64>,
+    /// Pre-release label, normalized for PEP 440 (`a1`, `b2`, `rc10`), or the
+    /// semver identifiers as written (`alpha.1`)
     pub pre_release: Option<String>,
+    /// Post-release number (`1.0.post2`)
+    pub post: Option<u64>,
+    /// Development release number (`1.0.dev3`)
+    pub dev: Option<u64>,
     /// Local version segment (Python) or build metadata (Cargo)
     pub local: Option<String>,
     /// Original string representation
@@ -27,10 +40,7 @@ pub struct Version {
 
 impl PartialEq for Version {
     fn eq(&self, other: &Self) -> bool {
-        self.major == other.major
-            && self.minor == other.minor
-            && self.patch == other.patch
-            && self.pre_release == other.pre_release
+        self.cmp(other) == Ordering::Equal
     }
 }
 
@@ -39,36 +49,117 @@ impl Eq for Version {}
 impl Version {
     pub fn new(major: u64, minor: u64, patch: u64) -> Self {
         Self {
-            major,
-            minor,
-            patch,
+            epoch: 0,
+            release: vec![major, minor, patch],
             pre_release: None,
+            post: None,
+            dev: None,
             local: None,
             original: format!("{major}.{minor}.{patch}"),
         }
     }
 
-    /// Check if this is a pre-release version
+    /// Check if this is a pre-release version (dev releases included, per PEP 440)
     pub fn is_prerelease(&self) -> bool {
-        self.pre_release.is_some()
+        self.pre_release.is_some() || self.dev.is_some()
+    }
+
+    /// Get a release segment by index, treating missing segments as 0
+    pub fn release_segment(&self, index: usize) -> u64 {
+        self.release.get(index).copied().unwrap_or(0)
+    }
+
+    /// First release segment
+    pub fn major(&self) -> u64 {
+        self.release_segment(0)
+    }
+
+    /// Second release segment, 0 if not written
+    pub fn minor(&self) -> u64 {
+        self.release_segment(1)
+    }
+
+    /// Third release segment, 0 if not written
+    pub fn patch(&self) -> u64 {
+        self.release_segment(2)
     }
 
     /// Check if this version is in the same major series as another
     pub fn same_major(&self, other: &Version) -> bool {
-        self.major == other.major
+        self.epoch == other.epoch && self.major() == other.major()
     }
 
     /// Check if this version is in the same minor series as another
     pub fn same_minor(&self, other: &Version) -> bool {
-        self.major == other.major && self.minor == other.minor
+        self.same_major(other) && self.minor() == other.minor()
+    }
+
+    /// Sort key for the pre-release phase (dev-only < pre-release < final)
+    fn phase(&self) -> Phase {
+        match (&self.pre_release, self.post, self.dev) {
+            // 1.0.dev1 sorts before 1.0a1
+            (None, None, Some(_)) => Phase::DevRelease,
+            (Some(pre), _, _) => Phase::PreRelease(pre_release_identifiers(pre)),
+            (None, _, _) => Phase::Final,
+        }
     }
 }
 
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Phase {
+    DevRelease,
+    PreRelease(Vec<Identifier>),
+    Final,
+}
+
+/// A pre-release identifier; numeric identifiers sort below alphanumeric ones
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Identifier {
+    Numeric(u64),
+    Alpha(String),
+}
+
+/// Split a pre-release label into comparable identifiers.
+///
+/// Splits on `.`, `-`, `_` and letter/digit boundaries so that `rc10` sorts
+/// above `rc9` and `beta.11` above `beta.2`. PEP 440 spellings are folded
+/// (`alpha` → `a`, `beta` → `b`, `c`/`pre`/`preview` → `rc`).
+fn pre_release_identifiers(pre: &str) -> Vec<Identifier> {
+    let mut identifiers = Vec::new();
+    for part in pre.split(['.', '-', '_']) {
+        let mut rest = part;
+        while let Some(first) = rest.chars().next() {
+            let is_digit = first.is_ascii_digit();
+            let len = rest
+                .find(|c: char| c.is_ascii_digit() != is_digit)
+                .unwrap_or(rest.len());
+            let (run, tail) = rest.split_at(len);
+            rest = tail;
+
+            let identifier = match run.parse::<u64>() {
+                Ok(n) if is_digit => Identifier::Numeric(n),
+                _ => Identifier::Alpha(
+                    match run {
+                        "alpha" => "a",
+                        "beta" => "b",
+                        "c" | "pre" | "preview" => "rc",
+                        other => other,
+                    }
+                    .to_string(),
+                ),
+            };
+            identifiers.push(identifier);
+        }
+    }
+    identifiers
+}
+
 impl FromStr for Version {
     type Err = VersionError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = s.trim();
+        let invalid = || VersionError::InvalidVersion(s.to_string());
 
         // Handle local version separator (+)
         let (version_part, local) = if let Some(idx) = s.find('+') {
@@ -77,70 +168,158 @@ impl FromStr for Version {
             (s, None)
         };
 
-        // Handle pre-release separators (-, a, b, rc, alpha, beta, dev, post)
-        let (base_part, pre_release) = parse_prerelease(version_part);
-
-        // Parse the base version (major.minor.patch)
-        let parts: Vec<&str> = base_part.split('.').collect();
+        let lower = version_part.to_ascii_lowercase();
+        let body = lower.strip_prefix('v').unwrap_or(&lower);
 
-        let major = parts
-            .first()
-            .and_then(|s| s.parse().ok())
-            .ok_or_else(|| VersionError::InvalidVersion(s.to_string()))?;
+        // Epoch (N!)
+        let (epoch, body) = match body.split_once('!') {
+            Some((epoch, rest)) => (epoch.parse().map_err(|_| invalid())?, rest),
+            None => (0, body),
+        };
 
-        let minor = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
+        // Release segments (N(.N)*)
+        let mut release = Vec::new();
+        let mut rest = body;
+        loop {
+            let len = leading_digits(rest);
+            if len == 0 {
+                break;
+            }
+            release.push(rest[..len].parse().map_err(|_| invalid())?);
+            rest = &rest[len..];
+            match rest.strip_prefix('.') {
+                Some(next) if leading_digits(next) > 0 => rest = next,
+                _ => break,
+            }
+        }
+        if release.is_empty() {
+            return Err(invalid());
+        }
 
-        let patch = parts.get(2).and_then(|s| s.parse().ok()).unwrap_or(0);
+        let semver_shaped = epoch == 0 && release.len() == 3;
+        let suffix = parse_suffix(rest, semver_shaped).ok_or_else(invalid)?;
 
         Ok(Version {
-            major,
-            minor,
-            patch,
-            pre_release,
+            epoch,
+            release,
+            pre_release: suffix.pre,
+            post: suffix.post,
+            dev: suffix.dev,
             local,
             original: s.to_string(),
         })
     }
 }
 
-fn parse_prerelease(s: &str) -> (&str, Option<String>) {
-    // Common pre-release patterns
-    let patterns = [
-        "dev", "post", "alpha", "beta", "rc", "a", "b", "c", "-",
-    ];
+/// Pre/post/dev parts following the release segments
+#[derive(Default)]
+struct Suffix {
+    pre: Option<String>,
+    post: Option<u64>,
+    dev: Option<u64>,
+}
 
-    for pattern in patterns {
-        if let Some(idx) = s.to_lowercase().find(pattern)
-            && idx > 0 {
-                return (&s[..idx], Some(s[idx..].to_string()));
-            }
+fn leading_digits(s: &str) -> usize {
+    s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len())
+}
+
+/// Parse everything after the release segments.
+///
+/// After an `X.Y.Z` release a `-` pre-release is taken as semver and kept
+/// verbatim, so `1.0.0-alpha` stays below `1.0.0-alpha.0` instead of both
+/// becoming `a0`; only a PEP 440 post-release (`1.0.0-post1`) is read the
+/// PEP 440 way there. Otherwise PEP 440 comes first, with semver as the
+/// fallback.
+///
+/// The PEP 440 implicit post-release form (`1.0-1`) is deliberately not
+/// supported: it collides with semver pre-releases such as `2.0.0-0`.
+fn parse_suffix(s: &str, semver_shaped: bool) -> Option<Suffix> {
+    if s.is_empty() {
+        return Some(Suffix::default());
+    }
+
+    let pep440 = parse_pep440_suffix(s);
+    if semver_shaped
+        && pep440.as_ref().is_none_or(|suffix| suffix.post.is_none())
+        && let Some(semver) = parse_semver_suffix(s)
+    {
+        return Some(semver);
     }
+    pep440.or_else(|| parse_semver_suffix(s))
+}
+
+/// `-<identifier>(.<identifier>)*`, kept as written
+fn parse_semver_suffix(s: &str) -> Option<Suffix> {
+    let identifiers = s.strip_prefix('-')?;
+    let valid = identifiers.split('.').all(|id| {
+        !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    });
+    valid.then(|| Suffix {
+        pre: Some(identifiers.to_string()),
+        ..Suffix::default()
+    })
+}
+
+fn parse_pep440_suffix(mut s: &str) -> Option<Suffix> {
+    let mut suffix = Suffix::default();
+
+    let pre_labels = ["preview", "alpha", "beta", "pre", "rc", "a", "b", "c"];
+    if let Some((label, n, rest)) = take_segment(s, &pre_labels) {
+        let label = match label {
+            "alpha" | "a" => "a",
+            "beta" | "b" => "b",
+            _ => "rc",
+        };
+        suffix.pre = Some(format!("{label}{n}"));
+        s = rest;
+    }
+    if let Some((_, n, rest)) = take_segment(s, &["post", "rev", "r"]) {
+        suffix.post = Some(n);
+        s = rest;
+    }
+    if let Some((_, n, rest)) = take_segment(s, &["dev"]) {
+        suffix.dev = Some(n);
+        s = rest;
+    }
+
+    s.is_empty().then_some(suffix)
+}
 
-    (s, None)
+/// Take `[-_.]?<label>[-_.]?<number>?` from the front of `s`.
+/// A missing number is implicitly 0.
+fn take_segment<'a>(s: &'a str, labels: &[&'static str]) -> Option<(&'static str, u64, &'a str)> {
+    let s = s.strip_prefix(['-', '_', '.']).unwrap_or(s);
+    let (label, rest) = labels
+        .iter()
+        .find_map(|label| s.strip_prefix(label).map(|rest| (*label, rest)))?;
+
+    let number = rest
+        .strip_prefix(['-', '_', '.'])
+        .filter(|r| leading_digits(r) > 0)
+        .unwrap_or(rest);
+    let len = leading_digits(number);
+    if len == 0 {
+        return Some((label, 0, rest));
+    }
+    Some((label, number[..len].parse().ok()?, &number[len..]))
 }
 
 impl Ord for Version {
     fn cmp(&self, other: &Self) -> Ordering {
-        match self.major.cmp(&other.major) {
-            Ordering::Equal => {}
-            ord => return ord,
-        }
-        match self.minor.cmp(&other.minor) {
-            Ordering::Equal => {}
-            ord => return ord,
-        }
-        match self.patch.cmp(&other.patch) {
-            Ordering::Equal => {}
-            ord => return ord,
-        }
-
-        // Pre-release versions are less than release versions
-        match (&self.pre_release, &other.pre_release) {
-            (None, Some(_)) => Ordering::Greater,
-            (Some(_), None) => Ordering::Less,
-            (Some(a), Some(b)) => a.cmp(b),
-            (None, None) => Ordering::Equal,
-        }
+        // PEP 440 ordering: epoch, release (zero-padded), pre-release phase,
+        // post-release, then dev release. Local/build metadata is ignored.
+        let segments = self.release.len().max(other.release.len());
+        self.epoch
+            .cmp(&other.epoch)
+            .then_with(|| {
+                (0..segments)
+                    .map(|i| self.release_segment(i).cmp(&other.release_segment(i)))
+                    .find(|ord| ord.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            })
+            .then_with(|| self.phase().cmp(&other.phase()))
+            .then_with(|| self.post.cmp(&other.post))
+            .then_with(|| (self.dev.is_none(), self.dev).cmp(&(other.dev.is_none(), other.dev)))
     }
 }
 
@@ -181,6 +360,8 @@ pub enum VersionSpec {
     Wildcard { prefix: String, pattern: String },
     /// !=1.2.3
     NotEqual(Version),
+    /// node-semver range beyond a single operator (`1.x`, `>=1 <2`, `^1 || ^2`)
+    NpmRange(NpmRange),
     /// Complex constraint we store as raw string
     Complex(String),
     /// Any version (no constraint or *)
@@ -291,50 +472,55 @@ impl VersionSpec {
             VersionSpec::LessThan(v) => version < v,
             VersionSpec::Range { min, max } => version >= min && version < max,
             VersionSpec::Caret(v) => {
-                // Caret: ^1.2.3 means >=1.2.3 <2.0.0
-                // But for 0.x: ^0.1.2 means >=0.1.2 <0.2.0
-                // And for 0.0.x: ^0.0.3 means =0.0.3
-                if version < v {
-                    return false;
-                }
-                if v.major == 0 {
-                    if v.minor == 0 {
-                        // ^0.0.z means =0.0.z
-                        version.major == 0 && version.minor == 0 && version.patch == v.patch
-                    } else {
-                        // ^0.y.z means >=0.y.z <0.(y+1).0
-                        version.major == 0 && version.minor == v.minor
-                    }
-                } else {
-                    // ^x.y.z means >=x.y.z <(x+1).0.0
-                    version.major == v.major
-                }
+                // Caret locks everything up to the leftmost non-zero component
+                // that was written: ^1.2.3 → <2.0.0, ^0.2.3 → <0.3.0,
+                // ^0.0.3 → <0.0.4, ^0.0 → <0.1.0, ^0 → <1.0.0
+                let written = v.release.len().max(1);
+                let locked = (0..written)
+                    .position(|i| v.release_segment(i) != 0)
+                    .map_or(written, |i| i + 1);
+                version >= v && Self::same_prefix(version, v, locked)
             }
             VersionSpec::Tilde(v) => {
-                version >= v && version.major == v.major && version.minor == v.minor
+                // ~1.2.3 and ~1.2 lock major+minor; ~1 locks major only
+                let locked = v.release.len().clamp(1, 2);
+                version >= v && Self::same_prefix(version, v, locked)
             }
             VersionSpec::Compatible(v) => {
-                // PEP 440: ~=X.Y means >=X.Y, <(X+1).0.0 (lock major only)
-                //          ~=X.Y.Z means >=X.Y.Z, <X.(Y+1).0 (lock major+minor)
-                let dot_count = v.original.chars().filter(|c| *c == '.').count();
-                if dot_count < 2 {
-                    // ~=X.Y form: only lock on major
-                    version >= v && version.major == v.major
-                } else {
-                    // ~=X.Y.Z form: lock on major+minor
-                    version >= v && version.major == v.major && version.minor == v.minor
-                }
+                // PEP 440: ~=X.Y means >=X.Y, ==X.* (lock major only)
+                //          ~=X.Y.Z means >=X.Y.Z, ==X.Y.* (lock major+minor)
+                //          ~=X.Y.Z.W means >=X.Y.Z.W, ==X.Y.Z.*
+                let locked = v.release.len().saturating_sub(1).max(1);
+                version >= v && Self::same_prefix(version, v, locked)
             }
             VersionSpec::Wildcard { prefix, .. } => {
-                // Must match prefix followed by a dot (or end), so 1.2.* doesn't match 1.20.x
-                version.original.starts_with(&format!("{prefix}."))
-                    || version.original == *prefix
+                // Compare release segments numerically, so 1.2.* matches 1.2 and
+                // 1.2.0.post1 but not 1.20.x
+                let segments: Option<Vec<u64>> =
+                    prefix.split('.').map(|s| s.parse().ok()).collect();
+                match segments {
+                    Some(segments) => segments
+                        .iter()
+                        .enumerate()
+                        .all(|(i, s)| version.release_segment(i) == *s),
+                    None => {
+                        version.original.starts_with(&format!("{prefix}."))
+                            || version.original == *prefix
+                    }
+                }
             }
             VersionSpec::NotEqual(v) => version != v,
+            VersionSpec::NpmRange(range) => range.satisfies(version),
             VersionSpec::Complex(_) => false, // Can't evaluate complex constraints; don't claim in-range
         }
     }
 
+    /// Check that the epoch and the first `segments` release segments match
+    fn same_prefix(version: &Version, base: &Version, segments: usize) -> bool {
+        version.epoch == base.epoch
+            && (0..segments).all(|i| version.release_segment(i) == base.release_segment(i))
+    }
+
     /// Get the base version from the spec (for comparison)
     pub fn base_version(&self) -> Option<&Version> {
         match self {
@@ -348,6 +534,7 @@ impl VersionSpec {
             | VersionSpec::Compatible(v)
             | VersionSpec::NotEqual(v) => Some(v),
             VersionSpec::Range { min, .. } => Some(min),
+            VersionSpec::NpmRange(range) => Some(range.base_version()),
             VersionSpec::Wildcard { .. } | VersionSpec::Complex(_) | VersionSpec::Any => None,
         }
     }
@@ -355,16 +542,17 @@ impl VersionSpec {
     /// Get the maximum allowed major version (for "in range" calculation)
     pub fn max_major(&self) -> Option<u64> {
         match self {
-            VersionSpec::Range { max, .. } => Some(max.major),
-            VersionSpec::Caret(v) => Some(v.major),
-            VersionSpec::LessThan(v) | VersionSpec::Maximum(v) => Some(v.major),
+            VersionSpec::Range { max, .. } => Some(max.major()),
+            VersionSpec::Caret(v) => Some(v.major()),
+            VersionSpec::LessThan(v) | VersionSpec::Maximum(v) => Some(v.major()),
             // For unbounded specs, we assume same major (semver)
             VersionSpec::Minimum(v)
             | VersionSpec::GreaterThan(v)
             | VersionSpec::Pinned(v)
             | VersionSpec::Compatible(v)
-            | VersionSpec::Tilde(v) => Some(v.major),
-            VersionSpec::NotEqual(v) => Some(v.major),
+            | VersionSpec::Tilde(v) => Some(v.major()),
+            VersionSpec::NotEqual(v) => Some(v.major()),
+            VersionSpec::NpmRange(range) => Some(range.base_version().major()),
             VersionSpec::Wildcard { prefix, .. } => {
                 prefix.split('.').next().and_then(|s| s.parse().ok())
             }
@@ -387,31 +575,12 @@ impl VersionSpec {
             | VersionSpec::NotEqual(v) => Some(v.to_string()),
             VersionSpec::Range { min, .. } => Some(min.to_string()),
             VersionSpec::Wildcard { prefix, .. } => Some(format!("{prefix}.*")),
+            VersionSpec::NpmRange(range) => Some(range.to_string()),
             VersionSpec::Complex(s) => Some(s.clone()),
             VersionSpec::Any => None,
         }
     }
 
-    /// Serialize to Cargo.toml requirement syntax.
-    /// Cargo conventions: bare version = caret, `=` for exact pin, `~` for tilde, etc.
-    pub fn to_cargo_string(&self) -> Option<String> {
-        match self {
-            VersionSpec::Caret(v) => Some(v.to_string()), // bare = caret in Cargo
-            VersionSpec::Tilde(v) => Some(format!("~{v}")),
-            VersionSpec::Pinned(v) => Some(format!("={v}")), // Cargo uses single =
-            VersionSpec::Minimum(v) => Some(format!(">={v}")),
-            VersionSpec::Maximum(v) => Some(format!("<={v}")),
-            VersionSpec::GreaterThan(v) => Some(format!(">{v}")),
-            VersionSpec::LessThan(v) => Some(format!("<{v}")),
-            VersionSpec::Range { min, max } => Some(format!(">={min}, <{max}")),
-            VersionSpec::Wildcard { prefix, .. } => Some(format!("{prefix}.*")),
-            VersionSpec::NotEqual(v) => Some(format!("!={v}")),
-            VersionSpec::Compatible(v) => Some(v.to_string()), // not a Cargo concept, treat as bare
-            VersionSpec::Complex(s) => Some(s.clone()),
-            VersionSpec::Any => Some("*".to_string()),
-        }
-    }
-
     /// Returns true if this spec can be safely rewritten by an updater
     pub fn is_rewritable(&self) -> bool {
         !matches!(self, VersionSpec::Complex(_) | VersionSpec::Any)
@@ -430,7 +599,7 @@ impl VersionSpec {
                 if new_version >= max {
                     VersionSpec::Range {
                         min: new_version.clone(),
-                        max: Version::new(new_version.major + 1, 0, 0),
+                        max: Version::new(new_version.major() + 1, 0, 0),
                     }
                 } else {
                     VersionSpec::Range {
@@ -447,8 +616,8 @@ impl VersionSpec {
                 // "1.*" (1 segment) → "2.*", "1.2.*" (2 segments) → "1.3.*"
                 let segments = prefix.split('.').count();
                 let new_prefix = match segments {
-                    0 | 1 => format!("{}", new_version.major),
-                    _ => format!("{}.{}", new_version.major, new_version.minor),
+                    0 | 1 => format!("{}", new_version.major()),
+                    _ => format!("{}.{}", new_version.major(), new_version.minor()),
                 };
                 VersionSpec::Wildcard {
                     prefix: new_prefix,
@@ -456,6 +625,7 @@ impl VersionSpec {
                 }
             }
             VersionSpec::NotEqual(_) => VersionSpec::NotEqual(new_version.clone()),
+            VersionSpec::NpmRange(range) => VersionSpec::NpmRange(range.with_version(new_version)),
             VersionSpec::Complex(s) => VersionSpec::Complex(s.clone()),
             VersionSpec::Any => VersionSpec::Any,
         }
@@ -477,6 +647,7 @@ impl fmt::Display for VersionSpec {
             VersionSpec::Compatible(v) => write!(f, "~={v}"),
             VersionSpec::Wildcard { prefix, .. } => write!(f, "=={prefix}.*"),
             VersionSpec::NotEqual(v) => write!(f, "!={v}"),
+            VersionSpec::NpmRange(range) => write!(f, "{range}"),
             VersionSpec::Complex(s) => write!(f, "{s}"),
         }
     }
@@ -489,14 +660,14 @@ mod tests {
     #[test]
     fn test_parse_version() {
         let v = Version::from_str("1.2.3").unwrap();
-        assert_eq!(v.major, 1);
-        assert_eq!(v.minor, 2);
-        assert_eq!(v.patch, 3);
+        assert_eq!(v.major(), 1);
+        assert_eq!(v.minor(), 2);
+        assert_eq!(v.patch(), 3);
 
         let v = Version::from_str("2.0").unwrap();
-        assert_eq!(v.major, 2);
-        assert_eq!(v.minor, 0);
-        assert_eq!(v.patch, 0);
+        assert_eq!(v.major(), 2);
+        assert_eq!(v.minor(), 0);
+        assert_eq!(v.patch(), 0);
     }
 
     #[test]
@@ -510,6 +681,103 @@ mod tests {
         assert!(v1 < v3);
     }
 
+    #[test]
+    fn test_parse_pep440_segments() {
+        let v = Version::from_str("1!2024.1.0.3rc2.post1.dev4+local.7").unwrap();
+        assert_eq!(v.epoch, 1);
+        assert_eq!(v.release, vec![2024, 1, 0, 3]);
+        assert_eq!((v.major(), v.minor(), v.patch()), (2024, 1, 0));
+        assert_eq!(v.pre_release.as_deref(), Some("rc2"));
+        assert_eq!(v.post, Some(1));
+        assert_eq!(v.dev, Some(4));
+        assert_eq!(v.local.as_deref(), Some("local.7"));
+        assert_eq!(v.to_string(), "1!2024.1.0.3rc2.post1.dev4+local.7");
+
+        // Alternate spellings normalize
+        let v = Version::from_str("1.0-Alpha.1").unwrap();
+        assert_eq!(v.pre_release.as_deref(), Some("a1"));
+        assert_eq!(Version::from_str("1.0-beta").unwrap().pre_release.as_deref(), Some("b0"));
+        // ... except semver pre-releases, which are kept as written
+        assert_eq!(Version::from_str("1.0.0-beta").unwrap().pre_release.as_deref(), Some("beta"));
+        assert_eq!(Version::from_str("1.0.0-post1").unwrap().post, Some(1));
+        // Spelled out or not, a label still compares as its PEP 440 form
+        assert_eq!(Version::from_str("1.0.0-beta.2").unwrap(), Version::from_str("1.0.0b2").unwrap());
+
+        assert!(Version::from_str("latest").is_err());
+        assert!(Version::from_str("1.2.3foo").is_err());
+    }
+
+    #[test]
+    fn test_pep440_ordering() {
+        let ordered = [
+            "1.0.dev1",
+            "1.0a1.dev1",
+            "1.0a1",
+            "1.0a2",
+            "1.0b1",
+            "1.0rc9",
+            "1.0rc10",
+            "1.0",
+            "1.0.post1.dev1",
+            "1.0.post1",
+            "1.0.post2",
+            "1.0.0.1",
+            "1.1",
+            "1!0.1",
+        ];
+        for pair in ordered.windows(2) {
+            let a = Version::from_str(pair[0]).unwrap();
+            let b = Version::from_str(pair[1]).unwrap();
+            assert!(a < b, "expected {} < {}", pair[0], pair[1]);
+        }
+
+        // Trailing zeros and local segments don't affect equality
+        assert_eq!(Version::from_str("1.0").unwrap(), Version::from_str("1.0.0").unwrap());
+        assert_eq!(Version::from_str("1.0+abc").unwrap(), Version::from_str("1.0").unwrap());
+        assert!(Version::from_str("1.0.dev0").unwrap().is_prerelease());
+        assert!(!Version::from_str("1.0.post1").unwrap().is_prerelease());
+    }
+
+    #[test]
+    fn test_semver_precedence() {
+        // Example chain from the semver 2.0 spec, section 11, plus `alpha.0`:
+        // a pre-release with fewer identifiers sorts lower
+        let ordered = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.0",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+        ];
+        for pair in ordered.windows(2) {
+            let a = Version::from_str(pair[0]).unwrap();
+            let b = Version::from_str(pair[1]).unwrap();
+            assert!(a < b, "expected {} < {}", pair[0], pair[1]);
+        }
+        assert!(Version::from_str("2.0.0-0").unwrap() < Version::from_str("2.0.0-alpha").unwrap());
+        assert_eq!(
+            Version::from_str("1.0.0+build.1").unwrap(),
+            Version::from_str("1.0.0+build.2").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_compatible_release_segments() {
+        let spec = VersionSpec::parse("~=1.4.5.0").unwrap();
+        assert!(spec.satisfies(&Version::from_str("1.4.5.0").unwrap()));
+        assert!(spec.satisfies(&Version::from_str("1.4.5.9").unwrap()));
+        assert!(!spec.satisfies(&Version::from_str("1.4.6").unwrap()));
+        assert!(!spec.satisfies(&Version::from_str("1.4.4.9").unwrap()));
+
+        let spec = VersionSpec::parse("~=2.2").unwrap();
+        assert!(spec.satisfies(&Version::from_str("2.9.post1").unwrap()));
+        assert!(!spec.satisfies(&Version::from_str("3.0").unwrap()));
+    }
+
     #[test]
     fn test_parse_version_spec() {
         assert!(matches!(
@@ -530,6 +798,24 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_caret_tilde_precision() {
+        let satisfies = |spec: &str, v: &str| {
+            VersionSpec::parse(spec)
+                .unwrap()
+                .satisfies(&Version::from_str(v).unwrap())
+        };
+        assert!(satisfies("^0.2", "0.2.9"));
+        assert!(!satisfies("^0.2", "0.3.0"));
+        assert!(satisfies("^0.0", "0.0.7"));
+        assert!(!satisfies("^0.0", "0.1.0"));
+        assert!(!satisfies("^0.0.3", "0.0.4"));
+        assert!(satisfies("^0", "0.9.0"));
+        assert!(satisfies("~1", "1.9.0"));
+        assert!(!satisfies("~1", "2.0.0"));
+        assert!(!satisfies("~1.2", "1.3.0"));
+    }
+
     #[test]
     fn test_satisfies() {
         let spec = VersionSpec::parse(">=1.0.0,<2.0.0").unwrap();
@@ -0,0 +1,88 @@
+use check_updates_core::{
+    leftmost_nonzero_severity, Ecosystem, NpmRange, UpdateSeverity, Version, VersionError,
+    VersionSpec,
+};
+
+/// npm's (node-semver) range semantics
+pub struct NpmEcosystem {
+    include_prerelease: bool,
+}
+
+impl NpmEcosystem {
+    pub fn new(include_prerelease: bool) -> Self {
+        Self { include_prerelease }
+    }
+}
+
+impl Ecosystem for NpmEcosystem {
+    fn name(&self) -> &'static str {
+        "npm"
+    }
+
+    /// Parse a node-semver range. Single fully specified operators (`^1.2.3`,
+    /// `>=1.0.0`) map onto the plain variants; anything else (`1.x`,
+    /// `>=1 <2`, `^1 || ^2`) becomes `VersionSpec::NpmRange`.
+    fn parse_spec(&self, s: &str) -> Result<VersionSpec, VersionError> {
+        Ok(NpmRange::parse(s.trim())?
+            .with_prerelease(self.include_prerelease)
+            .into_spec())
+    }
+
+    /// A bump of the leftmost non-zero component is breaking under caret rules
+    fn severity(&self, current: &Version, target: &Version) -> Option<UpdateSeverity> {
+        leftmost_nonzero_severity(current, target)
+    }
+
+    /// Serialize to package.json range syntax.
+    /// npm conventions: bare version = exact pin, space-separated comparator sets.
+    fn format_spec(&self, spec: &VersionSpec) -> Option<String> {
+        let formatted = match spec {
+            VersionSpec::Pinned(v) => v.to_string(), // bare = exact in npm
+            VersionSpec::Caret(v) => format!("^{v}"),
+            VersionSpec::Tilde(v) | VersionSpec::Compatible(v) => format!("~{v}"),
+            VersionSpec::Minimum(v) => format!(">={v}"),
+            VersionSpec::Maximum(v) => format!("<={v}"),
+            VersionSpec::GreaterThan(v) => format!(">{v}"),
+            VersionSpec::LessThan(v) => format!("<{v}"),
+            VersionSpec::Range { min, max } => format!(">={min} <{max}"),
+            VersionSpec::Wildcard { prefix, .. } => format!("{prefix}.x"),
+            VersionSpec::NpmRange(range) => range.to_string(),
+            VersionSpec::NotEqual(v) => format!("<{v} || >{v}"),
+            VersionSpec::Complex(s) => s.clone(),
+            VersionSpec::Any => "*".to_string(),
+        };
+        Some(formatted)
+    }
+}
+
+impl Default for NpmEcosystem {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_npm_specs() {
+        let eco = NpmEcosystem::default();
+        let pinned = VersionSpec::Pinned(Version::new(1, 2, 3));
+        assert_eq!(eco.format_spec(&pinned).unwrap(), "1.2.3");
+
+        let range = eco.parse_spec(">=1 <2").unwrap();
+        assert_eq!(eco.format_spec(&range).unwrap(), ">=1 <2");
+
+        let caret = eco.parse_spec("^4.18.0").unwrap();
+        assert_eq!(eco.format_spec(&caret).unwrap(), "^4.18.0");
+    }
+
+    #[test]
+    fn test_bare_partial_is_x_range() {
+        let eco = NpmEcosystem::default();
+        let spec = eco.parse_spec("1.2").unwrap();
+        assert!(eco.satisfies(&spec, &Version::new(1, 2, 9)));
+        assert!(!eco.satisfies(&spec, &Version::new(1, 3, 0)));
+    }
+}
@@ -1,5 +1,7 @@
 use anyhow::Result;
-use check_updates_core::{UpdateSeverity, Version};
+use crate::ecosystem::NpmEcosystem;
+use check_updates_core::report::GlobalRecord;
+use check_updates_core::{Ecosystem, UpdateSeverity, Version};
 use std::process::Command;
 use std::str::FromStr;
 
@@ -39,18 +41,18 @@ impl GlobalCheck {
         if !self.has_update {
             return None;
         }
-        let current = &self.package.installed_version;
-        let target = &self.latest;
-
-        if target.major > current.major {
-            Some(UpdateSeverity::Major)
-        } else if target.minor > current.minor {
-            Some(UpdateSeverity::Minor)
-        } else if target.patch > current.patch {
-            Some(UpdateSeverity::Patch)
-        } else {
-            None
-        }
+        NpmEcosystem::default().severity(&self.package.installed_version, &self.latest)
+    }
+
+    /// Convert to a machine-readable record
+    pub fn to_record(&self) -> GlobalRecord {
+        GlobalRecord::new(
+            &self.package.name,
+            &self.package.source.to_string(),
+            &self.package.installed_version.to_string(),
+            Some(self.latest.to_string()),
+        )
+        .with_update(self.has_update, self.update_severity())
     }
 }
 
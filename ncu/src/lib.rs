@@ -1,7 +1,10 @@
 pub mod cli;
 pub mod detector;
+pub mod ecosystem;
 pub mod global;
+pub mod node;
 pub mod npm;
+pub mod npmrc;
 pub mod output;
 pub mod parsers;
 pub mod updater;
@@ -1,31 +1,78 @@
 use anyhow::{Context, Result};
-use check_updates_core::{DependencyResolver, Version};
+use check_updates_core::config::ManifestSection;
+use check_updates_core::exit::code;
+use check_updates_core::interactive::Picker;
+use check_updates_core::registry::{FixtureRegistry, RegistryClient};
+use check_updates_core::{exit_status, Annotations, Config, DependencyResolver, NpmRange, Report, ReportMode, Version};
 use clap::Parser;
 use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::collections::{HashMap, HashSet};
+use std::process::ExitCode;
 
 use ncu::cli::Args;
 use ncu::detector::ProjectDetector;
+use ncu::ecosystem::NpmEcosystem;
 use ncu::global::{generate_upgrade_commands, GlobalCheck, GlobalPackageDiscovery};
+use ncu::node::project_node_version;
 use ncu::npm::NpmClient;
+use ncu::npmrc::Npmrc;
 use ncu::output::{GlobalTableRenderer, TableRenderer};
 use ncu::parsers::{LockfileParser, PackageJsonParser};
 use ncu::updater::FileUpdater;
 
+/// The `"check-updates"` key in package.json, used when there's no .check-updates.toml
+const CONFIG_SECTIONS: &[ManifestSection] = &[ManifestSection {
+    file: "package.json",
+    path: &["check-updates"],
+}];
+
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let args = Args::parse();
 
+    // Load .check-updates.toml (or the manifest section) and merge its default
+    // flags before building the client, which needs `registry` and `pre-release`
+    let config = if args.global {
+        Config::default()
+    } else {
+        Config::load(&args.project_path(), CONFIG_SECTIONS)?
+    };
+    let args = args.with_defaults(&config.defaults);
+
+    let status = match &args.registry_fixtures {
+        Some(dir) => {
+            let fixtures = FixtureRegistry::from_dir(dir, args.pre_release)?;
+            run(&args, &config, &fixtures).await?
+        }
+        None => {
+            // The project's .npmrc only applies in project mode; --registry
+            // (or NCU_REGISTRY, or the config) overrides its `registry`
+            let project_dir = (!args.global).then(|| args.project_path());
+            let mut client = NpmClient::new(args.pre_release)
+                .with_cache(args.cache())
+                .with_npmrc(Npmrc::load(project_dir.as_deref())?);
+            if let Some(registry) = &args.registry {
+                client = client.with_registry(registry.as_str());
+            }
+            run(&args, &config, &client).await?
+        }
+    };
+    Ok(ExitCode::from(status))
+}
+
+async fn run(args: &Args, config: &Config, client: &impl RegistryClient) -> Result<u8> {
     if args.global {
-        run_global_mode(&args).await
+        run_global_mode(args, client).await
     } else {
-        run_project_mode(&args).await
+        run_project_mode(args, config, client).await
     }
 }
 
-async fn run_global_mode(args: &Args) -> Result<()> {
-    if args.update {
+async fn run_global_mode(args: &Args, client: &impl RegistryClient) -> Result<u8> {
+    let human = args.format.is_human();
+
+    if args.update && human {
         println!(
             "Note: --update flag is ignored in global mode. Commands will be shown instead.\n"
         );
@@ -36,12 +83,14 @@ async fn run_global_mode(args: &Args) -> Result<()> {
     let packages = discovery.discover();
 
     if packages.is_empty() {
-        println!("No globally installed npm packages found.");
-        return Ok(());
+        if human {
+            println!("No globally installed npm packages found.");
+        }
+        Report::new("ncu", ReportMode::Global).print(args.format)?;
+        return Ok(code::SUCCESS);
     }
 
     // 2. Query npm registry for latest versions
-    let client = NpmClient::new(args.pre_release);
     let package_names: Vec<String> = packages
         .iter()
         .map(|p| p.name.clone())
@@ -58,26 +107,17 @@ async fn run_global_mode(args: &Args) -> Result<()> {
     );
 
     let pb = progress.clone();
-    let results = client
+    let result = client
         .get_packages(&package_names, move |done, _total| {
             pb.set_position(done as u64);
         })
         .await;
     progress.finish_and_clear();
 
-    let mut package_infos: HashMap<String, _> = HashMap::new();
-    let mut errors: Vec<(String, String)> = Vec::new();
-
-    for (name, result) in results {
-        match result {
-            Ok(info) => {
-                package_infos.insert(name, info);
-            }
-            Err(e) => {
-                errors.push((name, e.to_string()));
-            }
-        }
-    }
+    let package_infos = result.packages;
+    let not_found = result.not_found;
+    let errors = result.errors;
+    let uncached = result.uncached;
 
     // 3. Build check results
     let mut checks: Vec<GlobalCheck> = Vec::new();
@@ -87,7 +127,7 @@ async fn run_global_mode(args: &Args) -> Result<()> {
             let target = if args.minor {
                 info.versions
                     .iter()
-                    .filter(|v| v.major == package.installed_version.major)
+                    .filter(|v| v.major() == package.installed_version.major())
                     .max()
                     .cloned()
                     .unwrap_or_else(|| package.installed_version.clone())
@@ -105,11 +145,30 @@ async fn run_global_mode(args: &Args) -> Result<()> {
         }
     }
 
-    // 4. Display results
+    // Packages the registry doesn't have are listed, but only failed lookups
+    // leave the run unsure of them
+    let status = exit_status(
+        args.fail_on,
+        checks.iter().filter_map(GlobalCheck::update_severity),
+        !errors.is_empty() || !uncached.is_empty(),
+    );
+
+    // 4. Machine-readable output replaces the table and upgrade commands
+    if !human {
+        let mut report = Report::new("ncu", ReportMode::Global);
+        report.global.extend(checks.iter().map(GlobalCheck::to_record));
+        report.add_not_found(&not_found);
+        report.add_formatted_errors(&errors);
+        report.uncached = uncached;
+        report.print(args.format)?;
+        return Ok(status);
+    }
+
+    // 5. Display results
     let renderer = GlobalTableRenderer::new(true);
     renderer.render(&checks);
 
-    // 5. Print upgrade commands
+    // 6. Print upgrade commands
     let commands = generate_upgrade_commands(&checks);
     if !commands.is_empty() {
         println!();
@@ -119,20 +178,44 @@ async fn run_global_mode(args: &Args) -> Result<()> {
         }
     }
 
-    // 6. Print errors
-    if !errors.is_empty() {
+    // 7. Print errors
+    if !not_found.is_empty() {
         println!();
         println!("{}", "Packages not found on npm:".dimmed());
-        for (name, error) in errors {
-            println!("  {}: {}", name.dimmed(), error.dimmed());
+        for error in &not_found {
+            println!("  {}", error.dimmed());
+        }
+    }
+    if !errors.is_empty() {
+        println!();
+        println!("{}", "Failed to fetch from npm:".dimmed());
+        for error in &errors {
+            println!("  {}", error.dimmed());
+        }
+    }
+    if !uncached.is_empty() {
+        println!();
+        println!("{}", "Not in the offline cache (run once without --offline to fill it):".dimmed());
+        for name in &uncached {
+            println!("  {}", name.dimmed());
         }
     }
 
-    Ok(())
+    Ok(status)
 }
 
-async fn run_project_mode(args: &Args) -> Result<()> {
+async fn run_project_mode(
+    args: &Args,
+    config: &Config,
+    client: &impl RegistryClient,
+) -> Result<u8> {
     let project_path = args.project_path();
+    let human = args.format.is_human();
+    let mut report = Report::new("ncu", ReportMode::Project);
+
+    if args.interactive && !human {
+        anyhow::bail!("--interactive can't be combined with --format {}", args.format);
+    }
 
     if !project_path.exists() {
         anyhow::bail!("Project path does not exist: {project_path:?}");
@@ -143,8 +226,11 @@ async fn run_project_mode(args: &Args) -> Result<()> {
     let detected_files = detector.detect()?;
 
     if detected_files.is_empty() {
-        println!("No package.json files found in {project_path:?}");
-        return Ok(());
+        if human {
+            println!("No package.json files found in {project_path:?}");
+        }
+        report.print(args.format)?;
+        return Ok(code::SUCCESS);
     }
 
     // Parse lock file for installed versions
@@ -159,7 +245,7 @@ async fn run_project_mode(args: &Args) -> Result<()> {
         };
 
     // Parse all package.json files
-    let parser = PackageJsonParser::new();
+    let parser = PackageJsonParser::new().with_prerelease(args.pre_release);
     let mut all_deps = Vec::new();
 
     for file in &detected_files {
@@ -169,9 +255,19 @@ async fn run_project_mode(args: &Args) -> Result<()> {
         all_deps.extend(deps);
     }
 
+    // Drop packages excluded by config or a `check-updates: ignore` comment
+    let annotations = Annotations::scan(&all_deps, &NpmEcosystem::new(args.pre_release));
+    for warning in annotations.warnings() {
+        eprintln!("Warning: {warning}");
+    }
+    all_deps.retain(|d| !config.is_ignored(&d.name) && !annotations.is_ignored(d));
+
     if all_deps.is_empty() {
-        println!("No dependencies found");
-        return Ok(());
+        if human {
+            println!("No dependencies found");
+        }
+        report.print(args.format)?;
+        return Ok(code::SUCCESS);
     }
 
     // Deduplicate by package name (keep first occurrence)
@@ -179,7 +275,6 @@ async fn run_project_mode(args: &Args) -> Result<()> {
     all_deps.retain(|d| seen.insert(d.name.clone()));
 
     // Query npm registry
-    let client = NpmClient::new(args.pre_release);
     let package_names: Vec<String> = all_deps.iter().map(|d| d.name.clone()).collect();
 
     let progress = ProgressBar::new(package_names.len() as u64);
@@ -191,40 +286,93 @@ async fn run_project_mode(args: &Args) -> Result<()> {
     );
 
     let pb = progress.clone();
-    let results = client
+    let result = client
         .get_packages(&package_names, move |done, _total| {
             pb.set_position(done as u64);
         })
         .await;
     progress.finish_and_clear();
 
-    // Build package info map
-    let mut package_infos: HashMap<String, _> = HashMap::new();
-    let mut errors: Vec<(String, String)> = Vec::new();
-
-    for (name, result) in results {
-        match result {
-            Ok(info) => {
-                package_infos.insert(name, info);
-            }
-            Err(e) => {
-                errors.push((name, e.to_string()));
-            }
+    let package_infos = result.packages;
+    let not_found = result.not_found;
+    let errors = result.errors;
+    let uncached = result.uncached;
+
+    // Resolve dependencies, leaving out versions whose `engines.node`
+    // excludes the Node the project declares (or the installed one)
+    let mut resolver = DependencyResolver::new()
+        .with_ecosystem(NpmEcosystem::new(args.pre_release))
+        .with_min_age(args.min_age);
+    if !args.ignore_engines {
+        let root_package_json = project_path.join("package.json");
+        let engines = if root_package_json.exists() {
+            parser.node_engine(&root_package_json)?
+        } else {
+            None
+        };
+        if let Some(node) = project_node_version(&project_path, engines.as_deref()) {
+            resolver = resolver.with_filter(move |_, info, version| {
+                let requires = info.requires.get(version)?;
+                let supported = NpmRange::parse(requires).ok()?.satisfies(&node);
+                (!supported).then(|| format!("requires Node {requires}"))
+            });
         }
     }
-
-    // Resolve dependencies
-    let resolver = DependencyResolver::new();
     let mut checks = Vec::new();
 
     for dep in &all_deps {
         if let Some(info) = package_infos.get(&dep.name) {
             let installed = installed_versions.get(&dep.name);
             let check = resolver.resolve(dep, info, installed);
+            let check = config.apply(check, info, &resolver);
+            let check = annotations.apply(check, info, &resolver);
             checks.push(check);
         }
     }
 
+    // Packages the registry doesn't have are listed, but only failed lookups
+    // leave the run unsure of them
+    let status = exit_status(
+        args.fail_on,
+        checks.iter().filter_map(|c| c.severity),
+        !errors.is_empty() || !uncached.is_empty(),
+    );
+
+    // Machine-readable output replaces the table, hints and error list
+    if !human {
+        if args.update {
+            let result = FileUpdater::new().apply_updates(&checks, args.minor, args.force)?;
+            report.updated_files = result
+                .modified_files
+                .iter()
+                .map(|f| f.display().to_string())
+                .collect();
+            report.updated_files.sort();
+        }
+        report.add_checks(&checks, &NpmEcosystem::new(args.pre_release));
+        report.add_not_found(&not_found);
+        report.add_formatted_errors(&errors);
+        report.uncached = uncached;
+        report.print(args.format)?;
+        return Ok(status);
+    }
+
+    // Interactive mode: choose updates instead of showing the table
+    if args.interactive {
+        let picker = Picker::new(&checks, &NpmEcosystem::new(args.pre_release));
+        if picker.is_empty() {
+            println!("All dependencies are up to date!");
+            return Ok(status);
+        }
+        let Some(selected) = picker.run()? else {
+            println!("No changes made.");
+            return Ok(status);
+        };
+        let result = FileUpdater::new().apply_updates(&selected, false, true)?;
+        result.print_summary();
+        return Ok(status);
+    }
+
     // Render output
     let renderer = TableRenderer::new(true);
     let header = if args.update {
@@ -245,13 +393,27 @@ async fn run_project_mode(args: &Args) -> Result<()> {
     }
 
     // Show errors at the end
-    if !errors.is_empty() {
+    if !not_found.is_empty() {
         println!();
         println!("Packages not found on npm:");
-        for (name, error) in errors {
-            println!("  {name}: {error}");
+        for error in &not_found {
+            println!("  {error}");
+        }
+    }
+    if !errors.is_empty() {
+        println!();
+        println!("Failed to fetch from npm:");
+        for error in &errors {
+            println!("  {error}");
+        }
+    }
+    if !uncached.is_empty() {
+        println!();
+        println!("Not in the offline cache (run once without --offline to fill it):");
+        for name in &uncached {
+            println!("  {name}");
         }
     }
 
-    Ok(())
+    Ok(status)
 }
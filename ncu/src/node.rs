@@ -0,0 +1,103 @@
+use check_updates_core::{NpmRange, Version};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::str::FromStr;
+
+/// Detect the installed Node version
+pub fn detect_node_version() -> Option<Version> {
+    let output = Command::new("node").arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // Output is like "v20.11.0"
+    parse_node_version(String::from_utf8_lossy(&output.stdout).trim())
+}
+
+/// `20.11.0` or `v20.11.0`
+fn parse_node_version(s: &str) -> Option<Version> {
+    Version::from_str(s.strip_prefix('v').unwrap_or(s)).ok()
+}
+
+/// The Node version an `.nvmrc` selects
+///
+/// A partial version (`20`, `v20.11`) means the newest release of that line,
+/// so `installed` is used when it's on the line; otherwise the missing parts
+/// are zero. Aliases (`lts/iron`, `node`) give `None`.
+pub fn parse_nvmrc(content: &str, installed: Option<&Version>) -> Option<Version> {
+    let line = content
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with('#'))?;
+    let written = parse_node_version(line)?;
+    if written.release.len() >= 3 {
+        return Some(written);
+    }
+    let on_line = installed.filter(|i| {
+        (0..written.release.len()).all(|n| i.release_segment(n) == written.release_segment(n))
+    });
+    Some(
+        on_line
+            .cloned()
+            .unwrap_or_else(|| Version::new(written.major(), written.minor(), written.patch())),
+    )
+}
+
+/// The Node version to check packages' `engines.node` against
+///
+/// The oldest version the project's own `engines.node` admits, else the one
+/// its `.nvmrc` selects, else the installed one.
+pub fn project_node_version(project_path: &Path, engines: Option<&str>) -> Option<Version> {
+    let lowest = engines
+        .and_then(|e| NpmRange::parse(e).ok())
+        .map(|range| range.lowest_version())
+        .filter(|v| *v > Version::new(0, 0, 0));
+    if lowest.is_some() {
+        return lowest;
+    }
+
+    let installed = detect_node_version();
+    fs::read_to_string(project_path.join(".nvmrc"))
+        .ok()
+        .and_then(|content| parse_nvmrc(&content, installed.as_ref()))
+        .or(installed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> Version {
+        Version::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_nvmrc() {
+        let installed = v("20.11.1");
+        assert_eq!(
+            parse_nvmrc("v18.19.0\n", Some(&installed)),
+            Some(v("18.19.0"))
+        );
+        assert_eq!(parse_nvmrc("20\n", Some(&installed)), Some(v("20.11.1")));
+        assert_eq!(parse_nvmrc("v20.9", Some(&installed)), Some(v("20.9.0")));
+        assert_eq!(parse_nvmrc("22", None), Some(v("22.0.0")));
+        assert_eq!(parse_nvmrc("lts/iron", Some(&installed)), None);
+        assert_eq!(parse_nvmrc("", Some(&installed)), None);
+    }
+
+    #[test]
+    fn test_engines_win_over_nvmrc() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".nvmrc"), "22.1.0\n").unwrap();
+
+        assert_eq!(
+            project_node_version(dir.path(), Some("^18.18 || >=20")),
+            Some(v("18.18.0"))
+        );
+        // No lower bound to go on: fall back to .nvmrc
+        assert_eq!(
+            project_node_version(dir.path(), Some("*")),
+            Some(v("22.1.0"))
+        );
+    }
+}
@@ -1,10 +1,14 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use check_updates_core::cache::HttpCache;
+use check_updates_core::date::Timestamp;
+use check_updates_core::http::HttpClient;
+use check_updates_core::registry::{NotFound, RegistryClient, RegistryError};
 use check_updates_core::{PackageInfo, Version};
+use crate::npmrc::Npmrc;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
 use std::sync::Arc;
-use tokio::sync::Semaphore;
 
 const NPM_REGISTRY: &str = "https://registry.npmjs.org";
 
@@ -13,42 +17,90 @@ struct NpmPackageResponse {
     name: String,
     #[serde(rename = "dist-tags")]
     dist_tags: HashMap<String, String>,
-    versions: HashMap<String, serde_json::Value>,
+    versions: HashMap<String, NpmVersion>,
+    /// Publish time per version, plus `created` and `modified`
+    #[serde(default)]
+    time: HashMap<String, String>,
+}
+
+/// A version's manifest in the packument; only `engines` is read
+#[derive(Debug, Deserialize)]
+struct NpmVersion {
+    /// `{"node": ">=18"}`; an array or string in some very old packages
+    #[serde(default)]
+    engines: serde_json::Value,
 }
 
 #[derive(Clone)]
 pub struct NpmClient {
-    client: reqwest::Client,
+    http: HttpClient,
+    registry: String,
+    npmrc: Arc<Npmrc>,
     include_prerelease: bool,
 }
 
 impl NpmClient {
     pub fn new(include_prerelease: bool) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            http: HttpClient::new(reqwest::Client::new()),
+            registry: NPM_REGISTRY.to_string(),
+            npmrc: Arc::default(),
             include_prerelease,
         }
     }
 
+    /// Query a mirror or proxy instead of the public registry
+    pub fn with_registry(mut self, url: &str) -> Self {
+        self.registry = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Route packages and credentials as `.npmrc` says: its `registry`
+    /// replaces the public one, scoped packages go to their scope's registry
+    pub fn with_npmrc(mut self, npmrc: Npmrc) -> Self {
+        if let Some(registry) = npmrc.registry() {
+            self.registry = registry.to_string();
+        }
+        self.npmrc = Arc::new(npmrc);
+        self
+    }
+
+    /// Serve responses from (and store them in) an on-disk cache
+    pub fn with_cache(mut self, cache: Option<HttpCache>) -> Self {
+        self.http = self.http.with_cache(cache);
+        self
+    }
+
     /// Get package info from npm registry
-    pub async fn get_package(&self, name: &str) -> Result<PackageInfo> {
-        let url = format!("{NPM_REGISTRY}/{name}");
+    async fn fetch_package(&self, name: &str) -> Result<PackageInfo> {
+        let registry = self.npmrc.scope_registry(name).unwrap_or(&self.registry);
+        // Scoped names are requested as `@scope%2fname`, like npm does
+        let url = format!("{registry}/{}", name.replace('/', "%2f"));
+        let auth = self.npmrc.auth_for(&url, &self.registry);
 
         let response = self
-            .client
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
+            .http
+            .get_authorized(&url, Some("application/json"), auth)
             .await
             .with_context(|| format!("Failed to fetch package: {name}"))?;
 
-        if response.status() == reqwest::StatusCode::NOT_FOUND {
-            anyhow::bail!("Package '{name}' not found on npm");
+        let source = if registry == NPM_REGISTRY { "npm" } else { registry };
+        match response.status {
+            reqwest::StatusCode::NOT_FOUND => {
+                return Err(NotFound(format!("Package '{name}' not found on {source}")).into());
+            }
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                let hint = if auth.is_some() {
+                    "check the credentials in .npmrc"
+                } else {
+                    "no credentials for it in .npmrc"
+                };
+                bail!("{source} refused access to '{name}' ({}; {hint})", response.status);
+            }
+            _ => {}
         }
 
-        let data: NpmPackageResponse = response
-            .json()
-            .await
+        let data: NpmPackageResponse = serde_json::from_str(&response.body)
             .with_context(|| format!("Failed to parse npm response for: {name}"))?;
 
         let mut versions: Vec<Version> = data
@@ -68,50 +120,45 @@ impl NpmClient {
 
         let latest_stable = versions.iter().rfind(|v| !v.is_prerelease()).cloned();
 
+        let published: BTreeMap<Version, Timestamp> = versions
+            .iter()
+            .filter_map(|v| {
+                let time = data.time.get(&v.to_string())?;
+                Some((v.clone(), Timestamp::parse(time)?))
+            })
+            .collect();
+
+        // Each version's `engines.node`, e.g. `>=18.12`
+        let requires: BTreeMap<Version, String> = data
+            .versions
+            .iter()
+            .filter_map(|(v, manifest)| {
+                let node = manifest.engines.get("node")?.as_str()?.trim();
+                if node.is_empty() || node == "*" {
+                    return None;
+                }
+                Some((Version::from_str(v).ok()?, node.to_string()))
+            })
+            .collect();
+
         Ok(PackageInfo {
             name: data.name,
             versions,
             latest,
             latest_stable,
+            published,
+            requires,
+            cached_at: response.cached_at,
         })
     }
+}
 
-    /// Get multiple packages concurrently with progress callback and rate limiting
-    pub async fn get_packages(
-        &self,
-        names: &[String],
-        progress_callback: impl Fn(usize, usize) + Send + Sync + 'static,
-    ) -> Vec<(String, Result<PackageInfo>)> {
-        let total = names.len();
-        let progress_callback = Arc::new(progress_callback);
-        let semaphore = Arc::new(Semaphore::new(10));
-
-        let mut tasks = Vec::new();
-
-        for name in names {
-            let client = self.clone();
-            let name = name.clone();
-            let semaphore = Arc::clone(&semaphore);
-
-            let task = tokio::spawn(async move {
-                let _permit = semaphore.acquire().await.expect("semaphore closed");
-                let result = client.get_package(&name).await;
-                (name, result)
-            });
-
-            tasks.push(task);
-        }
-
-        let mut results = Vec::new();
-        for (i, task) in tasks.into_iter().enumerate() {
-            match task.await {
-                Ok(result) => results.push(result),
-                Err(e) => results.push(("unknown".to_string(), Err(anyhow::anyhow!("Task failed: {e}")))),
-            }
-            progress_callback(i + 1, total);
-        }
-
-        results
+impl RegistryClient for NpmClient {
+    async fn get_package(&self, name: &str) -> Result<PackageInfo, RegistryError> {
+        // Unwrap `NotFound` so the caller can tell it apart from other failures
+        self.fetch_package(name)
+            .await
+            .map_err(|e| e.downcast::<NotFound>().map_or_else(RegistryError::new, RegistryError::new))
     }
 }
 
@@ -137,4 +184,78 @@ mod tests {
             .await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_custom_registry() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/api/npm/npm-remote/left-pad"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"name": "left-pad", "dist-tags": {"latest": "1.3.0"},
+                    "versions": {"1.1.3": {"engines": ["node >=0.4"]}, "1.3.0": {"engines": {"node": ">=18.12"}}}}"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let client = NpmClient::new(false)
+            .with_registry(&format!("{}/api/npm/npm-remote/", server.uri()));
+        let info = client.get_package("left-pad").await.expect("served by the mirror");
+        assert_eq!(info.latest.to_string(), "1.3.0");
+        assert_eq!(info.versions.len(), 2);
+        assert_eq!(info.requires.len(), 1);
+        assert_eq!(
+            info.requires.get(&info.latest).map(String::as_str),
+            Some(">=18.12")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_npmrc_scoped_registry_and_token() {
+        use wiremock::matchers::{header, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let public = MockServer::start().await;
+        let private = MockServer::start().await;
+        Mock::given(path("/react"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"name": "react", "dist-tags": {"latest": "19.1.0"}, "versions": {"19.1.0": {}}}"#,
+            ))
+            .mount(&public)
+            .await;
+        Mock::given(path("/npm/@acme%2fwidgets"))
+            .and(header("authorization", "Bearer s3cret"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"name": "@acme/widgets", "dist-tags": {"latest": "2.0.0"}, "versions": {"1.0.0": {}, "2.0.0": {}}}"#,
+            ))
+            .mount(&private)
+            .await;
+        Mock::given(path("/npm/@acme%2fwidgets"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&private)
+            .await;
+
+        let private_host = private.uri().trim_start_matches("http:").to_string();
+        let npmrc = format!(
+            "registry={}/\n@acme:registry={}/npm/\n{private_host}/npm/:_authToken=${{ACME_TOKEN}}\n",
+            public.uri(),
+            private.uri(),
+        );
+        let env = |name: &str| (name == "ACME_TOKEN").then(|| "s3cret".to_string());
+        let client = NpmClient::new(false).with_npmrc(Npmrc::parse(&npmrc, &env));
+
+        let react = client.get_package("react").await.expect("public registry");
+        assert_eq!(react.latest.to_string(), "19.1.0");
+        let widgets = client.get_package("@acme/widgets").await.expect("private registry");
+        assert_eq!(widgets.latest.to_string(), "2.0.0");
+
+        // Without the token the private registry refuses
+        let anonymous = NpmClient::new(false).with_npmrc(Npmrc::parse(
+            &format!("@acme:registry={}/npm/\n", private.uri()),
+            &env,
+        ));
+        let error = anonymous.get_package("@acme/widgets").await.unwrap_err();
+        assert!(error.to_string().contains("no credentials for it in .npmrc"), "{error}");
+    }
 }
@@ -0,0 +1,352 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use check_updates_core::http::Auth;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// npm settings that decide where packages are looked up and with which
+/// credentials, merged from the project, user and global `.npmrc` files
+///
+/// ```ini
+/// registry=https://registry.npmjs.org/
+/// @acme:registry=https://npm.acme.dev/api/npm/private/
+/// //npm.acme.dev/api/npm/private/:_authToken=${ACME_NPM_TOKEN}
+/// always-auth=true
+/// ```
+///
+/// Each key is taken from the first file that sets it, as npm does. `${VAR}`
+/// is replaced with the environment variable (left as is if unset; `${VAR?}`
+/// becomes empty instead).
+#[derive(Debug, Clone, Default)]
+pub struct Npmrc {
+    /// Default registry, without the trailing slash
+    registry: Option<String>,
+    /// Registry per scope (`@acme`), without the trailing slash
+    scopes: HashMap<String, String>,
+    /// Credentials per "nerf dart": `//host/path/`, as in `//host/path/:_authToken`
+    credentials: HashMap<String, Auth>,
+    /// Top-level `_authToken`/`_auth`/`username`+`_password`, an older form
+    /// that npm only sends with `always-auth`
+    legacy: Option<Auth>,
+    always_auth: bool,
+}
+
+impl Npmrc {
+    /// Read `<project>/.npmrc`, the user's `~/.npmrc` and the global `npmrc`,
+    /// in that order of precedence. Missing files are skipped.
+    pub fn load(project_dir: Option<&Path>) -> Result<Self> {
+        let mut paths = Vec::new();
+        if let Some(dir) = project_dir {
+            paths.push(dir.join(".npmrc"));
+        }
+        paths.extend(user_config());
+        paths.extend(global_config());
+        Self::from_files(&paths, &|name| env::var(name).ok())
+    }
+
+    /// Merge the files in `paths`, highest precedence first
+    pub fn from_files(paths: &[PathBuf], env: &dyn Fn(&str) -> Option<String>) -> Result<Self> {
+        let mut settings = HashMap::new();
+        for path in paths {
+            let content = match fs::read_to_string(path) {
+                Ok(content) => content,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+                }
+            };
+            for (key, value) in parse(&content, env) {
+                settings.entry(key).or_insert(value);
+            }
+        }
+        Ok(Self::from_settings(&settings))
+    }
+
+    /// Parse a single `.npmrc`
+    pub fn parse(content: &str, env: &dyn Fn(&str) -> Option<String>) -> Self {
+        Self::from_settings(&parse(content, env).into_iter().collect())
+    }
+
+    fn from_settings(settings: &HashMap<String, String>) -> Self {
+        let mut npmrc = Self {
+            registry: settings.get("registry").map(|url| trim_url(url)),
+            always_auth: settings.get("always-auth").is_some_and(|v| v == "true"),
+            legacy: auth_from(|field| settings.get(field).map(String::as_str)),
+            ..Self::default()
+        };
+
+        for key in settings.keys() {
+            if let Some(scope) = key.strip_suffix(":registry")
+                && scope.starts_with('@')
+            {
+                npmrc
+                    .scopes
+                    .insert(scope.to_string(), trim_url(&settings[key]));
+            } else if let Some((dart, _)) = key.rsplit_once(':')
+                && dart.starts_with("//")
+            {
+                let field =
+                    |name: &str| settings.get(&format!("{dart}:{name}")).map(String::as_str);
+                let Some(auth) = auth_from(field) else {
+                    continue;
+                };
+                let dart = if dart.ends_with('/') {
+                    dart.to_string()
+                } else {
+                    format!("{dart}/")
+                };
+                npmrc.credentials.entry(dart).or_insert(auth);
+            }
+        }
+        npmrc
+    }
+
+    /// The default registry, if one is configured
+    pub fn registry(&self) -> Option<&str> {
+        self.registry.as_deref()
+    }
+
+    /// The registry configured for a scoped package's scope
+    pub fn scope_registry(&self, package: &str) -> Option<&str> {
+        let (scope, _) = package.split_once('/')?;
+        self.scopes.get(scope).map(String::as_str)
+    }
+
+    /// Credentials to send with a request for `url`
+    ///
+    /// Those of the longest `//host/path/` matching the URL win. Without a
+    /// match, the top-level credentials are used for the default registry
+    /// when `always-auth` is set.
+    pub fn auth_for(&self, url: &str, default_registry: &str) -> Option<&Auth> {
+        let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let dart = format!("//{rest}");
+        // `//host/a/b/pkg` is tried as `//host/a/b/`, `//host/a/`, `//host/`
+        let matched = dart
+            .match_indices('/')
+            .filter(|&(i, _)| i > 1)
+            .rev()
+            .find_map(|(i, _)| self.credentials.get(&dart[..=i]));
+        if matched.is_some() {
+            return matched;
+        }
+        let on_default = url
+            .strip_prefix(default_registry)
+            .is_some_and(|path| path.starts_with('/'));
+        self.legacy
+            .as_ref()
+            .filter(|_| self.always_auth && on_default)
+    }
+}
+
+/// `key=value` pairs of an ini-style `.npmrc`, with `${VAR}` replaced
+fn parse(content: &str, env: &dyn Fn(&str) -> Option<String>) -> Vec<(String, String)> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', ';', '[']))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            (interpolate(key.trim(), env), interpolate(value, env))
+        })
+        .collect()
+}
+
+/// Replace `${VAR}` with the variable's value; unset variables are left
+/// alone, except `${VAR?}` which becomes empty
+fn interpolate(text: &str, env: &dyn Fn(&str) -> Option<String>) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let expr = &rest[start + 2..start + len];
+        out.push_str(&rest[..start]);
+        let (name, optional) = match expr.strip_suffix('?') {
+            Some(name) => (name, true),
+            None => (expr, false),
+        };
+        match env(name) {
+            Some(value) => out.push_str(&value),
+            None if optional => {}
+            None => out.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Credentials from `_authToken`, `_auth` (base64 `user:pass`) or
+/// `username` with a base64 `_password`, looked up with `field`
+fn auth_from<'a>(field: impl Fn(&str) -> Option<&'a str>) -> Option<Auth> {
+    let non_empty = |name: &str| field(name).filter(|v| !v.is_empty());
+    if let Some(token) = non_empty("_authToken") {
+        return Some(Auth::Bearer(token.to_string()));
+    }
+    if let Some(auth) = non_empty("_auth") {
+        return Some(Auth::Header(format!("Basic {auth}")));
+    }
+    let username = non_empty("username")?;
+    let password = non_empty("_password")
+        .and_then(|p| BASE64.decode(p).ok())
+        .and_then(|p| String::from_utf8(p).ok());
+    Some(Auth::Basic {
+        username: username.to_string(),
+        password,
+    })
+}
+
+fn trim_url(url: &str) -> String {
+    url.trim_end_matches('/').to_string()
+}
+
+/// An npm setting from the environment (`npm_config_<name>`, in either case)
+fn npm_env(name: &str) -> Option<String> {
+    env::var(format!("npm_config_{name}"))
+        .or_else(|_| env::var(format!("NPM_CONFIG_{}", name.to_uppercase())))
+        .ok()
+        .filter(|v| !v.is_empty())
+}
+
+/// `$npm_config_userconfig`, or `~/.npmrc`
+fn user_config() -> Option<PathBuf> {
+    npm_env("userconfig")
+        .map(PathBuf::from)
+        .or_else(|| Some(dirs::home_dir()?.join(".npmrc")))
+}
+
+/// `$npm_config_globalconfig`, or `<prefix>/etc/npmrc` where the prefix is
+/// `$npm_config_prefix` or the directory `node` is installed in
+fn global_config() -> Option<PathBuf> {
+    if let Some(path) = npm_env("globalconfig") {
+        return Some(PathBuf::from(path));
+    }
+    let prefix = match npm_env("prefix") {
+        Some(prefix) => PathBuf::from(prefix),
+        None => {
+            let node = env::split_paths(&env::var_os("PATH")?)
+                .map(|dir| dir.join(if cfg!(windows) { "node.exe" } else { "node" }))
+                .find(|path| path.is_file())?;
+            let bin = node.parent()?;
+            // Windows installs node in the prefix itself, elsewhere in <prefix>/bin
+            if cfg!(windows) {
+                bin.to_path_buf()
+            } else {
+                bin.parent()?.to_path_buf()
+            }
+        }
+    };
+    Some(prefix.join("etc").join("npmrc"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(name: &str) -> Option<String> {
+        (name == "ACME_TOKEN").then(|| "s3cret".to_string())
+    }
+
+    #[test]
+    fn test_scopes_and_credentials() {
+        let npmrc = Npmrc::parse(
+            r#"
+            ; company mirror
+            registry = https://npm.acme.dev/api/npm/public/
+            @acme:registry=https://npm.acme.dev/api/npm/private/
+            //npm.acme.dev/api/npm/private/:_authToken=${ACME_TOKEN}
+            //npm.acme.dev/:username=ci
+            //npm.acme.dev/:_password="aHVudGVyMg=="
+            "#,
+            &env,
+        );
+        assert_eq!(
+            npmrc.registry(),
+            Some("https://npm.acme.dev/api/npm/public")
+        );
+        assert_eq!(
+            npmrc.scope_registry("@acme/widgets"),
+            Some("https://npm.acme.dev/api/npm/private")
+        );
+        assert_eq!(npmrc.scope_registry("@other/thing"), None);
+        assert_eq!(npmrc.scope_registry("react"), None);
+
+        let default = "https://npm.acme.dev/api/npm/public";
+        assert_eq!(
+            npmrc.auth_for(
+                "https://npm.acme.dev/api/npm/private/@acme%2fwidgets",
+                default
+            ),
+            Some(&Auth::Bearer("s3cret".to_string()))
+        );
+        // Falls back to the host-wide entry
+        assert_eq!(
+            npmrc.auth_for("https://npm.acme.dev/api/npm/public/react", default),
+            Some(&Auth::Basic {
+                username: "ci".to_string(),
+                password: Some("hunter2".to_string()),
+            })
+        );
+        assert_eq!(
+            npmrc.auth_for("https://registry.npmjs.org/react", default),
+            None
+        );
+    }
+
+    #[test]
+    fn test_always_auth_and_interpolation() {
+        let legacy = "_auth=Y2k6aHVudGVyMg==\nregistry=https://npm.acme.dev/\n";
+        let npmrc = Npmrc::parse(legacy, &env);
+        assert_eq!(
+            npmrc.auth_for("https://npm.acme.dev/react", "https://npm.acme.dev"),
+            None
+        );
+
+        let npmrc = Npmrc::parse(&format!("{legacy}always-auth=true\n"), &env);
+        assert_eq!(
+            npmrc.auth_for("https://npm.acme.dev/react", "https://npm.acme.dev"),
+            Some(&Auth::Header("Basic Y2k6aHVudGVyMg==".to_string()))
+        );
+        assert_eq!(
+            npmrc.auth_for(
+                "https://npm.acme.dev.evil.com/react",
+                "https://npm.acme.dev"
+            ),
+            None
+        );
+
+        assert_eq!(interpolate("a${ACME_TOKEN}b", &env), "as3cretb");
+        assert_eq!(interpolate("${UNSET}", &env), "${UNSET}");
+        assert_eq!(interpolate("x${UNSET?}", &env), "x");
+    }
+
+    #[test]
+    fn test_precedence() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let project = dir.path().join("project.npmrc");
+        let user = dir.path().join("user.npmrc");
+        fs::write(&project, "registry=https://project.example/\n")?;
+        fs::write(
+            &user,
+            "registry=https://user.example/\n@acme:registry=https://user.example/acme/\n",
+        )?;
+
+        let npmrc = Npmrc::from_files(&[project, dir.path().join("missing"), user], &env)?;
+        assert_eq!(npmrc.registry(), Some("https://project.example"));
+        assert_eq!(
+            npmrc.scope_registry("@acme/x"),
+            Some("https://user.example/acme")
+        );
+        Ok(())
+    }
+}
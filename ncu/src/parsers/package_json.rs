@@ -1,13 +1,24 @@
 use anyhow::{Context, Result};
-use check_updates_core::{Dependency, VersionSpec};
+use crate::ecosystem::NpmEcosystem;
+use check_updates_core::{Dependency, Ecosystem, VersionSpec};
 use std::fs;
 use std::path::Path;
 
-pub struct PackageJsonParser;
+pub struct PackageJsonParser {
+    ecosystem: NpmEcosystem,
+}
 
 impl PackageJsonParser {
     pub fn new() -> Self {
-        Self
+        Self {
+            ecosystem: NpmEcosystem::default(),
+        }
+    }
+
+    /// Let ranges match pre-releases outside their own release (npm's `includePrerelease`)
+    pub fn with_prerelease(mut self, include_prerelease: bool) -> Self {
+        self.ecosystem = NpmEcosystem::new(include_prerelease);
+        self
     }
 
     /// Parse dependencies from a package.json file
@@ -43,6 +54,19 @@ impl PackageJsonParser {
         Ok(deps)
     }
 
+    /// The Node versions a package supports (`engines.node`)
+    pub fn node_engine(&self, path: &Path) -> Result<Option<String>> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let parsed: serde_json::Value = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse JSON in {}", path.display()))?;
+        Ok(parsed
+            .get("engines")
+            .and_then(|e| e.get("node"))
+            .and_then(|n| n.as_str())
+            .map(String::from))
+    }
+
     fn parse_deps(
         &self,
         deps: &serde_json::Map<String, serde_json::Value>,
@@ -64,7 +88,7 @@ impl PackageJsonParser {
                     continue;
                 }
 
-                if let Ok(version_spec) = Self::parse_npm_version(version_str) {
+                if let Ok(version_spec) = self.parse_npm_version(version_str) {
                     let line_number = Self::find_line_number(content, name);
                     let original_line = content
                         .lines()
@@ -74,6 +98,8 @@ impl PackageJsonParser {
 
                     result.push(Dependency {
                         name: name.clone(),
+                        registry: None,
+                        package: None,
                         version_spec,
                         source_file: source_file.to_path_buf(),
                         line_number,
@@ -87,12 +113,16 @@ impl PackageJsonParser {
     }
 
     /// Parse npm version spec into VersionSpec
-    fn parse_npm_version(s: &str) -> Result<VersionSpec> {
+    fn parse_npm_version(&self, s: &str) -> Result<VersionSpec> {
         let s = s.trim();
 
-        // npm uses same caret/tilde semantics
-        // ^1.2.3, ~1.2.3, >=1.0.0, 1.2.3, etc.
-        VersionSpec::parse(s).map_err(|e| anyhow::anyhow!("{e}"))
+        // Dist-tags (`latest`, `next`) and aliases (`npm:pkg@^1`) aren't ranges;
+        // keep them as complex so they're still reported, just never rewritten
+        match self.ecosystem.parse_spec(s) {
+            Ok(spec) => Ok(spec),
+            Err(_) if !s.is_empty() => Ok(VersionSpec::Complex(s.to_string())),
+            Err(e) => Err(anyhow::anyhow!("{e}")),
+        }
     }
 
     fn find_line_number(content: &str, package_name: &str) -> usize {
@@ -114,7 +144,9 @@ impl Default for PackageJsonParser {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use check_updates_core::Version;
     use std::io::Write;
+    use std::str::FromStr;
     use tempfile::NamedTempFile;
 
     #[test]
@@ -124,6 +156,7 @@ mod tests {
             file,
             r#"{{
   "name": "test",
+  "engines": {{ "node": ">=18" }},
   "dependencies": {{
     "express": "^4.18.0",
     "lodash": "~4.17.0"
@@ -135,12 +168,40 @@ mod tests {
         )?;
 
         let parser = PackageJsonParser::new();
-        let deps = parser.parse(&file.path().to_path_buf())?;
+        let deps = parser.parse(file.path())?;
 
         assert_eq!(deps.len(), 3);
 
         let express = deps.iter().find(|d| d.name == "express").unwrap();
         assert_eq!(express.version_spec.version_string().unwrap(), "4.18.0");
+        assert_eq!(parser.node_engine(file.path())?.as_deref(), Some(">=18"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_npm_ranges() -> Result<()> {
+        let parser = PackageJsonParser::new();
+        let spec = parser.parse_npm_version("^1 || ^2")?;
+        assert!(matches!(spec, VersionSpec::NpmRange(_)));
+        assert!(spec.satisfies(&Version::from_str("2.4.0")?));
+        assert!(!spec.satisfies(&Version::from_str("3.0.0")?));
+
+        let spec = parser.parse_npm_version("1.2 - 1.4")?;
+        assert!(spec.satisfies(&Version::from_str("1.4.9")?));
+
+        let spec = parser.parse_npm_version(">=1 <2")?;
+        assert!(spec.satisfies(&Version::from_str("1.0.0")?));
+        assert!(!spec.satisfies(&Version::from_str("2.0.0")?));
+
+        assert!(matches!(
+            parser.parse_npm_version("*")?,
+            VersionSpec::Any
+        ));
+        assert!(matches!(
+            parser.parse_npm_version("latest")?,
+            VersionSpec::Complex(_)
+        ));
 
         Ok(())
     }
@@ -160,7 +221,7 @@ mod tests {
         )?;
 
         let parser = PackageJsonParser::new();
-        let deps = parser.parse(&file.path().to_path_buf())?;
+        let deps = parser.parse(file.path())?;
 
         assert_eq!(deps.len(), 1);
         assert_eq!(deps[0].name, "express");
@@ -1,4 +1,5 @@
-use check_updates_core::{DependencyCheck, UpdateSeverity};
+use crate::ecosystem::NpmEcosystem;
+use check_updates_core::{DependencyCheck, Ecosystem, UpdateSeverity};
 use anyhow::{Context, Result};
 use std::collections::HashSet;
 use std::fs;
@@ -37,9 +38,9 @@ impl FileUpdater {
             };
 
             if let Some(spec) = version_spec
-                && spec.is_rewritable() {
+                && spec.is_rewritable()
+                && let Some(new_version) = NpmEcosystem::default().format_spec(spec) {
                 // For npm, preserve the original prefix (^, ~, etc.)
-                let new_version = spec.to_string();
                 file_updates
                     .entry(check.dependency.source_file.clone())
                     .or_default()
@@ -138,18 +139,26 @@ mod tests {
         DependencyCheck {
             dependency: Dependency {
                 name: name.to_string(),
+                registry: None,
+                package: None,
                 version_spec: VersionSpec::parse(spec_str).unwrap(),
                 source_file: path,
                 line_number: 2,
-                original_line: format!("\"{}\": \"{}\"", name, spec_str),
+                original_line: format!("\"{name}\": \"{spec_str}\""),
             },
             installed: Some(Version::from_str(spec_str.trim_start_matches('^').trim_start_matches('~')).unwrap()),
             in_range: Some(target.clone()),
             latest: target.clone(),
             target: Some(target.clone()),
-            target_spec: Some(VersionSpec::parse(&format!("^{}", target_version)).unwrap()),
+            target_spec: Some(VersionSpec::parse(&format!("^{target_version}")).unwrap()),
             severity: Some(severity),
-            force_spec: Some(VersionSpec::parse(&format!("^{}", target_version)).unwrap()),
+            force_spec: Some(VersionSpec::parse(&format!("^{target_version}")).unwrap()),
+            held: None,
+            too_new: None,
+            blocked: None,
+            installed_released: None,
+            latest_released: None,
+            cached_at: None,
         }
     }
 
@@ -178,9 +187,38 @@ mod tests {
         updater.apply_updates(&checks, false, false)?;
 
         let content = fs::read_to_string(&temp_path)?;
-        assert!(content.contains("4.18.2"), "express should be updated: {}", content);
+        assert!(content.contains("4.18.2"), "express should be updated: {content}");
         assert!(!content.contains("4.18.0") || content.contains("^4.18.0"), "lodash should NOT be updated");
 
         Ok(())
     }
+
+    #[test]
+    fn test_update_union_range() -> Result<()> {
+        let mut file = NamedTempFile::new()?;
+        writeln!(
+            file,
+            r#"{{
+  "peerDependencies": {{
+    "react": "^17.0.0 || ^18.0.0"
+  }}
+}}"#
+        )?;
+        file.flush()?;
+
+        let temp_path = file.path().to_path_buf();
+        let spec = NpmEcosystem::default().parse_spec("^17.0.0 || ^18.0.0")?;
+        let latest = Version::from_str("19.1.0")?;
+        let mut check = create_check("react", "^18.0.0", temp_path.clone(), "19.1.0", UpdateSeverity::Major);
+        check.force_spec = Some(spec.with_version(&latest));
+        check.dependency.version_spec = spec;
+
+        let updater = FileUpdater::new();
+        updater.apply_updates(&[check], false, true)?;
+
+        let content = fs::read_to_string(&temp_path)?;
+        assert!(content.contains(r#""react": "^17.0.0 || ^19.1.0""#), "union should keep ^17: {content}");
+
+        Ok(())
+    }
 }
@@ -1,3 +1,8 @@
+use check_updates_core::cache::{HttpCache, DEFAULT_TTL};
+use check_updates_core::config::Defaults;
+use check_updates_core::date::Age;
+use check_updates_core::registry::RegistryUrl;
+use check_updates_core::{OutputFormat, UpdateSeverity};
 use clap::Parser;
 use std::path::PathBuf;
 
@@ -26,12 +31,83 @@ pub struct Args {
     #[arg(short, long)]
     pub force: bool,
 
+    /// Pick updates from a checklist, then write the selected ones
+    #[arg(short, long, conflicts_with = "global")]
+    pub interactive: bool,
+
     /// Include pre-release versions
     #[arg(short, long)]
     pub pre_release: bool,
+
+    /// Output format: table, json or ndjson
+    #[arg(long, value_name = "FORMAT", default_value = "table")]
+    pub format: OutputFormat,
+
+    /// Exit with status 2 if any update is at least this severe (patch, minor, major),
+    /// or 3 if registry lookups failed
+    #[arg(long, value_name = "SEVERITY")]
+    pub fail_on: Option<UpdateSeverity>,
+
+    /// Skip versions published less than this long ago (e.g. 7d, 12h, 2w)
+    #[arg(long, value_name = "AGE", conflicts_with = "global")]
+    pub min_age: Option<Age>,
+
+    /// Consider releases whose Requires-Python excludes the project's Python
+    #[arg(long, conflicts_with = "global")]
+    pub ignore_requires_python: bool,
+
+    /// Don't read or write the registry response cache
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Revalidate every cached registry response
+    #[arg(long, conflicts_with = "no_cache")]
+    pub refresh: bool,
+
+    /// Answer every registry query from the cache, without network access
+    #[arg(long, conflicts_with_all = ["no_cache", "refresh"])]
+    pub offline: bool,
+
+    /// Reuse cached registry responses younger than this without asking (default 1h)
+    #[arg(long, value_name = "AGE")]
+    pub cache_ttl: Option<Age>,
+
+    /// PyPI JSON API base to query instead of https://pypi.org/pypi (a mirror or proxy)
+    #[arg(long, value_name = "URL", env = "PCU_REGISTRY")]
+    pub registry: Option<RegistryUrl>,
+
+    /// Answer registry queries from a directory of `<package>.json` fixtures
+    /// instead of the network; only meant for the test suite
+    #[arg(long, value_name = "DIR", hide = true)]
+    pub registry_fixtures: Option<PathBuf>,
 }
 
 impl Args {
+    /// Turn on flags from the config's `[defaults]` that weren't given on the command line
+    pub fn with_defaults(mut self, defaults: &Defaults) -> Self {
+        self.minor |= defaults.minor;
+        self.force |= defaults.force;
+        self.pre_release |= defaults.pre_release;
+        self.fail_on = self.fail_on.or(defaults.fail_on);
+        self.min_age = self.min_age.or(defaults.min_age);
+        self.cache_ttl = self.cache_ttl.or(defaults.cache_ttl);
+        self.registry = self.registry.or_else(|| defaults.registry.clone());
+        self
+    }
+
+    /// The registry response cache, unless disabled
+    pub fn cache(&self) -> Option<HttpCache> {
+        if self.no_cache {
+            return None;
+        }
+        HttpCache::new().map(|cache| {
+            cache
+                .with_ttl(self.cache_ttl.unwrap_or(DEFAULT_TTL))
+                .with_refresh(self.refresh)
+                .with_offline(self.offline)
+        })
+    }
+
     /// Get the project path, defaulting to current directory
     pub fn project_path(&self) -> PathBuf {
         self.path.clone().unwrap_or_else(|| PathBuf::from("."))
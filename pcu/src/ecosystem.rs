@@ -0,0 +1,29 @@
+use check_updates_core::Ecosystem;
+
+/// PEP 440 specifiers, plus the Poetry/PDM caret and tilde extensions
+pub struct PythonEcosystem;
+
+impl Ecosystem for PythonEcosystem {
+    fn name(&self) -> &'static str {
+        "pypi"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use check_updates_core::Version;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_bare_version_is_exact() {
+        let eco = PythonEcosystem;
+        let spec = eco.parse_spec("1.2").unwrap();
+        assert!(eco.satisfies(&spec, &Version::from_str("1.2.0").unwrap()));
+        assert!(!eco.satisfies(&spec, &Version::from_str("1.2.1").unwrap()));
+
+        let spec = eco.parse_spec("==1.*").unwrap();
+        assert!(eco.satisfies(&spec, &Version::from_str("1.9").unwrap()));
+        assert_eq!(eco.format_spec(&spec).unwrap(), "==1.*");
+    }
+}
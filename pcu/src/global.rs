@@ -1,3 +1,4 @@
+use check_updates_core::report::GlobalRecord;
 use check_updates_core::{UpdateSeverity, Version};
 use anyhow::Result;
 use std::collections::{HashMap, HashSet};
@@ -51,16 +52,29 @@ impl GlobalCheck {
         let current = &self.package.installed_version;
         let target = &self.latest;
 
-        if target.major > current.major {
+        if target.major() > current.major() {
             Some(UpdateSeverity::Major)
-        } else if target.minor > current.minor {
+        } else if target.minor() > current.minor() {
             Some(UpdateSeverity::Minor)
-        } else if target.patch > current.patch {
+        } else if target.patch() > current.patch() {
             Some(UpdateSeverity::Patch)
         } else {
             None
         }
     }
+
+    /// Convert to a machine-readable record
+    pub fn to_record(&self) -> GlobalRecord {
+        let mut record = GlobalRecord::new(
+            &self.package.name,
+            &self.package.source.to_string(),
+            &self.package.installed_version.to_string(),
+            Some(self.latest.to_string()),
+        )
+        .with_update(self.has_update, self.update_severity());
+        record.python_version.clone_from(&self.package.python_version);
+        record
+    }
 }
 
 /// Discovers globally installed packages from various sources
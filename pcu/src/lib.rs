@@ -1,10 +1,13 @@
 pub mod cli;
 pub mod detector;
+pub mod ecosystem;
 pub mod global;
+pub mod indexes;
 pub mod output;
 pub mod parsers;
 pub mod pypi;
 pub mod python;
+pub mod simple;
 pub mod updater;
 pub mod uv_python;
 
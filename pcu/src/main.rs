@@ -4,35 +4,81 @@ use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
 use pcu::cli::Args;
 use pcu::detector::ProjectDetector;
+use pcu::ecosystem::PythonEcosystem;
 use pcu::global::{
     generate_upgrade_commands, GlobalCheck, GlobalPackageDiscovery, UpgradeCommand,
 };
+use pcu::indexes::IndexSet;
 use pcu::output::{GlobalTableRenderer, TableRenderer, UvPythonTableRenderer};
 use pcu::parsers::{
     CondaParser, DependencyParser, LockfileParser, PyProjectParser, RequirementsParser,
 };
 use pcu::pypi::PyPiClient;
-use pcu::python::get_python_info;
+use pcu::python::{get_python_info, RequiresPython};
 use pcu::updater::FileUpdater;
-use pcu::uv_python::{generate_uv_python_upgrade_commands, UvPythonDiscovery};
-use check_updates_core::{DependencyCheck, DependencyResolver};
+use pcu::uv_python::{generate_uv_python_upgrade_commands, UvPythonCheck, UvPythonDiscovery};
+use check_updates_core::config::ManifestSection;
+use check_updates_core::exit::code;
+use check_updates_core::interactive::Picker;
+use check_updates_core::registry::{FixtureRegistry, RegistryClient};
+use check_updates_core::{exit_status, Annotations, Config, DependencyCheck, DependencyResolver, Report, ReportMode};
 use std::collections::HashSet;
+use std::process::ExitCode;
 use std::sync::{Arc, Mutex};
 
+/// `[tool.check-updates]` in pyproject.toml, used when there's no .check-updates.toml
+const CONFIG_SECTIONS: &[ManifestSection] = &[ManifestSection {
+    file: "pyproject.toml",
+    path: &["tool", "check-updates"],
+}];
+
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let args = Args::parse();
 
+    // Load .check-updates.toml (or the manifest section) and merge its default
+    // flags before building the client, which needs `registry` and `pre-release`
+    let config = if args.global {
+        Config::default()
+    } else {
+        Config::load(&args.project_path(), CONFIG_SECTIONS)?
+    };
+    let args = args.with_defaults(&config.defaults);
+
+    let status = match &args.registry_fixtures {
+        Some(dir) => {
+            let fixtures = FixtureRegistry::from_dir(dir, args.pre_release)?;
+            run(&args, &config, &fixtures).await?
+        }
+        None => {
+            // Indexes declared by the project (and pip.conf); --registry (or
+            // PCU_REGISTRY, or the config) replaces the default one
+            let project_dir = (!args.global).then(|| args.project_path());
+            let mut pypi_client = PyPiClient::new(args.pre_release)
+                .with_cache(args.cache())
+                .with_indexes(IndexSet::discover(project_dir.as_deref())?);
+            if let Some(registry) = &args.registry {
+                pypi_client = pypi_client.with_index_url(registry.as_str());
+            }
+            run(&args, &config, &pypi_client).await?
+        }
+    };
+    Ok(ExitCode::from(status))
+}
+
+async fn run(args: &Args, config: &Config, client: &impl RegistryClient) -> Result<u8> {
     if args.global {
-        run_global_mode(&args).await
+        run_global_mode(args, client).await
     } else {
-        run_project_mode(&args).await
+        run_project_mode(args, config, client).await
     }
 }
 
-async fn run_global_mode(args: &Args) -> Result<()> {
+async fn run_global_mode(args: &Args, pypi_client: &impl RegistryClient) -> Result<u8> {
+    let human = args.format.is_human();
+
     // Warn if -u flag is used
-    if args.update {
+    if args.update && human {
         println!(
             "Note: --update flag is ignored in global mode. Commands will be shown instead.\n"
         );
@@ -48,7 +94,7 @@ async fn run_global_mode(args: &Args) -> Result<()> {
     );
 
     // Print Python version header
-    if let Some(py_info) = python_info {
+    if human && let Some(py_info) = &python_info {
         let version_str = if let Some(ref latest) = py_info.latest {
             if py_info.has_update() {
                 format!(
@@ -65,10 +111,10 @@ async fn run_global_mode(args: &Args) -> Result<()> {
         println!("{version_str}\n");
     }
 
-    if packages.is_empty() {
+    if packages.is_empty() && human {
         println!("No globally installed packages found.");
         println!("Checked: uv tools, pipx, pip --user");
-        return Ok(());
+        return Ok(code::SUCCESS);
     }
 
     // 2. Query PyPI for latest versions
@@ -79,8 +125,6 @@ async fn run_global_mode(args: &Args) -> Result<()> {
         .into_iter()
         .collect();
 
-    let pypi_client = PyPiClient::new(args.pre_release);
-
     // Create progress bar
     let progress_bar = ProgressBar::new(package_names.len() as u64);
     progress_bar.set_style(
@@ -98,12 +142,17 @@ async fn run_global_mode(args: &Args) -> Result<()> {
             let pb = pb_clone.lock().expect("lock poisoned");
             pb.set_position(current as u64);
         })
-        .await?;
+        .await;
 
     progress_bar.finish_and_clear();
+    if result.all_failed() {
+        anyhow::bail!("Failed to fetch all packages:\n{}", result.failures());
+    }
 
     let package_infos = result.packages;
+    let not_found = result.not_found;
     let fetch_errors = result.errors;
+    let uncached = result.uncached;
 
     // 3. Build check results
     let mut checks: Vec<GlobalCheck> = Vec::new();
@@ -115,7 +164,7 @@ async fn run_global_mode(args: &Args) -> Result<()> {
                 // -m flag: limit to same major
                 info.versions
                     .iter()
-                    .filter(|v| v.major == package.installed_version.major)
+                    .filter(|v| v.major() == package.installed_version.major())
                     .max()
                     .cloned()
                     .unwrap_or_else(|| package.installed_version.clone())
@@ -134,11 +183,37 @@ async fn run_global_mode(args: &Args) -> Result<()> {
         }
     }
 
-    // 4. Display results (renderer shows "All packages up to date." per section if needed)
+    let uv_severities = uv_python_checks
+        .iter()
+        .flatten()
+        .filter_map(|c| c.to_record().severity);
+    // Packages the registry doesn't have are listed, but only failed lookups
+    // leave the run unsure of them
+    let status = exit_status(
+        args.fail_on,
+        checks.iter().filter_map(GlobalCheck::update_severity).chain(uv_severities),
+        !fetch_errors.is_empty() || !uncached.is_empty(),
+    );
+
+    // 4. Machine-readable output replaces the tables and upgrade commands
+    if !human {
+        let mut report = Report::new("pcu", ReportMode::Global);
+        report.global.extend(checks.iter().map(GlobalCheck::to_record));
+        if let Ok(uv_checks) = &uv_python_checks {
+            report.global.extend(uv_checks.iter().map(UvPythonCheck::to_record));
+        }
+        report.add_not_found(&not_found);
+        report.add_formatted_errors(&fetch_errors);
+        report.uncached = uncached;
+        report.print(args.format)?;
+        return Ok(status);
+    }
+
+    // 5. Display results (renderer shows "All packages up to date." per section if needed)
     let renderer = GlobalTableRenderer::new(true);
     renderer.render(&checks);
 
-    // 4b. Display uv Python version checks
+    // 5b. Display uv Python version checks
     if let Ok(uv_checks) = &uv_python_checks
         && !uv_checks.is_empty() {
             println!();
@@ -146,7 +221,7 @@ async fn run_global_mode(args: &Args) -> Result<()> {
             uv_renderer.render(uv_checks);
         }
 
-    // 5. Print upgrade commands
+    // 6. Print upgrade commands
     let mut commands = generate_upgrade_commands(&checks);
 
     // Add uv Python upgrade commands
@@ -165,20 +240,44 @@ async fn run_global_mode(args: &Args) -> Result<()> {
         }
     }
 
-    // 6. Print fetch errors at the end
-    if !fetch_errors.is_empty() {
+    // 7. Print fetch errors at the end
+    if !not_found.is_empty() {
         println!();
         println!("{}", "Packages not found on PyPI:".dimmed());
+        for error in &not_found {
+            println!("  {}", error.dimmed());
+        }
+    }
+    if !fetch_errors.is_empty() {
+        println!();
+        println!("{}", "Failed to fetch from PyPI:".dimmed());
         for error in &fetch_errors {
             println!("  {}", error.dimmed());
         }
     }
+    if !uncached.is_empty() {
+        println!();
+        println!("{}", "Not in the offline cache (run once without --offline to fill it):".dimmed());
+        for name in &uncached {
+            println!("  {}", name.dimmed());
+        }
+    }
 
-    Ok(())
+    Ok(status)
 }
 
-async fn run_project_mode(args: &Args) -> Result<()> {
+async fn run_project_mode(
+    args: &Args,
+    config: &Config,
+    pypi_client: &impl RegistryClient,
+) -> Result<u8> {
     let project_path = args.project_path();
+    let human = args.format.is_human();
+    let mut report = Report::new("pcu", ReportMode::Project);
+
+    if args.interactive && !human {
+        anyhow::bail!("--interactive can't be combined with --format {}", args.format);
+    }
 
     // Validate project path exists
     if !project_path.exists() {
@@ -194,8 +293,11 @@ async fn run_project_mode(args: &Args) -> Result<()> {
     let detected_files = detector.detect()?;
 
     if detected_files.is_empty() {
-        println!("No dependency files found in {project_path:?}");
-        return Ok(());
+        if human {
+            println!("No dependency files found in {project_path:?}");
+        }
+        report.print(args.format)?;
+        return Ok(code::SUCCESS);
     }
 
     // 2. Parse all dependency files
@@ -220,9 +322,19 @@ async fn run_project_mode(args: &Args) -> Result<()> {
         all_dependencies.extend(deps);
     }
 
+    // Drop packages excluded by config or a `check-updates: ignore` comment
+    let annotations = Annotations::scan(&all_dependencies, &PythonEcosystem);
+    for warning in annotations.warnings() {
+        eprintln!("Warning: {warning}");
+    }
+    all_dependencies.retain(|d| !config.is_ignored(&d.name) && !annotations.is_ignored(d));
+
     if all_dependencies.is_empty() {
-        println!("No dependencies found in any files");
-        return Ok(());
+        if human {
+            println!("No dependencies found in any files");
+        }
+        report.print(args.format)?;
+        return Ok(code::SUCCESS);
     }
 
     // Get installed versions from lock file
@@ -236,8 +348,6 @@ async fn run_project_mode(args: &Args) -> Result<()> {
         .into_iter()
         .collect();
 
-    let pypi_client = PyPiClient::new(args.pre_release);
-
     // Create progress bar
     let progress_bar = ProgressBar::new(package_names.len() as u64);
     progress_bar.set_style(
@@ -258,13 +368,17 @@ async fn run_project_mode(args: &Args) -> Result<()> {
         pb.set_position(current as u64);
     }).await;
 
-    let pypi_result = pypi_result?;
+    if pypi_result.all_failed() {
+        anyhow::bail!("Failed to fetch all packages:\n{}", pypi_result.failures());
+    }
     let package_infos = pypi_result.packages;
+    let not_found = pypi_result.not_found;
     let fetch_errors = pypi_result.errors;
+    let uncached = pypi_result.uncached;
     progress_bar.finish_and_clear();
 
     // Print Python version header
-    if let Some(py_info) = python_info {
+    if human && let Some(py_info) = &python_info {
         let version_str = if let Some(ref latest) = py_info.latest {
             if py_info.has_update() {
                 format!(
@@ -282,26 +396,73 @@ async fn run_project_mode(args: &Args) -> Result<()> {
     }
 
     // Print fetch errors if any
-    if !fetch_errors.is_empty() {
+    if human && !not_found.is_empty() {
         println!("{}", "Packages not found on PyPI:".dimmed());
+        for error in &not_found {
+            println!("  {}", error.dimmed());
+        }
+    }
+    if human && !fetch_errors.is_empty() {
+        println!("{}", "Failed to fetch from PyPI:".dimmed());
         for error in &fetch_errors {
             println!("  {}", error.dimmed());
         }
         println!();
     }
+    if human && !uncached.is_empty() {
+        println!("{}", "Not in the offline cache (run once without --offline to fill it):".dimmed());
+        for name in &uncached {
+            println!("  {}", name.dimmed());
+        }
+        println!();
+    }
 
-    // 4. Resolve updates
-    let resolver = DependencyResolver::new();
+    // 4. Resolve updates, leaving out releases that don't support the oldest
+    // Python in the project's `requires-python` (or, without one, the
+    // interpreter on PATH)
+    let mut resolver = DependencyResolver::new()
+        .with_ecosystem(PythonEcosystem)
+        .with_min_age(args.min_age);
+    let pyproject = project_path.join("pyproject.toml");
+    let declared_python = if pyproject.exists() {
+        pyproject_parser.requires_python(&pyproject)?
+    } else {
+        None
+    };
+    let target_python = declared_python
+        .as_deref()
+        .and_then(RequiresPython::parse)
+        .and_then(|requires| requires.lowest())
+        .or_else(|| python_info.as_ref().map(|p| p.current.clone()));
+    if !args.ignore_requires_python
+        && let Some(python) = target_python
+    {
+        resolver = resolver.with_filter(move |_, info, version| {
+            let requires = info.requires.get(version)?;
+            let supported = RequiresPython::parse(requires)?.contains(&python);
+            (!supported).then(|| format!("requires Python {requires}"))
+        });
+    }
     let mut checks: Vec<DependencyCheck> = Vec::new();
 
     for dependency in &all_dependencies {
         if let Some(package_info) = package_infos.get(&dependency.name) {
             let installed = installed_versions.get(&dependency.name);
             let check = resolver.resolve(dependency, package_info, installed);
+            let check = config.apply(check, package_info, &resolver);
+            let check = annotations.apply(check, package_info, &resolver);
             checks.push(check);
         }
     }
 
+    // Packages the registry doesn't have are listed, but only failed lookups
+    // leave the run unsure of them
+    let status = exit_status(
+        args.fail_on,
+        checks.iter().filter_map(|c| c.severity),
+        !fetch_errors.is_empty() || !uncached.is_empty(),
+    );
+
     // 5. Deduplicate for display (same package with same target)
     let mut seen: HashSet<String> = HashSet::new();
     let deduplicated: Vec<&DependencyCheck> = checks
@@ -319,20 +480,61 @@ async fn run_project_mode(args: &Args) -> Result<()> {
         })
         .collect();
 
-    // 6. Display results table
-    let renderer = TableRenderer::new(true);
-    let header = if args.update {
-        "Dependencies updated:"
-    } else {
-        "Outdated dependencies:"
-    };
-    renderer.render_deduped(&deduplicated, header);
+    // 6. Interactive mode: choose updates instead of showing the table
+    if args.interactive {
+        let picker = Picker::new(&checks, &PythonEcosystem);
+        if picker.is_empty() {
+            println!("All dependencies are up to date!");
+            return Ok(status);
+        }
+        let Some(selected) = picker.run()? else {
+            println!("No changes made.");
+            return Ok(status);
+        };
+        let result = FileUpdater::new().apply_updates(&selected, false, true)?;
+        if !result.modified_files.is_empty() {
+            println!("Updated {} file(s):", result.modified_files.len());
+            for file in &result.modified_files {
+                println!("  - {}", file.display());
+            }
+        }
+        result.print_summary();
+        return Ok(status);
+    }
+
+    // 7. Display results table
+    if human {
+        let renderer = TableRenderer::new(true);
+        let header = if args.update {
+            "Dependencies updated:"
+        } else {
+            "Outdated dependencies:"
+        };
+        renderer.render_deduped(&deduplicated, header);
+        renderer.render_too_new(&checks);
+        renderer.render_blocked(&checks);
+        renderer.render_summary(&checks);
+    }
 
-    // 7. If --update, apply updates based on severity filter
+    // 8. If --update, apply updates based on severity filter
     if args.update {
         let updater = FileUpdater::new();
         let result = updater.apply_updates(&checks, args.minor, args.force)?;
 
+        if !human {
+            report.add_checks(&checks, &PythonEcosystem);
+            report.add_not_found(&not_found);
+            report.add_formatted_errors(&fetch_errors);
+            report.uncached.clone_from(&uncached);
+            report.updated_files = result
+                .modified_files
+                .iter()
+                .map(|f| f.display().to_string())
+                .collect();
+            report.print(args.format)?;
+            return Ok(status);
+        }
+
         println!();
         if !result.modified_files.is_empty() {
             println!("Updated {} file(s):", result.modified_files.len());
@@ -342,6 +544,12 @@ async fn run_project_mode(args: &Args) -> Result<()> {
         }
 
         result.print_summary();
+    } else if !human {
+        report.add_checks(&checks, &PythonEcosystem);
+        report.add_not_found(&not_found);
+        report.add_formatted_errors(&fetch_errors);
+        report.uncached.clone_from(&uncached);
+        report.print(args.format)?;
     } else if !deduplicated.is_empty() {
         println!();
         println!(
@@ -352,5 +560,5 @@ async fn run_project_mode(args: &Args) -> Result<()> {
         );
     }
 
-    Ok(())
+    Ok(status)
 }
@@ -1,5 +1,6 @@
 use super::{Dependency, DependencyParser};
-use check_updates_core::VersionSpec;
+use crate::ecosystem::PythonEcosystem;
+use check_updates_core::{Ecosystem, VersionSpec};
 use anyhow::{Context, Result};
 use serde_yaml::Value;
 use std::fs;
@@ -40,7 +41,7 @@ impl CondaParser {
         if let Some(idx) = dep_str.find(">=") {
             let name = dep_str[..idx].trim().to_lowercase();
             let version_str = dep_str[idx + 2..].trim();
-            return match VersionSpec::parse(&format!(">={version_str}")) {
+            return match PythonEcosystem.parse_spec(&format!(">={version_str}")) {
                 Ok(spec) => Some((name, spec)),
                 Err(_) => Some((name, VersionSpec::Any)),
             };
@@ -49,7 +50,7 @@ impl CondaParser {
         if let Some(idx) = dep_str.find("<=") {
             let name = dep_str[..idx].trim().to_lowercase();
             let version_str = dep_str[idx + 2..].trim();
-            return match VersionSpec::parse(&format!("<={version_str}")) {
+            return match PythonEcosystem.parse_spec(&format!("<={version_str}")) {
                 Ok(spec) => Some((name, spec)),
                 Err(_) => Some((name, VersionSpec::Any)),
             };
@@ -58,7 +59,7 @@ impl CondaParser {
         if let Some(idx) = dep_str.find("!=") {
             let name = dep_str[..idx].trim().to_lowercase();
             let version_str = dep_str[idx + 2..].trim();
-            return match VersionSpec::parse(&format!("!={version_str}")) {
+            return match PythonEcosystem.parse_spec(&format!("!={version_str}")) {
                 Ok(spec) => Some((name, spec)),
                 Err(_) => Some((name, VersionSpec::Any)),
             };
@@ -67,7 +68,7 @@ impl CondaParser {
         if let Some(idx) = dep_str.find('>') {
             let name = dep_str[..idx].trim().to_lowercase();
             let version_str = dep_str[idx + 1..].trim();
-            return match VersionSpec::parse(&format!(">{version_str}")) {
+            return match PythonEcosystem.parse_spec(&format!(">{version_str}")) {
                 Ok(spec) => Some((name, spec)),
                 Err(_) => Some((name, VersionSpec::Any)),
             };
@@ -76,7 +77,7 @@ impl CondaParser {
         if let Some(idx) = dep_str.find('<') {
             let name = dep_str[..idx].trim().to_lowercase();
             let version_str = dep_str[idx + 1..].trim();
-            return match VersionSpec::parse(&format!("<{version_str}")) {
+            return match PythonEcosystem.parse_spec(&format!("<{version_str}")) {
                 Ok(spec) => Some((name, spec)),
                 Err(_) => Some((name, VersionSpec::Any)),
             };
@@ -87,7 +88,7 @@ impl CondaParser {
             let version_str = dep_str[idx + 1..].trim();
 
             // Conda uses = for pinning, convert to ==
-            return match VersionSpec::parse(&format!("=={version_str}")) {
+            return match PythonEcosystem.parse_spec(&format!("=={version_str}")) {
                 Ok(spec) => Some((name, spec)),
                 Err(_) => Some((name, VersionSpec::Any)),
             };
@@ -129,7 +130,7 @@ impl CondaParser {
             let name = dep_str[..pos].trim().to_lowercase();
             let version_str = dep_str[pos..].trim();
 
-            return match VersionSpec::parse(version_str) {
+            return match PythonEcosystem.parse_spec(version_str) {
                 Ok(spec) => Some((name, spec)),
                 Err(_) => Some((name, VersionSpec::Any)),
             };
@@ -164,6 +165,8 @@ impl DependencyParser for CondaParser {
                     if let Some((name, version_spec)) = Self::parse_conda_dependency(dep_str) {
                         dependencies.push(Dependency {
                             name,
+                            registry: None,
+                            package: None,
                             version_spec,
                             source_file: path.to_path_buf(),
                             line_number,
@@ -178,6 +181,8 @@ impl DependencyParser for CondaParser {
                                 && let Some((name, version_spec)) = Self::parse_pip_dependency(pip_dep_str) {
                                     dependencies.push(Dependency {
                                         name,
+                                        registry: None,
+                                        package: None,
                                         version_spec,
                                         source_file: path.to_path_buf(),
                                         line_number: line_number + pip_idx + 1, // Approximate line number
@@ -282,7 +287,7 @@ dependencies:
 "#;
 
         let mut temp_file = NamedTempFile::new().unwrap();
-        write!(temp_file, "{}", yaml_content).unwrap();
+        write!(temp_file, "{yaml_content}").unwrap();
         let path = temp_file.path().to_path_buf();
 
         let parser = CondaParser::new();
@@ -323,7 +328,7 @@ dependencies:
 "#;
 
         let mut temp_file = NamedTempFile::new().unwrap();
-        write!(temp_file, "{}", yaml_content).unwrap();
+        write!(temp_file, "{yaml_content}").unwrap();
 
         // Rename to .yaml extension
         let temp_path = temp_file.path().to_path_buf();
@@ -349,7 +354,7 @@ dependencies: []
 "#;
 
         let mut temp_file = NamedTempFile::new().unwrap();
-        write!(temp_file, "{}", yaml_content).unwrap();
+        write!(temp_file, "{yaml_content}").unwrap();
         let path = temp_file.path().to_path_buf();
 
         let parser = CondaParser::new();
@@ -1,5 +1,6 @@
 use super::{Dependency, DependencyParser};
-use check_updates_core::VersionSpec;
+use crate::ecosystem::PythonEcosystem;
+use check_updates_core::{Ecosystem, VersionSpec};
 use anyhow::{Context, Result};
 use std::fs;
 use std::path::Path;
@@ -19,6 +20,19 @@ impl PyProjectParser {
         Self
     }
 
+    /// The Python versions a project supports (`[project] requires-python`)
+    pub fn requires_python(&self, path: &Path) -> Result<Option<String>> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read file: {}", path.display()))?;
+        let toml_value: Value = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse TOML: {}", path.display()))?;
+        Ok(toml_value
+            .get("project")
+            .and_then(|p| p.get("requires-python"))
+            .and_then(Value::as_str)
+            .map(String::from))
+    }
+
     /// Parse PEP 621 format dependencies
     fn parse_pep621_dependencies(
         &self,
@@ -232,10 +246,12 @@ impl PyProjectParser {
         let (line_number, original_line) = self.find_line_in_content(content, name, &version_str);
 
         // Parse the version spec
-        let version_spec = VersionSpec::parse(&version_str).ok()?;
+        let version_spec = PythonEcosystem.parse_spec(&version_str).ok()?;
 
         Some(Dependency {
             name: name.to_lowercase().replace('_', "-"),
+            registry: None,
+            package: None,
             version_spec,
             source_file: path.to_path_buf(),
             line_number,
@@ -272,13 +288,15 @@ impl PyProjectParser {
                 let version_part = dep_str_no_extras[idx..].trim();
 
                 // Parse version spec
-                let version_spec = VersionSpec::parse(version_part).ok()?;
+                let version_spec = PythonEcosystem.parse_spec(version_part).ok()?;
 
                 // Find line number and original line
                 let (line_number, original_line) = self.find_line_in_content(content, pkg_name, version_part);
 
                 return Some(Dependency {
                     name: pkg_name.to_lowercase().replace('_', "-"),
+                    registry: None,
+                    package: None,
                     version_spec,
                     source_file: path.to_path_buf(),
                     line_number,
@@ -294,6 +312,8 @@ impl PyProjectParser {
 
             return Some(Dependency {
                 name: pkg_name.to_lowercase().replace('_', "-"),
+                registry: None,
+                package: None,
                 version_spec: VersionSpec::Any,
                 source_file: path.to_path_buf(),
                 line_number,
@@ -403,6 +423,7 @@ mod tests {
         let content = r#"
 [project]
 name = "myproject"
+requires-python = ">=3.9"
 dependencies = [
     "requests>=2.28.0",
     "numpy==1.24.0",
@@ -429,6 +450,7 @@ dev = [
         assert!(deps.iter().any(|d| d.name == "flask"));
         assert!(deps.iter().any(|d| d.name == "pytest"));
         assert!(deps.iter().any(|d| d.name == "black"));
+        assert_eq!(parser.requires_python(&path).unwrap().as_deref(), Some(">=3.9"));
     }
 
     #[test]
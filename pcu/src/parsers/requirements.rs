@@ -1,5 +1,6 @@
 use super::{Dependency, DependencyParser};
-use check_updates_core::VersionSpec;
+use crate::ecosystem::PythonEcosystem;
+use check_updates_core::{Ecosystem, VersionSpec};
 use anyhow::{Context, Result};
 use std::fs;
 use std::path::Path;
@@ -7,6 +8,15 @@ use std::path::Path;
 /// Parser for requirements.txt files
 pub struct RequirementsParser;
 
+/// The index options of a requirements file, which `parse_line` skips
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexOptions {
+    /// `--index-url` / `-i`: replaces PyPI
+    pub index_url: Option<String>,
+    /// `--extra-index-url`: searched as well
+    pub extra_index_urls: Vec<String>,
+}
+
 impl Default for RequirementsParser {
     fn default() -> Self {
         Self::new()
@@ -80,7 +90,7 @@ impl RequirementsParser {
         let version_spec = if version_str.is_empty() {
             VersionSpec::Any
         } else {
-            match VersionSpec::parse(version_str) {
+            match PythonEcosystem.parse_spec(version_str) {
                 Ok(spec) => spec,
                 Err(_) => {
                     // If parsing fails, store as complex constraint
@@ -91,6 +101,8 @@ impl RequirementsParser {
 
         Some(Dependency {
             name: normalized_name,
+            registry: None,
+            package: None,
             version_spec,
             source_file: source_file.to_path_buf(),
             line_number,
@@ -98,6 +110,29 @@ impl RequirementsParser {
         })
     }
 
+    /// Read `--index-url` (`-i`) and `--extra-index-url` options, in either
+    /// the `--option URL` or `--option=URL` form
+    pub fn parse_index_options(content: &str) -> IndexOptions {
+        let mut options = IndexOptions::default();
+        for line in content.lines() {
+            let Some((option, value)) = line
+                .trim()
+                .split_once(|c: char| c == '=' || c.is_whitespace())
+            else {
+                continue;
+            };
+            let Some(url) = value.split_whitespace().next() else {
+                continue;
+            };
+            match option {
+                "-i" | "--index-url" => options.index_url = Some(url.to_string()),
+                "--extra-index-url" => options.extra_index_urls.push(url.to_string()),
+                _ => {}
+            }
+        }
+        options
+    }
+
     /// Split a package specification into name (with extras) and version
     /// Returns (package_with_extras, version_spec)
     fn split_package_version(spec: &str) -> Option<(&str, &str)> {
@@ -173,7 +208,7 @@ mod tests {
         writeln!(file, "flask").unwrap();
 
         let parser = RequirementsParser::new();
-        let deps = parser.parse(&file.path().to_path_buf()).unwrap();
+        let deps = parser.parse(file.path()).unwrap();
 
         assert_eq!(deps.len(), 3);
         assert_eq!(deps[0].name, "requests");
@@ -191,7 +226,7 @@ mod tests {
         writeln!(file, "celery[redis,msgpack]==5.2.0").unwrap();
 
         let parser = RequirementsParser::new();
-        let deps = parser.parse(&file.path().to_path_buf()).unwrap();
+        let deps = parser.parse(file.path()).unwrap();
 
         assert_eq!(deps.len(), 2);
         assert_eq!(deps[0].name, "requests");
@@ -203,11 +238,11 @@ mod tests {
         let mut file = NamedTempFile::new().unwrap();
         writeln!(file, "# This is a comment").unwrap();
         writeln!(file, "requests==2.28.0  # inline comment").unwrap();
-        writeln!(file, "").unwrap();
+        writeln!(file).unwrap();
         writeln!(file, "numpy>=1.24.0").unwrap();
 
         let parser = RequirementsParser::new();
-        let deps = parser.parse(&file.path().to_path_buf()).unwrap();
+        let deps = parser.parse(file.path()).unwrap();
 
         assert_eq!(deps.len(), 2);
         assert_eq!(deps[0].name, "requests");
@@ -221,7 +256,7 @@ mod tests {
         writeln!(file, "typing-extensions>=3.7; python_version >= '3.8'").unwrap();
 
         let parser = RequirementsParser::new();
-        let deps = parser.parse(&file.path().to_path_buf()).unwrap();
+        let deps = parser.parse(file.path()).unwrap();
 
         assert_eq!(deps.len(), 2);
         assert_eq!(deps[0].name, "dataclasses");
@@ -236,12 +271,23 @@ mod tests {
         writeln!(file, "requests==2.28.0").unwrap();
 
         let parser = RequirementsParser::new();
-        let deps = parser.parse(&file.path().to_path_buf()).unwrap();
+        let deps = parser.parse(file.path()).unwrap();
 
         assert_eq!(deps.len(), 1);
         assert_eq!(deps[0].name, "requests");
     }
 
+    #[test]
+    fn test_parse_index_options() {
+        let options = RequirementsParser::parse_index_options(
+            "-i https://pkgs.acme.dev/simple\n\
+             --extra-index-url=https://download.pytorch.org/whl/cpu  # torch\n\
+             requests==2.28.0\n",
+        );
+        assert_eq!(options.index_url.as_deref(), Some("https://pkgs.acme.dev/simple"));
+        assert_eq!(options.extra_index_urls, ["https://download.pytorch.org/whl/cpu"]);
+    }
+
     #[test]
     fn test_parse_complex_version_specs() {
         let mut file = NamedTempFile::new().unwrap();
@@ -250,7 +296,7 @@ mod tests {
         writeln!(file, "click!=8.0.0").unwrap();
 
         let parser = RequirementsParser::new();
-        let deps = parser.parse(&file.path().to_path_buf()).unwrap();
+        let deps = parser.parse(file.path()).unwrap();
 
         assert_eq!(deps.len(), 3);
         assert_eq!(deps[0].name, "django");
@@ -264,11 +310,11 @@ mod tests {
         let mut file = NamedTempFile::new().unwrap();
         writeln!(file, "# Comment line").unwrap();
         writeln!(file, "requests==2.28.0").unwrap();
-        writeln!(file, "").unwrap();
+        writeln!(file).unwrap();
         writeln!(file, "numpy>=1.24.0").unwrap();
 
         let parser = RequirementsParser::new();
-        let deps = parser.parse(&file.path().to_path_buf()).unwrap();
+        let deps = parser.parse(file.path()).unwrap();
 
         assert_eq!(deps.len(), 2);
         assert_eq!(deps[0].line_number, 2);
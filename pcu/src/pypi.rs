@@ -1,15 +1,20 @@
+use check_updates_core::cache::HttpCache;
+use check_updates_core::date::Timestamp;
+use check_updates_core::http::{HttpClient, HttpResponse};
+use check_updates_core::registry::{NotFound, RegistryClient, RegistryError};
 use check_updates_core::{PackageInfo, Version};
-use anyhow::{anyhow, Context, Result};
+use crate::indexes::{normalize, IndexSet, PackageIndex};
+use crate::simple;
+use anyhow::{anyhow, bail, Context, Result};
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
 use std::sync::Arc;
-use tokio::sync::Semaphore;
 
-/// Client for querying PyPI API
+/// Client for querying PyPI API, or the project's own indexes
 pub struct PyPiClient {
-    client: reqwest::Client,
-    base_url: String,
+    http: HttpClient,
+    indexes: Arc<IndexSet>,
     include_prerelease: bool,
 }
 
@@ -27,58 +32,161 @@ struct PyPiInfo {
 
 #[derive(Debug, Deserialize)]
 struct PyPiRelease {
-    #[allow(dead_code)]
     yanked: Option<bool>,
+    upload_time_iso_8601: Option<String>,
+    requires_python: Option<String>,
 }
 
 impl PyPiClient {
     pub fn new(include_prerelease: bool) -> Self {
         Self {
-            client: reqwest::Client::builder()
-                .user_agent("python-check-updates/0.1.0")
-                .timeout(std::time::Duration::from_secs(30))
-                .build()
-                .unwrap_or_else(|_| reqwest::Client::new()),
-            base_url: "https://pypi.org/pypi".to_string(),
+            http: HttpClient::new(
+                reqwest::Client::builder()
+                    .user_agent("python-check-updates/0.1.0")
+                    .timeout(std::time::Duration::from_secs(30))
+                    .build()
+                    .unwrap_or_else(|_| reqwest::Client::new()),
+            ),
+            indexes: Arc::default(),
             include_prerelease,
         }
     }
 
+    /// Query `url` in place of PyPI (the default index)
     pub fn with_index_url(mut self, url: &str) -> Self {
-        // Remove trailing slash if present
-        self.base_url = url.trim_end_matches('/').to_string();
+        let index = PackageIndex::new(Some("pypi"), url, &|_| None);
+        self.indexes = Arc::new(self.indexes.as_ref().clone().with_default(index));
         self
     }
 
-    /// Fetch package info from PyPI
-    pub async fn get_package(&self, name: &str) -> Result<PackageInfo> {
-        let url = format!("{}/{}/json", self.base_url, name);
+    /// Look packages up on the project's indexes
+    pub fn with_indexes(mut self, indexes: IndexSet) -> Self {
+        self.indexes = Arc::new(indexes);
+        self
+    }
+
+    /// Serve responses from (and store them in) an on-disk cache
+    pub fn with_cache(mut self, cache: Option<HttpCache>) -> Self {
+        self.http = self.http.with_cache(cache);
+        self
+    }
+
+    /// Fetch package info from the first index that has the package
+    async fn fetch_package(&self, name: &str) -> Result<PackageInfo> {
+        let indexes = self.indexes.candidates(name).map_err(|e| anyhow!(e))?;
+        for index in &indexes {
+            if let Some(info) = self.fetch_from(index, name).await? {
+                return Ok(info);
+            }
+        }
+        let searched: Vec<&str> = indexes.iter().map(|i| i.display_name()).collect();
+        Err(NotFound(format!("Package '{name}' not found on {}", searched.join(" or "))).into())
+    }
 
+    /// Fetch package info from one index, `None` if it doesn't have the package
+    ///
+    /// A `.../pypi` URL is read through the JSON API, falling back to the
+    /// Simple API next to it when that doesn't answer; any other URL is a
+    /// Simple API index.
+    async fn fetch_from(&self, index: &PackageIndex, name: &str) -> Result<Option<PackageInfo>> {
+        if index.is_json_api() {
+            match self.fetch_json(index, name).await {
+                Ok(Some(info)) => return Ok(Some(info)),
+                Ok(None) => {}
+                Err(e) if e.is::<serde_json::Error>() => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.fetch_simple(index, name).await
+    }
+
+    /// Fetch `<index>/<name>/json` from a JSON API
+    async fn fetch_json(&self, index: &PackageIndex, name: &str) -> Result<Option<PackageInfo>> {
+        let url = format!("{}/{}/json", index.url, name);
+        let Some(response) = self.get(index, &url, None, name).await? else {
+            return Ok(None);
+        };
+
+        let pypi_data: PyPiResponse = serde_json::from_str(&response.body)
+            .context(format!("Failed to parse JSON response for '{name}'"))?;
+        self.package_info(pypi_data.info.name, &pypi_data.releases, response.cached_at)
+            .map(Some)
+    }
+
+    /// Fetch `<index>/<normalized name>/` from a Simple API, taking versions
+    /// from the names of the files listed
+    async fn fetch_simple(&self, index: &PackageIndex, name: &str) -> Result<Option<PackageInfo>> {
+        let url = format!("{}/{}/", index.simple_url(), normalize(name));
+        let Some(response) = self.get(index, &url, Some(simple::ACCEPT), name).await? else {
+            return Ok(None);
+        };
+
+        let files = simple::parse_project_page(&response.body)
+            .context(format!("Failed to parse index page for '{name}'"))?;
+        let mut releases: HashMap<String, Vec<PyPiRelease>> = HashMap::new();
+        for file in files {
+            if let Some(version) = simple::version_from_filename(&file.filename, name) {
+                releases.entry(version.to_string()).or_default().push(PyPiRelease {
+                    yanked: Some(file.yanked),
+                    upload_time_iso_8601: file.upload_time,
+                    requires_python: file.requires_python,
+                });
+            }
+        }
+        self.package_info(name.to_string(), &releases, response.cached_at)
+            .map(Some)
+    }
+
+    /// GET from an index, `None` for a `404`
+    async fn get(
+        &self,
+        index: &PackageIndex,
+        url: &str,
+        accept: Option<&str>,
+        name: &str,
+    ) -> Result<Option<HttpResponse>> {
         let response = self
-            .client
-            .get(&url)
-            .send()
+            .http
+            .get_authorized(url, accept, index.auth.as_ref())
             .await
             .context(format!("Failed to fetch package '{name}'"))?;
 
-        if !response.status().is_success() {
-            if response.status() == 404 {
-                return Err(anyhow!("Package '{name}' not found on PyPI"));
+        if !response.is_success() {
+            match response.status.as_u16() {
+                404 => return Ok(None),
+                401 | 403 => bail!(
+                    "{} refused access to '{name}' ({}; {})",
+                    index.display_name(),
+                    response.status,
+                    if index.auth.is_some() {
+                        "check its credentials"
+                    } else {
+                        "no credentials configured for it"
+                    }
+                ),
+                _ => {}
             }
             return Err(anyhow!(
-                "PyPI API request failed with status: {}",
-                response.status()
+                "{} API request failed with status: {}",
+                index.display_name(),
+                response.status
             ));
         }
+        Ok(Some(response))
+    }
 
-        let pypi_data: PyPiResponse = response
-            .json()
-            .await
-            .context(format!("Failed to parse JSON response for '{name}'"))?;
-
+    /// Collect a package's releases (version to files) into `PackageInfo`
+    fn package_info(
+        &self,
+        name: String,
+        releases: &HashMap<String, Vec<PyPiRelease>>,
+        cached_at: Option<Timestamp>,
+    ) -> Result<PackageInfo> {
         // Parse all versions from releases
         let mut all_versions: Vec<Version> = Vec::new();
-        for (version_str, releases) in &pypi_data.releases {
+        let mut published = BTreeMap::new();
+        let mut requires = BTreeMap::new();
+        for (version_str, releases) in releases {
             // Skip yanked releases (empty release list or all yanked)
             if releases.is_empty() {
                 continue;
@@ -92,6 +200,22 @@ impl PyPiClient {
 
             // Try to parse the version
             if let Ok(version) = Version::from_str(version_str) {
+                // A release is published when its first file is uploaded
+                let uploaded = releases
+                    .iter()
+                    .filter_map(|r| r.upload_time_iso_8601.as_deref().and_then(Timestamp::parse))
+                    .min();
+                if let Some(uploaded) = uploaded {
+                    published.insert(version.clone(), uploaded);
+                }
+                let requires_python = releases
+                    .iter()
+                    .filter(|r| !r.yanked.unwrap_or(false))
+                    .filter_map(|r| r.requires_python.as_deref())
+                    .find(|r| !r.trim().is_empty());
+                if let Some(requires_python) = requires_python {
+                    requires.insert(version.clone(), requires_python.trim().to_string());
+                }
                 all_versions.push(version);
             }
         }
@@ -140,103 +264,32 @@ impl PyPiClient {
             .cloned();
 
         Ok(PackageInfo {
-            name: pypi_data.info.name,
+            name,
             versions: filtered_versions,
             latest,
             latest_stable,
+            published,
+            requires,
+            cached_at,
         })
     }
-
-    /// Fetch multiple packages concurrently
-    pub async fn get_packages(
-        &self,
-        names: &[String],
-        progress_callback: impl Fn(usize, usize) + Send + Sync + 'static,
-    ) -> Result<GetPackagesResult> {
-        let total = names.len();
-        let progress_callback = Arc::new(progress_callback);
-
-        // Limit concurrent requests to avoid overwhelming the server
-        let semaphore = Arc::new(Semaphore::new(10));
-
-        let mut tasks = Vec::new();
-
-        for (index, name) in names.iter().enumerate() {
-            let client = self.clone();
-            let name = name.clone();
-            let callback = Arc::clone(&progress_callback);
-            let semaphore = Arc::clone(&semaphore);
-
-            let task = tokio::spawn(async move {
-                // Acquire semaphore permit
-                let _permit = semaphore.acquire().await.expect("semaphore closed");
-
-                let result = client.get_package(&name).await;
-
-                // Call progress callback
-                callback(index + 1, total);
-
-                (name, result)
-            });
-
-            tasks.push(task);
-        }
-
-        // Wait for all tasks to complete
-        let mut packages = HashMap::new();
-        let mut errors = Vec::new();
-
-        for task in tasks {
-            match task.await {
-                Ok((name, Ok(package_info))) => {
-                    packages.insert(name, package_info);
-                }
-                Ok((name, Err(e))) => {
-                    // Extract just the error message without "Failed to fetch" prefix
-                    let error_msg = e.to_string();
-                    errors.push((name, error_msg));
-                }
-                Err(e) => {
-                    errors.push(("unknown".to_string(), format!("Task failed: {e}")));
-                }
-            }
-        }
-
-        // Format errors as strings
-        let formatted_errors: Vec<String> = errors
-            .into_iter()
-            .map(|(name, msg)| format!("{name}: {msg}"))
-            .collect();
-
-        // If we have some results, return them even if some packages failed
-        if !packages.is_empty() || formatted_errors.is_empty() {
-            Ok(GetPackagesResult {
-                packages,
-                errors: formatted_errors,
-            })
-        } else {
-            // All packages failed
-            Err(anyhow!(
-                "Failed to fetch all packages:\n{}",
-                formatted_errors.join("\n")
-            ))
-        }
-    }
 }
 
-/// Result of fetching multiple packages
-#[derive(Debug, Clone)]
-pub struct GetPackagesResult {
-    pub packages: HashMap<String, PackageInfo>,
-    pub errors: Vec<String>,
+impl RegistryClient for PyPiClient {
+    async fn get_package(&self, name: &str) -> Result<PackageInfo, RegistryError> {
+        // Unwrap `NotFound` so the caller can tell it apart from other failures
+        self.fetch_package(name)
+            .await
+            .map_err(|e| e.downcast::<NotFound>().map_or_else(RegistryError::new, RegistryError::new))
+    }
 }
 
 // Implement Clone for PyPiClient to support concurrent usage
 impl Clone for PyPiClient {
     fn clone(&self) -> Self {
         Self {
-            client: self.client.clone(),
-            base_url: self.base_url.clone(),
+            http: self.http.clone(),
+            indexes: Arc::clone(&self.indexes),
             include_prerelease: self.include_prerelease,
         }
     }
@@ -245,6 +298,7 @@ impl Clone for PyPiClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Arc;
 
     #[tokio::test]
     async fn test_get_package_requests() {
@@ -280,13 +334,11 @@ mod tests {
         let progress_calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
         let progress_calls_clone = Arc::clone(&progress_calls);
 
-        let result = client.get_packages(&packages, move |_current, _total| {
+        let results = client.get_packages(&packages, move |_current, _total| {
             progress_calls_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
         }).await;
 
-        assert!(result.is_ok(), "Failed to fetch packages: {:?}", result.err());
-
-        let results = result.unwrap();
+        assert!(results.errors.is_empty(), "Failed to fetch packages: {:?}", results.errors);
         assert!(!results.packages.is_empty());
 
         // Verify progress callback was called
@@ -299,7 +351,10 @@ mod tests {
         let client = PyPiClient::new(false)
             .with_index_url("https://pypi.org/pypi/");
 
-        assert_eq!(client.base_url, "https://pypi.org/pypi");
+        let index = client.indexes.default_index().unwrap();
+        assert_eq!(index.url, "https://pypi.org/pypi");
+        assert!(index.is_json_api());
+        assert_eq!(index.simple_url(), "https://pypi.org/simple");
     }
 
     #[tokio::test]
@@ -312,12 +367,66 @@ mod tests {
         let result_stable = client_stable.get_package("django").await;
         let result_pre = client_pre.get_package("django").await;
 
-        if result_stable.is_ok() && result_pre.is_ok() {
-            let stable = result_stable.unwrap();
-            let pre = result_pre.unwrap();
+        if let (Ok(stable), Ok(pre)) = (result_stable, result_pre) {
 
             // Pre-release client might have more versions
             assert!(pre.versions.len() >= stable.versions.len());
         }
     }
+
+    #[tokio::test]
+    async fn test_not_found_vs_throttled() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/gone/"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(path("/busy/"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "3600"))
+            .mount(&server)
+            .await;
+
+        let client = PyPiClient::new(false).with_index_url(&server.uri());
+        let result = client
+            .get_packages(&["gone".to_string(), "busy".to_string()], |_, _| {})
+            .await;
+
+        assert_eq!(result.not_found, ["gone: Package 'gone' not found on PyPI"]);
+        assert_eq!(
+            result.errors,
+            ["busy: Failed to fetch package 'busy': rate limited by the registry (retry after 3600s)"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_simple_api_fallback() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/pypi/Holy.Grail/json"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(path("/simple/holy-grail/"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<a href="holy_grail-1.0.tar.gz" data-requires-python="&gt;=3.8">holy_grail-1.0.tar.gz</a>
+                   <a href="holy_grail-1.1.tar.gz" data-yanked="broken">holy_grail-1.1.tar.gz</a>
+                   <a href="holy_grail-2.0b1.tar.gz">holy_grail-2.0b1.tar.gz</a>"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let client = PyPiClient::new(false).with_index_url(&format!("{}/pypi", server.uri()));
+        let info = client.get_package("Holy.Grail").await.unwrap();
+        assert_eq!(info.latest.to_string(), "1.0");
+        assert_eq!(info.versions.len(), 1);
+        assert_eq!(
+            info.requires.get(&info.latest).map(String::as_str),
+            Some(">=3.8")
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use check_updates_core::Version;
+use check_updates_core::{Version, VersionSpec};
 use std::collections::HashMap;
 use std::process::Command;
 use std::str::FromStr;
@@ -59,7 +59,7 @@ pub fn fetch_latest_python_version(current: &Version) -> Option<Version> {
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    let current_series = format!("{}.{}", current.major, current.minor);
+    let current_series = format!("{}.{}", current.major(), current.minor());
 
     // Find the highest version in the same series from uv's available list
     let mut best: Option<Version> = None;
@@ -83,12 +83,11 @@ pub fn fetch_latest_python_version(current: &Version) -> Option<Version> {
         }
         let version_str = name_parts[1];
         if let Ok(version) = Version::from_str(version_str) {
-            let series = format!("{}.{}", version.major, version.minor);
-            if series == current_series {
-                if best.as_ref().is_none_or(|b| version > *b) {
+            let series = format!("{}.{}", version.major(), version.minor());
+            if series == current_series
+                && best.as_ref().is_none_or(|b| version > *b) {
                     best = Some(version);
                 }
-            }
         }
     }
 
@@ -124,7 +123,7 @@ pub fn fetch_all_latest_python_versions() -> HashMap<String, Version> {
         }
         let version_str = name_parts[1];
         if let Ok(version) = Version::from_str(version_str) {
-            let series = format!("{}.{}", version.major, version.minor);
+            let series = format!("{}.{}", version.major(), version.minor());
             let entry = versions.entry(series).or_insert_with(|| version.clone());
             if version > *entry {
                 *entry = version;
@@ -135,6 +134,59 @@ pub fn fetch_all_latest_python_versions() -> HashMap<String, Version> {
     versions
 }
 
+/// A `Requires-Python` specifier set, e.g. `>=3.8, !=3.9.*, <4`
+#[derive(Debug, Clone)]
+pub struct RequiresPython {
+    /// Each clause, and whether it's negated (`!=3.9.*`)
+    clauses: Vec<(VersionSpec, bool)>,
+}
+
+impl RequiresPython {
+    /// Parse a specifier set; `None` if any clause isn't understood
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut clauses = Vec::new();
+        for clause in s.split(',') {
+            let clause: String = clause.split_whitespace().collect();
+            if clause.is_empty() {
+                continue;
+            }
+            let (spec, negated) = match clause.strip_prefix("!=") {
+                Some(rest) if rest.ends_with('*') => (VersionSpec::parse(&format!("=={rest}")), true),
+                _ => (VersionSpec::parse(&clause), false),
+            };
+            match spec.ok()? {
+                VersionSpec::Complex(_) => return None,
+                spec => clauses.push((spec, negated)),
+            }
+        }
+        Some(Self { clauses })
+    }
+
+    /// Whether `python` meets every clause
+    pub fn contains(&self, python: &Version) -> bool {
+        self.clauses
+            .iter()
+            .all(|(spec, negated)| spec.satisfies(python) != *negated)
+    }
+
+    /// Oldest Python the set admits, from its lower bounds (`>=3.9`,
+    /// `~=3.10`, `==3.11.*`); `None` if it has none
+    pub fn lowest(&self) -> Option<Version> {
+        self.clauses
+            .iter()
+            .filter(|(_, negated)| !negated)
+            .filter_map(|(spec, _)| match spec {
+                VersionSpec::Minimum(v)
+                | VersionSpec::Compatible(v)
+                | VersionSpec::Pinned(v)
+                | VersionSpec::Range { min: v, .. } => Some(v.clone()),
+                VersionSpec::Wildcard { prefix, .. } => Version::from_str(prefix).ok(),
+                _ => None,
+            })
+            .max()
+    }
+}
+
 /// Get Python info (current version and optionally latest available)
 pub fn get_python_info(check_latest: bool) -> Option<PythonInfo> {
     let current = detect_python_version()?;
@@ -158,7 +210,7 @@ mod tests {
         let version = detect_python_version();
         // We just check it returns something reasonable
         if let Some(v) = version {
-            assert!(v.major >= 2);
+            assert!(v.major() >= 2);
         }
     }
 
@@ -182,4 +234,23 @@ mod tests {
         };
         assert!(!info.has_update());
     }
+
+    #[test]
+    fn test_requires_python() {
+        let version = |v: &str| Version::from_str(v).unwrap();
+
+        let spec = RequiresPython::parse(">=2.7, !=3.0.*, !=3.1.*, <4").unwrap();
+        assert!(spec.contains(&version("2.7.18")));
+        assert!(!spec.contains(&version("3.1.4")));
+        assert!(spec.contains(&version("3.12.1")));
+        assert!(!spec.contains(&version("4.0")));
+        assert_eq!(spec.lowest(), Some(version("2.7")));
+
+        let spec = RequiresPython::parse(">= 3.9").unwrap();
+        assert!(!spec.contains(&version("3.8.10")));
+        assert_eq!(RequiresPython::parse("~=3.10").unwrap().lowest(), Some(version("3.10")));
+        assert_eq!(RequiresPython::parse("==3.11.*").unwrap().lowest(), Some(version("3.11")));
+        assert_eq!(RequiresPython::parse("<4").unwrap().lowest(), None);
+        assert!(RequiresPython::parse(">=3.8 || <2").is_none());
+    }
 }
@@ -0,0 +1,315 @@
+use crate::indexes::normalize;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// What to ask an index for: JSON if it has it, HTML otherwise
+pub const ACCEPT: &str = "application/vnd.pypi.simple.v1+json, \
+                          application/vnd.pypi.simple.v1+html;q=0.2, text/html;q=0.01";
+
+/// A distribution file listed on a project page
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectFile {
+    pub filename: String,
+    /// `Requires-Python` of the file, e.g. `>=3.9`
+    pub requires_python: Option<String>,
+    pub yanked: bool,
+    /// When the file was uploaded (PEP 700, JSON only)
+    pub upload_time: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonPage {
+    files: Vec<JsonFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonFile {
+    filename: String,
+    #[serde(rename = "requires-python")]
+    requires_python: Option<String>,
+    /// `true`, or the reason the file was yanked
+    yanked: Option<Value>,
+    #[serde(rename = "upload-time")]
+    upload_time: Option<String>,
+}
+
+/// The files on a Simple API project page: PEP 691 JSON, or the PEP 503
+/// HTML page indexes that predate it serve
+pub fn parse_project_page(body: &str) -> Result<Vec<ProjectFile>> {
+    if !body.trim_start().starts_with('{') {
+        return Ok(parse_html(body));
+    }
+    let page: JsonPage = serde_json::from_str(body).context("Invalid Simple API JSON")?;
+    Ok(page
+        .files
+        .into_iter()
+        .map(|f| ProjectFile {
+            filename: f.filename,
+            requires_python: f.requires_python.filter(|r| !r.trim().is_empty()),
+            yanked: matches!(f.yanked, Some(Value::Bool(true) | Value::String(_))),
+            upload_time: f.upload_time,
+        })
+        .collect())
+}
+
+/// The anchors of a PEP 503 page
+fn parse_html(body: &str) -> Vec<ProjectFile> {
+    // Tag and attribute names are ASCII, so byte offsets into the lowercased
+    // copy are valid in the original
+    let lower = body.to_ascii_lowercase();
+    let mut files = Vec::new();
+    let mut pos = 0;
+    while let Some(start) = lower[pos..].find("<a").map(|i| pos + i) {
+        pos = start + 2;
+        if !lower[pos..].starts_with(|c: char| c.is_ascii_whitespace() || c == '>') {
+            continue;
+        }
+        let Some(tag_end) = lower[pos..].find('>').map(|i| pos + i) else {
+            break;
+        };
+        let attributes = parse_attributes(&body[pos..tag_end]);
+        let text_end = lower[tag_end..]
+            .find("</a")
+            .map_or(body.len(), |i| tag_end + i);
+        pos = text_end;
+
+        let attribute = |name: &str| {
+            attributes
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, v)| v.as_str())
+        };
+        let text = unescape(body[tag_end + 1..text_end].trim());
+        let filename = if text.is_empty() {
+            let href = attribute("href").unwrap_or_default();
+            let path = href.split(['#', '?']).next().unwrap_or_default();
+            path.rsplit('/').next().unwrap_or_default().to_string()
+        } else {
+            text
+        };
+        if filename.is_empty() {
+            continue;
+        }
+        files.push(ProjectFile {
+            filename,
+            requires_python: attribute("data-requires-python")
+                .filter(|r| !r.trim().is_empty())
+                .map(str::to_string),
+            yanked: attribute("data-yanked").is_some(),
+            upload_time: None,
+        });
+    }
+    files
+}
+
+/// `name="value"` pairs of a tag, names lowercased and values unescaped
+fn parse_attributes(tag: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut rest = tag.trim_start();
+    while !rest.is_empty() {
+        let name_end = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let mut value = String::new();
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            let (raw, remainder) = match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &after[1..];
+                    let end = inner.find(quote).unwrap_or(inner.len());
+                    (&inner[..end], inner.get(end + 1..).unwrap_or_default())
+                }
+                _ => {
+                    let end = after
+                        .find(|c: char| c.is_ascii_whitespace())
+                        .unwrap_or(after.len());
+                    (&after[..end], &after[end..])
+                }
+            };
+            value = unescape(raw);
+            rest = remainder;
+        } else if name.is_empty() {
+            // A stray `/` or other junk
+            rest = &rest[1.min(rest.len())..];
+        }
+        if !name.is_empty() {
+            attributes.push((name, value));
+        }
+        rest = rest.trim_start();
+    }
+    attributes
+}
+
+/// Decode the character references an index is likely to use
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(str::parse))
+                    .and_then(Result::ok)
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Version of `project` a distribution file holds, from its name
+///
+/// Wheels and eggs put the version second (`name-1.0-py3-none-any.whl`);
+/// source distributions end with it, after a name that may itself contain
+/// dashes (`python-dateutil-2.9.0.tar.gz`). Other files (`.exe`, `.rpm`)
+/// are ignored.
+pub fn version_from_filename<'a>(filename: &'a str, project: &str) -> Option<&'a str> {
+    if let Some(stem) = filename
+        .strip_suffix(".whl")
+        .or_else(|| filename.strip_suffix(".egg"))
+    {
+        return stem.split('-').nth(1).filter(|v| !v.is_empty());
+    }
+
+    let lower = filename.to_ascii_lowercase();
+    let stem = [".tar.gz", ".tar.bz2", ".tar.xz", ".tgz", ".tar", ".zip"]
+        .iter()
+        .find_map(|ext| lower.strip_suffix(ext).map(|s| &filename[..s.len()]))?;
+    let project = normalize(project);
+    stem.match_indices('-')
+        .find(|(i, _)| normalize(&stem[..*i]) == project)
+        .map(|(i, _)| &stem[i + 1..])
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_page() {
+        let files = parse_project_page(
+            r#"{
+                "meta": {"api-version": "1.1"},
+                "name": "holygrail",
+                "files": [
+                    {"filename": "holygrail-1.0.tar.gz", "url": "https://files.example.com/holygrail-1.0.tar.gz",
+                     "hashes": {}, "requires-python": ">=3.8", "upload-time": "2024-01-02T03:04:05.000000Z"},
+                    {"filename": "holygrail-1.1-py3-none-any.whl", "url": "x", "hashes": {},
+                     "requires-python": "", "yanked": "broken metadata"},
+                    {"filename": "holygrail-1.2.zip", "url": "x", "hashes": {}, "yanked": false}
+                ],
+                "versions": ["1.0", "1.1", "1.2"]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].requires_python.as_deref(), Some(">=3.8"));
+        assert_eq!(
+            files[0].upload_time.as_deref(),
+            Some("2024-01-02T03:04:05.000000Z")
+        );
+        assert!(!files[0].yanked);
+        assert!(files[1].yanked);
+        assert_eq!(files[1].requires_python, None);
+        assert!(!files[2].yanked);
+
+        assert!(parse_project_page(r#"{"files": 3}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_html_page() {
+        let files = parse_project_page(
+            r#"<!DOCTYPE html>
+            <html><body>
+            <h1>Links for holygrail</h1>
+            <a href="../../files/holygrail-1.0.tar.gz#sha256=abc" data-requires-python="&gt;=3.8,&lt;4">holygrail-1.0.tar.gz</a><br/>
+            <A HREF='/files/holygrail-1.1-py3-none-any.whl' data-yanked>holygrail-1.1-py3-none-any.whl</A><br/>
+            <a data-yanked="" href="/files/holygrail-1.2.tar.gz"></a>
+            <abbr>not a link</abbr>
+            </body></html>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            files,
+            [
+                ProjectFile {
+                    filename: "holygrail-1.0.tar.gz".to_string(),
+                    requires_python: Some(">=3.8,<4".to_string()),
+                    ..ProjectFile::default()
+                },
+                ProjectFile {
+                    filename: "holygrail-1.1-py3-none-any.whl".to_string(),
+                    yanked: true,
+                    ..ProjectFile::default()
+                },
+                ProjectFile {
+                    filename: "holygrail-1.2.tar.gz".to_string(),
+                    yanked: true,
+                    ..ProjectFile::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_version_from_filename() {
+        let cases = [
+            (
+                "requests-2.32.3-py3-none-any.whl",
+                "requests",
+                Some("2.32.3"),
+            ),
+            ("requests-2.32.3.tar.gz", "requests", Some("2.32.3")),
+            (
+                "python_dateutil-2.9.0.post0-py2.py3-none-any.whl",
+                "python-dateutil",
+                Some("2.9.0.post0"),
+            ),
+            (
+                "python-dateutil-2.8.2.tar.gz",
+                "python-dateutil",
+                Some("2.8.2"),
+            ),
+            ("Django-1.11.zip", "django", Some("1.11")),
+            ("zope.interface-6.0.tar.gz", "zope-interface", Some("6.0")),
+            ("setuptools-0.6c11-py2.7.egg", "setuptools", Some("0.6c11")),
+            ("numpy-1.0.win32-py2.5.exe", "numpy", None),
+            ("other-1.0.tar.gz", "requests", None),
+        ];
+        for (filename, project, expected) in cases {
+            assert_eq!(
+                version_from_filename(filename, project),
+                expected,
+                "{filename}"
+            );
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use check_updates_core::{DependencyCheck, UpdateSeverity};
+use check_updates_core::{DependencyCheck, Ecosystem, UpdateSeverity};
+use crate::ecosystem::PythonEcosystem;
 use crate::detector::PackageManager;
 use anyhow::{Context, Result};
 use std::collections::{HashSet, HashMap};
@@ -50,8 +51,8 @@ impl FileUpdater {
             };
 
             if let Some(spec) = version_spec
-                && spec.is_rewritable() {
-                let new_version = spec.to_string();
+                && spec.is_rewritable()
+                && let Some(new_version) = PythonEcosystem.format_spec(spec) {
                 file_updates
                     .entry(check.dependency.source_file.clone())
                     .or_default()
@@ -478,6 +479,8 @@ mod tests {
         let check1 = DependencyCheck {
             dependency: Dependency {
                 name: "requests".to_string(),
+                registry: None,
+                package: None,
                 version_spec: VersionSpec::Pinned(Version::new(2, 28, 0)),
                 source_file: temp_path.clone(),
                 line_number: 1,
@@ -490,10 +493,18 @@ mod tests {
             target_spec: Some(VersionSpec::Pinned(Version::new(2, 32, 3))),
             severity: Some(UpdateSeverity::Minor),
             force_spec: Some(VersionSpec::Pinned(Version::new(2, 32, 3))),
+            held: None,
+            too_new: None,
+            blocked: None,
+            installed_released: None,
+            latest_released: None,
+            cached_at: None,
         };
         let check2 = DependencyCheck {
             dependency: Dependency {
                 name: "flask".to_string(),
+                registry: None,
+                package: None,
                 version_spec: VersionSpec::Pinned(Version::new(2, 0, 3)),
                 source_file: temp_path.clone(),
                 line_number: 3,
@@ -506,6 +517,12 @@ mod tests {
             target_spec: Some(VersionSpec::Pinned(Version::new(2, 3, 3))),
             severity: Some(UpdateSeverity::Minor),
             force_spec: Some(VersionSpec::Pinned(Version::new(2, 3, 3))),
+            held: None,
+            too_new: None,
+            blocked: None,
+            installed_released: None,
+            latest_released: None,
+            cached_at: None,
         };
 
         // Create updates with version strings
@@ -545,6 +562,8 @@ mod tests {
             DependencyCheck {
                 dependency: Dependency {
                     name: "serde".to_string(),
+                    registry: None,
+                    package: None,
                     version_spec: VersionSpec::Pinned(Version::new(1, 0, 0)),
                     source_file: temp_path.clone(),
                     line_number: 1,
@@ -557,10 +576,18 @@ mod tests {
                 target_spec: Some(VersionSpec::Pinned(Version::new(1, 0, 200))),
                 severity: Some(UpdateSeverity::Patch),
                 force_spec: Some(VersionSpec::Pinned(Version::new(1, 0, 200))),
+                held: None,
+                too_new: None,
+                blocked: None,
+                installed_released: None,
+                latest_released: None,
+                cached_at: None,
             },
             DependencyCheck {
                 dependency: Dependency {
                     name: "tokio".to_string(),
+                    registry: None,
+                    package: None,
                     version_spec: VersionSpec::Pinned(Version::new(1, 0, 0)),
                     source_file: temp_path.clone(),
                     line_number: 2,
@@ -573,6 +600,12 @@ mod tests {
                 target_spec: Some(VersionSpec::Pinned(Version::new(1, 5, 0))),
                 severity: Some(UpdateSeverity::Minor),
                 force_spec: Some(VersionSpec::Pinned(Version::new(1, 5, 0))),
+                held: None,
+                too_new: None,
+                blocked: None,
+                installed_released: None,
+                latest_released: None,
+                cached_at: None,
             },
         ];
 
@@ -580,8 +613,8 @@ mod tests {
         updater.apply_updates(&checks, false, false)?; // patch only
 
         let content = fs::read_to_string(&temp_path)?;
-        assert!(content.contains("==1.0.200"), "serde should be updated: {}", content);
-        assert!(!content.contains("==1.5.0"), "tokio should NOT be updated: {}", content);
+        assert!(content.contains("==1.0.200"), "serde should be updated: {content}");
+        assert!(!content.contains("==1.5.0"), "tokio should NOT be updated: {content}");
 
         Ok(())
     }
@@ -602,6 +635,8 @@ mod tests {
             DependencyCheck {
                 dependency: Dependency {
                     name: "serde".to_string(),
+                    registry: None,
+                    package: None,
                     version_spec: VersionSpec::Pinned(Version::new(1, 0, 0)),
                     source_file: temp_path.clone(),
                     line_number: 1,
@@ -614,10 +649,18 @@ mod tests {
                 target_spec: Some(VersionSpec::Pinned(Version::new(1, 0, 200))),
                 severity: Some(UpdateSeverity::Patch),
                 force_spec: Some(VersionSpec::Pinned(Version::new(1, 0, 200))),
+                held: None,
+                too_new: None,
+                blocked: None,
+                installed_released: None,
+                latest_released: None,
+                cached_at: None,
             },
             DependencyCheck {
                 dependency: Dependency {
                     name: "tokio".to_string(),
+                    registry: None,
+                    package: None,
                     version_spec: VersionSpec::Pinned(Version::new(1, 0, 0)),
                     source_file: temp_path.clone(),
                     line_number: 2,
@@ -630,6 +673,12 @@ mod tests {
                 target_spec: Some(VersionSpec::Pinned(Version::new(1, 5, 0))),
                 severity: Some(UpdateSeverity::Minor),
                 force_spec: Some(VersionSpec::Pinned(Version::new(1, 5, 0))),
+                held: None,
+                too_new: None,
+                blocked: None,
+                installed_released: None,
+                latest_released: None,
+                cached_at: None,
             },
         ];
 
@@ -637,8 +686,8 @@ mod tests {
         updater.apply_updates(&checks, true, false)?; // patch + minor
 
         let content = fs::read_to_string(&temp_path)?;
-        assert!(content.contains("==1.0.200"), "serde should be updated: {}", content);
-        assert!(content.contains("==1.5.0"), "tokio should be updated: {}", content);
+        assert!(content.contains("==1.0.200"), "serde should be updated: {content}");
+        assert!(content.contains("==1.5.0"), "tokio should be updated: {content}");
 
         Ok(())
     }
@@ -1,5 +1,6 @@
 use crate::global::UpgradeCommand;
-use check_updates_core::Version;
+use check_updates_core::report::GlobalRecord;
+use check_updates_core::{UpdateSeverity, Version};
 use anyhow::Result;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
@@ -40,8 +41,26 @@ impl UvPythonCheck {
     /// Get update severity for coloring (patch or minor)
     pub fn is_patch_update(&self) -> bool {
         self.has_update
-            && self.latest_version.major == self.installed_version.major
-            && self.latest_version.minor == self.installed_version.minor
+            && self.latest_version.major() == self.installed_version.major()
+            && self.latest_version.minor() == self.installed_version.minor()
+    }
+
+    /// Convert to a machine-readable record (source `uv-python`)
+    pub fn to_record(&self) -> GlobalRecord {
+        let severity = if !self.has_update {
+            None
+        } else if self.is_patch_update() {
+            Some(UpdateSeverity::Patch)
+        } else {
+            Some(UpdateSeverity::Minor)
+        };
+        GlobalRecord::new(
+            &format!("python{}", self.series),
+            "uv-python",
+            &self.installed_version.to_string(),
+            Some(self.latest_version.to_string()),
+        )
+        .with_update(self.has_update, severity)
     }
 }
 
@@ -123,7 +142,7 @@ impl UvPythonDiscovery {
     fn latest_versions_from_uv_list(&self, all_versions: &[UvPythonInfo]) -> HashMap<String, Version> {
         let mut latest: HashMap<String, Version> = HashMap::new();
         for info in all_versions {
-            let series = format!("{}.{}", info.version.major, info.version.minor);
+            let series = format!("{}.{}", info.version.major(), info.version.minor());
             let entry = latest.entry(series).or_insert_with(|| info.version.clone());
             if info.version > *entry {
                 *entry = info.version.clone();
@@ -163,7 +182,7 @@ impl UvPythonDiscovery {
         let mut seen_series = HashSet::new();
 
         for python in installed {
-            let series = format!("{}.{}", python.version.major, python.version.minor);
+            let series = format!("{}.{}", python.version.major(), python.version.minor());
 
             // Only check each series once (if multiple same series installed)
             if seen_series.contains(&series) {
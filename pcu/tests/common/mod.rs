@@ -1,7 +1,17 @@
+use assert_cmd::Command;
 use std::fs;
 use std::path::{Path, PathBuf};
 use tempfile::TempDir;
 
+/// `pcu` answering from the fixture registry in `tests/fixtures/registry`
+/// instead of PyPI, so tests run without network access
+pub fn pcu() -> Command {
+    let mut cmd = Command::cargo_bin("pcu").expect("pcu binary");
+    cmd.arg("--registry-fixtures")
+        .arg(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/registry"));
+    cmd
+}
+
 /// Helper to create a temporary project directory
 pub struct TempProject {
     pub dir: TempDir,
@@ -166,6 +176,7 @@ dependencies:
 }
 
 /// Create a sample uv.lock file (simplified)
+#[allow(dead_code)]
 pub fn sample_uv_lock() -> &'static str {
     r#"version = 1
 
@@ -53,13 +53,12 @@ fn test_version_short_flag() {
 fn test_detect_requirements_txt() {
     let project = common::create_temp_project_with_requirements();
 
-    let mut cmd = Command::cargo_bin("pcu").unwrap();
+    let mut cmd = common::pcu();
     cmd.arg(project.path())
         .assert()
-        .success();
-
-    // Just verify it runs without error for now
-    // Later we'll add mocking to verify output
+        .success()
+        .stdout(predicate::str::contains("requests"))
+        .stdout(predicate::str::contains("2.32.3"));
 }
 
 /// Test running on a project with PEP 621 pyproject.toml
@@ -67,7 +66,7 @@ fn test_detect_requirements_txt() {
 fn test_detect_pep621_pyproject() {
     let project = common::create_temp_project_with_pep621();
 
-    let mut cmd = Command::cargo_bin("pcu").unwrap();
+    let mut cmd = common::pcu();
     cmd.arg(project.path())
         .assert()
         .success();
@@ -78,7 +77,7 @@ fn test_detect_pep621_pyproject() {
 fn test_detect_poetry_pyproject() {
     let project = common::create_temp_project_with_poetry();
 
-    let mut cmd = Command::cargo_bin("pcu").unwrap();
+    let mut cmd = common::pcu();
     cmd.arg(project.path())
         .assert()
         .success();
@@ -89,7 +88,7 @@ fn test_detect_poetry_pyproject() {
 fn test_detect_pdm_pyproject() {
     let project = common::create_temp_project_with_pdm();
 
-    let mut cmd = Command::cargo_bin("pcu").unwrap();
+    let mut cmd = common::pcu();
     cmd.arg(project.path())
         .assert()
         .success();
@@ -100,7 +99,7 @@ fn test_detect_pdm_pyproject() {
 fn test_detect_conda_environment() {
     let project = common::create_temp_project_with_conda();
 
-    let mut cmd = Command::cargo_bin("pcu").unwrap();
+    let mut cmd = common::pcu();
     cmd.arg(project.path())
         .assert()
         .success();
@@ -111,7 +110,7 @@ fn test_detect_conda_environment() {
 fn test_detect_multiple_files() {
     let project = common::create_temp_project_with_multiple_files();
 
-    let mut cmd = Command::cargo_bin("pcu").unwrap();
+    let mut cmd = common::pcu();
     cmd.arg(project.path())
         .assert()
         .success();
@@ -122,7 +121,7 @@ fn test_detect_multiple_files() {
 fn test_empty_project() {
     let project = common::TempProject::new();
 
-    let mut cmd = Command::cargo_bin("pcu").unwrap();
+    let mut cmd = common::pcu();
     // Should still succeed but indicate no files found
     cmd.arg(project.path()).assert().success();
 }
@@ -132,7 +131,7 @@ fn test_empty_project() {
 fn test_update_flag() {
     let project = common::create_temp_project_with_requirements();
 
-    let mut cmd = Command::cargo_bin("pcu").unwrap();
+    let mut cmd = common::pcu();
     cmd.arg(project.path())
         .arg("--update")
         .assert()
@@ -144,7 +143,7 @@ fn test_update_flag() {
 fn test_minor_flag() {
     let project = common::create_temp_project_with_requirements();
 
-    let mut cmd = Command::cargo_bin("pcu").unwrap();
+    let mut cmd = common::pcu();
     cmd.arg(project.path())
         .arg("--minor")
         .assert()
@@ -156,7 +155,7 @@ fn test_minor_flag() {
 fn test_force_flag() {
     let project = common::create_temp_project_with_requirements();
 
-    let mut cmd = Command::cargo_bin("pcu").unwrap();
+    let mut cmd = common::pcu();
     cmd.arg(project.path())
         .arg("--force")
         .assert()
@@ -168,19 +167,37 @@ fn test_force_flag() {
 fn test_pre_release_flag() {
     let project = common::create_temp_project_with_requirements();
 
-    let mut cmd = Command::cargo_bin("pcu").unwrap();
+    let mut cmd = common::pcu();
     cmd.arg(project.path())
         .arg("--pre-release")
         .assert()
         .success();
 }
 
+/// Test that --update --minor rewrites requirements to the fixture versions
+#[test]
+fn test_update_minor_rewrites_file() {
+    let project = common::create_temp_project_with_requirements();
+
+    let mut cmd = common::pcu();
+    cmd.arg(project.path())
+        .arg("--update")
+        .arg("--minor")
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(project.file_path("requirements.txt")).unwrap();
+    assert!(content.contains("requests>=2.32.3,<3.0.0"));
+    assert!(content.contains("click>=8.1.7,<9.0.0"));
+    assert!(content.contains("numpy==1.24.0"));
+}
+
 /// Test running with combined flags
 #[test]
 fn test_combined_flags() {
     let project = common::create_temp_project_with_requirements();
 
-    let mut cmd = Command::cargo_bin("pcu").unwrap();
+    let mut cmd = common::pcu();
     cmd.arg(project.path())
         .arg("--update")
         .arg("--minor")
@@ -197,7 +214,7 @@ fn test_no_modification_without_update() {
 
     let original_content = fs::read_to_string(&req_path).unwrap();
 
-    let mut cmd = Command::cargo_bin("pcu").unwrap();
+    let mut cmd = common::pcu();
     cmd.arg(project.path()).assert().success();
 
     // File should not be modified
@@ -208,7 +225,7 @@ fn test_no_modification_without_update() {
 /// Test running on non-existent directory
 #[test]
 fn test_nonexistent_directory() {
-    let mut cmd = Command::cargo_bin("pcu").unwrap();
+    let mut cmd = common::pcu();
     cmd.arg("/nonexistent/path/to/project")
         .assert()
         .failure();
@@ -219,8 +236,227 @@ fn test_nonexistent_directory() {
 fn test_default_to_current_directory() {
     let project = common::create_temp_project_with_requirements();
 
-    let mut cmd = Command::cargo_bin("pcu").unwrap();
+    let mut cmd = common::pcu();
     cmd.current_dir(project.path())
         .assert()
         .success();
 }
+
+/// Test that `registry` in the config points pcu at a mirror, and that
+/// --registry overrides it
+#[tokio::test(flavor = "multi_thread")]
+async fn test_registry_from_config_and_flag() {
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(path("/mirror/six/"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"{"name": "six", "files": [{"filename": "six-1.16.0.tar.gz"}, {"filename": "six-1.17.0-py2.py3-none-any.whl"}]}"#,
+        ))
+        .mount(&server)
+        .await;
+
+    let project = common::TempProject::new();
+    project.create_file("requirements.txt", "six==1.16.0\n");
+    project.create_file(
+        ".check-updates.toml",
+        &format!("[defaults]\nregistry = \"{}/mirror/\"\n", server.uri()),
+    );
+    let cache = tempfile::TempDir::new().unwrap();
+
+    Command::cargo_bin("pcu")
+        .unwrap()
+        .env("CHECK_UPDATES_CACHE_DIR", cache.path())
+        .env_remove("PCU_REGISTRY")
+        .arg(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1.17.0"));
+
+    Command::cargo_bin("pcu")
+        .unwrap()
+        .env("CHECK_UPDATES_CACHE_DIR", cache.path())
+        .args(["--no-cache", "--registry", &format!("{}/elsewhere", server.uri())])
+        .arg(project.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not found"));
+}
+
+/// Test that indexes declared in pyproject.toml are searched before PyPI,
+/// with credentials from the environment, and that pinned packages only use
+/// their index
+#[tokio::test(flavor = "multi_thread")]
+async fn test_uv_indexes_with_pin_and_credentials() {
+    use wiremock::matchers::{header, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let private = MockServer::start().await;
+    let pypi = MockServer::start().await;
+    // "Y2k6czNjcmV0" is base64 for "ci:s3cret"
+    Mock::given(path("/simple/acme-widgets/"))
+        .and(header("authorization", "Basic Y2k6czNjcmV0"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"<a href="acme_widgets-1.0.0.tar.gz">acme_widgets-1.0.0.tar.gz</a>
+               <a href="acme_widgets-1.4.0.tar.gz">acme_widgets-1.4.0.tar.gz</a>"#,
+        ))
+        .mount(&private)
+        .await;
+    Mock::given(path("/simple/six/"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"{"name": "six", "files": [{"filename": "six-1.16.0.tar.gz"}, {"filename": "six-1.17.0.tar.gz"}]}"#,
+        ))
+        .mount(&pypi)
+        .await;
+
+    let project = common::TempProject::new();
+    project.create_file(
+        "pyproject.toml",
+        &format!(
+            r#"[project]
+name = "app"
+dependencies = ["acme-widgets==1.0.0", "six==1.16.0"]
+
+[[tool.uv.index]]
+name = "acme"
+url = "{}/simple"
+explicit = true
+
+[tool.uv.sources]
+acme-widgets = {{ index = "acme" }}
+"#,
+            private.uri()
+        ),
+    );
+    let cache = tempfile::TempDir::new().unwrap();
+
+    Command::cargo_bin("pcu")
+        .unwrap()
+        .env("CHECK_UPDATES_CACHE_DIR", cache.path())
+        .env("PIP_CONFIG_FILE", project.file_path("no-pip.conf"))
+        .env_remove("PIP_INDEX_URL")
+        .env_remove("PIP_EXTRA_INDEX_URL")
+        .env("UV_INDEX_ACME_USERNAME", "ci")
+        .env("UV_INDEX_ACME_PASSWORD", "s3cret")
+        .args(["--registry", &format!("{}/simple", pypi.uri())])
+        .arg(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1.4.0"))
+        .stdout(predicate::str::contains("1.17.0"));
+}
+
+/// Test that releases dropping the project's oldest supported Python are
+/// held back, and that --ignore-requires-python lets them through
+#[tokio::test(flavor = "multi_thread")]
+async fn test_requires_python_blocks_incompatible_releases() {
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(path("/simple/six/"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"{"name": "six", "files": [
+                {"filename": "six-1.16.0.tar.gz"},
+                {"filename": "six-1.16.5.tar.gz", "requires-python": ">=3.7"},
+                {"filename": "six-1.17.0.tar.gz", "requires-python": ">=3.10"}
+            ]}"#,
+        ))
+        .mount(&server)
+        .await;
+
+    let project = common::TempProject::new();
+    project.create_file(
+        "pyproject.toml",
+        r#"[project]
+name = "app"
+requires-python = ">=3.8"
+dependencies = ["six==1.16.0"]
+"#,
+    );
+    let cache = tempfile::TempDir::new().unwrap();
+    let pcu = || {
+        let mut cmd = Command::cargo_bin("pcu").unwrap();
+        cmd.env("CHECK_UPDATES_CACHE_DIR", cache.path())
+            .env("PIP_CONFIG_FILE", project.file_path("no-pip.conf"))
+            .env_remove("PIP_INDEX_URL")
+            .env_remove("PIP_EXTRA_INDEX_URL")
+            .args(["--registry", &format!("{}/simple", server.uri())]);
+        cmd
+    };
+
+    pcu()
+        .arg(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1.16.5"))
+        .stdout(predicate::str::contains("1.17.0 requires Python >=3.10"));
+
+    pcu()
+        .arg("--ignore-requires-python")
+        .arg(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1.17.0"))
+        .stdout(predicate::str::contains("requires Python").not());
+}
+
+/// Test that a check-updates comment that doesn't parse is reported with its
+/// location instead of being dropped silently
+#[test]
+fn test_invalid_annotation_warns() {
+    let project = common::TempProject::new();
+    project.create_file(
+        "requirements.txt",
+        "requests==2.28.0  # check-updates: hodl <3\n",
+    );
+
+    let mut cmd = common::pcu();
+    cmd.arg(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2.32.3"))
+        .stderr(predicate::str::contains(
+            "requirements.txt:1: ignoring check-updates annotation: unknown annotation 'hodl <3'",
+        ));
+}
+
+/// Test that a lookup that couldn't be made exits 3 even without --fail-on,
+/// and outranks an outdated package with it
+#[tokio::test(flavor = "multi_thread")]
+async fn test_failed_lookups_exit_3_without_fail_on() {
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(path("/simple/six/"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"{"name": "six", "files": [{"filename": "six-1.16.0.tar.gz"}, {"filename": "six-1.17.0.tar.gz"}]}"#,
+        ))
+        .mount(&server)
+        .await;
+
+    let project = common::TempProject::new();
+    project.create_file("requirements.txt", "six==1.16.0\n");
+    let cache = tempfile::TempDir::new().unwrap();
+    let pcu = || {
+        let mut cmd = Command::cargo_bin("pcu").unwrap();
+        cmd.env("CHECK_UPDATES_CACHE_DIR", cache.path())
+            .env("PIP_CONFIG_FILE", project.file_path("no-pip.conf"))
+            .env_remove("PIP_INDEX_URL")
+            .args(["--registry", &format!("{}/simple", server.uri())])
+            .arg(project.path());
+        cmd
+    };
+    pcu().assert().success();
+
+    // idna was never cached, so --offline can't look it up
+    project.create_file("requirements.txt", "six==1.16.0\nidna==3.0\n");
+    pcu().arg("--offline").assert().code(3);
+    pcu()
+        .args(["--offline", "--fail-on", "patch"])
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("1.17.0"));
+}